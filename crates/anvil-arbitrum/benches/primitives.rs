@@ -0,0 +1,49 @@
+//! Benchmarks for the decode-heavy `U256` conversions
+
+use anvil_arbitrum::precompiles::U256;
+#[cfg(feature = "alloy")]
+use anvil_arbitrum::primitives::U256Ext;
+use anvil_arbitrum::tx7e::{Tx7eParser, Tx7eTransaction};
+use criterion::{criterion_group, criterion_main, Criterion};
+use std::hint::black_box;
+
+fn bench_from_big_endian(c: &mut Criterion) {
+    let small = 20_000_000_000u64.to_be_bytes();
+    let full = [0xabu8; 32];
+
+    c.bench_function("U256::from_big_endian 8 bytes", |b| {
+        b.iter(|| U256::from_big_endian(black_box(&small)))
+    });
+    c.bench_function("U256::from_big_endian 32 bytes", |b| {
+        b.iter(|| U256::from_big_endian(black_box(&full)))
+    });
+    c.bench_function("U256::to_big_endian", |b| {
+        let value = U256::from_u64(20_000_000_000);
+        b.iter(|| black_box(value).to_big_endian())
+    });
+}
+
+fn bench_parse_tx7e(c: &mut Criterion) {
+    let tx = Tx7eTransaction::new(
+        42161,
+        "0x1234567890123456789012345678901234567890".parse().unwrap(),
+        U256::from_u64(1_000_000_000_000_000_000),
+        vec![0x60, 0x2b, 0x57, 0xfd],
+        100000,
+        12345,
+        1640995200,
+        U256::from_u64(20_000_000_000),
+        U256::from_u64(25_000_000_000),
+        50000,
+        U256::from_u64(1_000_000_000_000_000),
+        "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd".parse().unwrap(),
+        [1u8; 32],
+    );
+    let raw = tx.rlp_encode_typed();
+    let parser = Tx7eParser::new();
+
+    c.bench_function("Tx7eParser::parse", |b| b.iter(|| parser.parse(black_box(&raw)).unwrap()));
+}
+
+criterion_group!(benches, bench_from_big_endian, bench_parse_tx7e);
+criterion_main!(benches);
@@ -0,0 +1,17 @@
+//! Feed arbitrary bytes to `Tx7eParser::parse`
+//!
+//! The parser must never panic, and every deposit it accepts must re-encode to
+//! a raw transaction that parses back to the same deposit.
+
+#![no_main]
+
+use anvil_arbitrum::tx7e::Tx7eParser;
+use libfuzzer_sys::fuzz_target;
+
+fuzz_target!(|data: &[u8]| {
+    let parser = Tx7eParser::new();
+    if let Ok(tx) = parser.parse(data) {
+        let reparsed = parser.parse(&tx.rlp_encode_typed()).expect("re-encoded deposit parses");
+        assert_eq!(reparsed, tx);
+    }
+});
@@ -0,0 +1,181 @@
+//! Anvil's well-known dev accounts, derived from its default mnemonic
+
+use crate::precompiles::{Address, U256};
+#[cfg(feature = "alloy")]
+use crate::primitives::U256Ext;
+use hmac::{Hmac, Mac};
+use k256::elliptic_curve::ff::PrimeField;
+use k256::elliptic_curve::sec1::ToEncodedPoint;
+use k256::{FieldBytes, Scalar, SecretKey};
+use sha2::Sha512;
+use sha3::{Digest, Keccak256};
+
+type HmacSha512 = Hmac<Sha512>;
+
+/// Mnemonic Anvil derives its dev accounts from
+pub const DEFAULT_MNEMONIC: &str = "test test test test test test test test test test test junk";
+
+/// BIP-44 path of the accounts, ending in the account index
+pub const DERIVATION_PATH_PREFIX: &str = "m/44'/60'/0'/0/";
+
+/// BIP-39 seed stretching rounds
+const PBKDF2_ROUNDS: u32 = 2048;
+
+/// Flag marking a hardened BIP-32 child index
+const HARDENED: u32 = 0x8000_0000;
+
+/// Wei per ether
+const WEI_PER_ETHER: u64 = 1_000_000_000_000_000_000;
+
+/// Errors deriving dev accounts
+#[derive(Debug, thiserror::Error, PartialEq, Eq)]
+pub enum AccountError {
+    #[error("Mnemonic does not derive a valid account key")]
+    InvalidSeed,
+    #[error("Derived key for account {index} is not a valid secp256k1 key")]
+    InvalidDerivedKey { index: u32 },
+}
+
+/// A dev account with its private key and starting balance
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct DevAccount {
+    pub address: Address,
+    pub private_key: [u8; 32],
+    /// Starting balance in wei
+    pub balance: U256,
+}
+
+impl DevAccount {
+    /// Private key as `0x`-prefixed hex, as Anvil prints it
+    pub fn private_key_hex(&self) -> String {
+        format!("0x{}", hex::encode(self.private_key))
+    }
+}
+
+/// The first `count` accounts of `DEFAULT_MNEMONIC`, each funded with `balance_ether` ETH
+pub fn dev_accounts(count: u32, balance_ether: u64) -> Result<Vec<DevAccount>, AccountError> {
+    accounts_from_mnemonic(DEFAULT_MNEMONIC, count, balance_ether)
+}
+
+/// The first `count` accounts of `mnemonic` at `m/44'/60'/0'/0/{index}`
+///
+/// The mnemonic is not checked against the BIP-39 word list, matching how the
+/// seed itself is computed.
+pub fn accounts_from_mnemonic(mnemonic: &str, count: u32, balance_ether: u64) -> Result<Vec<DevAccount>, AccountError> {
+    let balance = U256::from_u64(balance_ether).saturating_mul(U256::from_u64(WEI_PER_ETHER));
+    let account_key = derive_account_key(&mnemonic_to_seed(mnemonic)).ok_or(AccountError::InvalidSeed)?;
+    (0..count)
+        .map(|index| {
+            let key = account_key
+                .child(index)
+                .ok_or(AccountError::InvalidDerivedKey { index })?;
+            Ok(DevAccount {
+                address: key.address(),
+                private_key: key.secret.to_bytes().into(),
+                balance,
+            })
+        })
+        .collect()
+}
+
+/// BIP-39 seed: PBKDF2-HMAC-SHA512 of the mnemonic, salted with "mnemonic"
+///
+/// A single 64-byte output block, so PBKDF2 reduces to one HMAC chain.
+fn mnemonic_to_seed(mnemonic: &str) -> [u8; 64] {
+    let prf = HmacSha512::new_from_slice(mnemonic.as_bytes()).expect("HMAC accepts any key length");
+    let mut mac = prf.clone();
+    mac.update(b"mnemonic");
+    mac.update(&1u32.to_be_bytes());
+    let mut block: [u8; 64] = mac.finalize().into_bytes().into();
+    let mut seed = block;
+    for _ in 1..PBKDF2_ROUNDS {
+        let mut mac = prf.clone();
+        mac.update(&block);
+        block = mac.finalize().into_bytes().into();
+        seed.iter_mut().zip(block.iter()).for_each(|(s, b)| *s ^= b);
+    }
+    seed
+}
+
+/// Extended private key (BIP-32)
+struct ExtendedKey {
+    secret: SecretKey,
+    chain_code: [u8; 32],
+}
+
+impl ExtendedKey {
+    /// Master key of a BIP-32 seed
+    fn master(seed: &[u8]) -> Option<Self> {
+        let mut mac = HmacSha512::new_from_slice(b"Bitcoin seed").expect("HMAC accepts any key length");
+        mac.update(seed);
+        Self::from_hmac(mac, None)
+    }
+
+    /// Child key at `index` (hardened if `index` has the `HARDENED` bit set)
+    fn child(&self, index: u32) -> Option<Self> {
+        let mut mac = HmacSha512::new_from_slice(&self.chain_code).expect("HMAC accepts any key length");
+        if index & HARDENED != 0 {
+            mac.update(&[0]);
+            mac.update(&self.secret.to_bytes());
+        } else {
+            mac.update(self.secret.public_key().to_encoded_point(true).as_bytes());
+        }
+        mac.update(&index.to_be_bytes());
+        Self::from_hmac(mac, Some(&self.secret))
+    }
+
+    /// Split an HMAC output into key and chain code, adding the key to `parent`
+    fn from_hmac(mac: HmacSha512, parent: Option<&SecretKey>) -> Option<Self> {
+        let output = mac.finalize().into_bytes();
+        let (key, chain_code) = output.split_at(32);
+        let tweak = Option::<Scalar>::from(Scalar::from_repr(*FieldBytes::from_slice(key)))?;
+        let scalar = match parent {
+            Some(parent) => tweak + parent.to_nonzero_scalar().as_ref(),
+            None => tweak,
+        };
+        Some(Self {
+            secret: SecretKey::from_bytes(&scalar.to_bytes()).ok()?,
+            chain_code: chain_code.try_into().unwrap(),
+        })
+    }
+
+    /// Ethereum address: the last 20 bytes of the Keccak-256 of the public key
+    fn address(&self) -> Address {
+        let point = self.secret.public_key().to_encoded_point(false);
+        let hash = Keccak256::digest(&point.as_bytes()[1..]);
+        Address::new(hash[12..].try_into().unwrap())
+    }
+}
+
+/// Key at `m/44'/60'/0'/0`, the parent of every dev account
+fn derive_account_key(seed: &[u8]) -> Option<ExtendedKey> {
+    [44 | HARDENED, 60 | HARDENED, HARDENED, 0]
+        .into_iter()
+        .try_fold(ExtendedKey::master(seed)?, |key, index| key.child(index))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloy")]
+    use crate::primitives::AddressExt;
+
+    #[test]
+    fn test_first_account_matches_anvil() {
+        let accounts = dev_accounts(2, 10_000).unwrap();
+        assert_eq!(accounts.len(), 2);
+        assert_eq!(
+            accounts[0].address,
+            Address::from_hex("0xf39Fd6e51aad88F6F4ce6aB8827279cffFb92266").unwrap()
+        );
+        assert_eq!(
+            accounts[0].private_key_hex(),
+            "0xac0974bec39a17e36ba4a6b4d238ff944bacb478cbed5efcae784d7bf4f2ff80"
+        );
+        assert_eq!(
+            accounts[1].address,
+            Address::from_hex("0x70997970C51812dc3A010C7d01b50e0d17dc79C8").unwrap()
+        );
+        assert_eq!(accounts[0].balance, U256::from_u64(10_000).saturating_mul(U256::from_u64(WEI_PER_ETHER)));
+    }
+}
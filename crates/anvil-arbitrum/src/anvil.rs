@@ -0,0 +1,172 @@
+//! Locating and running the upstream Anvil binary
+
+use crate::arbitrum::ArbitrumConfig;
+use crate::rpc::shutdown_signal;
+use anyhow::{anyhow, Result};
+use std::path::{Path, PathBuf};
+use std::process::Stdio;
+use tempfile::NamedTempFile;
+use tokio::io::{AsyncBufReadExt, AsyncRead, BufReader};
+use tokio::process::{Child, Command};
+use tracing::{info, warn};
+
+/// Environment variable that points at a specific `anvil` binary
+pub const ANVIL_PATH_ENV: &str = "ANVIL_PATH";
+
+/// Locate the `anvil` binary via `ANVIL_PATH` or the `PATH` search path
+pub fn find_anvil_binary() -> Option<PathBuf> {
+    if let Some(path) = std::env::var_os(ANVIL_PATH_ENV) {
+        let path = PathBuf::from(path);
+        return path.is_file().then_some(path);
+    }
+
+    let search_path = std::env::var_os("PATH")?;
+    std::env::split_paths(&search_path)
+        .map(|dir| dir.join(anvil_file_name()))
+        .find(|candidate| candidate.is_file())
+}
+
+/// Write the genesis for `config` to a file in the temp directory
+///
+/// The file is passed to a spawned Anvil with `--init` and deleted when the
+/// returned handle is dropped, so keep it alive as long as Anvil runs.
+pub fn write_genesis_file(config: &ArbitrumConfig) -> Result<NamedTempFile> {
+    let file = tempfile::Builder::new()
+        .prefix("anvil-arbitrum-genesis-")
+        .suffix(".json")
+        .tempfile()
+        .map_err(|e| anyhow!("Failed to create genesis file: {}", e))?;
+    std::fs::write(file.path(), serde_json::to_string_pretty(&config.to_genesis_json())?)
+        .map_err(|e| anyhow!("Failed to write genesis file {}: {}", file.path().display(), e))?;
+    Ok(file)
+}
+
+fn anvil_file_name() -> &'static str {
+    if cfg!(windows) {
+        "anvil.exe"
+    } else {
+        "anvil"
+    }
+}
+
+/// A running Anvil subprocess whose output is forwarded to `tracing`
+pub struct AnvilProcess {
+    child: Child,
+}
+
+impl AnvilProcess {
+    /// Spawn `binary` with the given arguments
+    ///
+    /// The child is killed if the returned handle is dropped.
+    pub fn spawn(binary: &Path, args: &[String]) -> Result<Self> {
+        info!("Spawning {} {}", binary.display(), args.join(" "));
+
+        let mut child = Command::new(binary)
+            .args(args)
+            .stdin(Stdio::null())
+            .stdout(Stdio::piped())
+            .stderr(Stdio::piped())
+            .kill_on_drop(true)
+            .spawn()
+            .map_err(|e| anyhow!("Failed to spawn {}: {}", binary.display(), e))?;
+
+        if let Some(stdout) = child.stdout.take() {
+            tokio::spawn(forward_lines(stdout, false));
+        }
+        if let Some(stderr) = child.stderr.take() {
+            tokio::spawn(forward_lines(stderr, true));
+        }
+
+        Ok(Self { child })
+    }
+
+    /// Process id of the child, if it is still running
+    pub fn id(&self) -> Option<u32> {
+        self.child.id()
+    }
+
+    /// Wait for the child to exit or for Ctrl-C/SIGTERM, terminating the child on the latter
+    pub async fn run_until_shutdown(mut self) -> Result<()> {
+        tokio::select! {
+            status = self.child.wait() => {
+                let status = status?;
+                if status.success() {
+                    Ok(())
+                } else {
+                    Err(anyhow!("Anvil exited with {}", status))
+                }
+            }
+            _ = shutdown_signal() => {
+                info!("Stopping Anvil");
+                self.kill().await
+            }
+        }
+    }
+
+    /// Terminate the child and wait for it to exit
+    pub async fn kill(&mut self) -> Result<()> {
+        self.child.kill().await?;
+        Ok(())
+    }
+}
+
+async fn forward_lines<R: AsyncRead + Unpin>(stream: R, is_stderr: bool) {
+    let mut lines = BufReader::new(stream).lines();
+    while let Ok(Some(line)) = lines.next_line().await {
+        if is_stderr {
+            warn!(target: "anvil", "{}", line);
+        } else {
+            info!(target: "anvil", "{}", line);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::cli::AnvilArbitrumArgs;
+    use clap::Parser;
+    use std::time::Duration;
+
+    #[test]
+    fn test_genesis_file_removed_on_drop() {
+        let config = ArbitrumConfig::default();
+        let file = write_genesis_file(&config).unwrap();
+        let path = file.path().to_path_buf();
+        let written: serde_json::Value = serde_json::from_str(&std::fs::read_to_string(&path).unwrap()).unwrap();
+        assert_eq!(written, config.to_genesis_json());
+
+        drop(file);
+        assert!(!path.exists());
+    }
+
+    #[tokio::test]
+    async fn test_spawned_anvil_binds_port() {
+        let Some(binary) = find_anvil_binary() else {
+            eprintln!("anvil not found, skipping");
+            return;
+        };
+
+        let port = std::net::TcpListener::bind("127.0.0.1:0")
+            .unwrap()
+            .local_addr()
+            .unwrap()
+            .port();
+        let port_arg = port.to_string();
+        let args = AnvilArbitrumArgs::try_parse_from(["anvil-arbitrum", "--port", &port_arg]).unwrap();
+
+        let mut anvil = AnvilProcess::spawn(&binary, &args.get_anvil_args()).unwrap();
+
+        let mut bound = false;
+        for _ in 0..50 {
+            if tokio::net::TcpStream::connect(("127.0.0.1", port)).await.is_ok() {
+                bound = true;
+                break;
+            }
+            tokio::time::sleep(Duration::from_millis(100)).await;
+        }
+
+        anvil.kill().await.unwrap();
+        assert!(bound, "anvil did not bind port {}", port);
+    }
+}
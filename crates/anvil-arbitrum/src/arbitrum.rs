@@ -1,10 +1,133 @@
 //! Arbitrum configuration and initialization for Anvil
 
+use crate::compression::CompressionModel;
+use crate::precompiles::Address;
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt};
 use serde::{Deserialize, Serialize};
+use serde_json::json;
 use std::collections::HashMap;
 
+/// Default L2 block gas limit (matches the Anvil `--gas-limit` default)
+pub const DEFAULT_L2_BLOCK_GAS_LIMIT: u64 = 30_000_000;
+
+/// Default address of the mock L1 bridge endpoint
+pub const DEFAULT_MOCK_L1_BRIDGE: &str = "0x0000000000000000000000000000000000001001";
+
+/// Upper bound accepted for the L1 and L2 base fees (10,000 gwei)
+pub const MAX_BASE_FEE: u64 = 10_000_000_000_000;
+
+/// Default floor on the L2 gas price (0.1 gwei, Arbitrum One's minimum)
+pub const DEFAULT_MIN_L2_GAS_PRICE: u64 = 100_000_000;
+
+/// Default lifetime of a retryable ticket in seconds (7 days)
+pub const DEFAULT_RETRYABLE_LIFETIME_SECS: u64 = 7 * 24 * 60 * 60;
+
+/// Default storage gas reported by `ArbSys.getStorageGasAvailable` at startup
+pub const DEFAULT_STORAGE_GAS_AVAILABLE: u64 = 10_000_000;
+
+/// Default weight of the previous L1 base fee estimate (ArbOS's `L1BaseFeeEstimateInertia`)
+pub const DEFAULT_L1_BASE_FEE_INERTIA: u64 = 10;
+
+/// L1 fee scalar of 1.0x, in parts per million
+pub const DEFAULT_L1_FEE_SCALAR: u64 = 1_000_000;
+
+/// Upper bound accepted for the L1 calldata cost per byte
+pub const MAX_L1_CALLDATA_COST: u64 = 1_000;
+
+/// Code of the genesis stub accounts at precompile addresses (`INVALID`, as on Nitro)
+pub const PRECOMPILE_STUB_CODE: &str = "0xfe";
+
+/// Top-level keys of a configuration file
+pub const CONFIG_FIELDS: &[&str] = &[
+    "chain_id",
+    "arb_os_version",
+    "l1_base_fee",
+    "l1_base_fee_inertia",
+    "l2_block_gas_limit",
+    "gas_price_components",
+    "tx7e_enabled",
+    "mock_l1_bridge",
+    "retryable_lifetime_secs",
+    "storage_gas_available",
+    "allow_debug_precompiles",
+    "precompile_gas",
+    "precompiles",
+];
+
+/// Top-level keys a configuration file must set; the others have defaults
+pub const REQUIRED_CONFIG_FIELDS: &[&str] = &[
+    "chain_id",
+    "arb_os_version",
+    "l1_base_fee",
+    "gas_price_components",
+    "tx7e_enabled",
+    "mock_l1_bridge",
+    "precompiles",
+];
+
+/// Keys of the `gas_price_components` object
+pub const GAS_PRICE_COMPONENT_FIELDS: &[&str] = &[
+    "l2_base_fee",
+    "l1_calldata_cost",
+    "l1_storage_cost",
+    "congestion_fee",
+    "min_l2_gas_price",
+    "compression",
+    "l1_fee_scalar",
+];
+
+/// Keys of the `gas_price_components` object that must be set
+pub const REQUIRED_GAS_PRICE_COMPONENT_FIELDS: &[&str] =
+    &["l2_base_fee", "l1_calldata_cost", "l1_storage_cost", "congestion_fee"];
+
+/// Keys of each entry in the `precompiles` object, all of which must be set
+pub const PRECOMPILE_CONFIG_FIELDS: &[&str] = &["address", "name", "enabled", "config"];
+
+/// Values accepted for `gas_price_components.compression`
+pub const COMPRESSION_MODELS: &[&str] = &["none", "brotli"];
+
+/// An error loading, overriding or validating an `ArbitrumConfig`
+#[derive(Debug, thiserror::Error)]
+pub enum ConfigError {
+    #[error(transparent)]
+    Io(#[from] std::io::Error),
+    #[error(transparent)]
+    Json(#[from] serde_json::Error),
+    #[error("Invalid configuration: {}", errors.join("; "))]
+    Schema { errors: Vec<String> },
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlDe(#[from] toml::de::Error),
+    #[cfg(feature = "toml")]
+    #[error(transparent)]
+    TomlSer(#[from] toml::ser::Error),
+    #[error("TOML configuration requires the `toml` feature")]
+    TomlUnsupported,
+    #[error("Invalid value '{value}' for {name}: {reason}")]
+    InvalidOverride { name: String, value: String, reason: String },
+    #[error("{field} cannot be 0")]
+    Zero { field: &'static str },
+    #[error("{lhs} {lhs_value} {op} {rhs} {rhs_value} overflows u64")]
+    Overflow {
+        lhs: &'static str,
+        lhs_value: u64,
+        op: &'static str,
+        rhs: &'static str,
+        rhs_value: u64,
+    },
+    #[error("{field} {value} exceeds the maximum of {max} {unit}")]
+    ExceedsMax { field: &'static str, value: u64, max: u64, unit: &'static str },
+    #[error("Invalid precompile address key '{key}': {reason}")]
+    InvalidPrecompileKey { key: String, reason: String },
+    #[error("Precompile key '{key}' does not match its address '{address}'")]
+    PrecompileKeyMismatch { key: String, address: Address },
+    #[error("Unknown precompile '{name}' (known: {known})")]
+    UnknownPrecompile { name: String, known: String },
+}
+
 /// Configuration for Arbitrum mode in Anvil
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct ArbitrumConfig {
     /// Arbitrum chain ID
     pub chain_id: u64,
@@ -12,18 +135,36 @@ pub struct ArbitrumConfig {
     pub arb_os_version: u32,
     /// L1 base fee in wei
     pub l1_base_fee: u64,
+    /// Initial smoothing of the L1 base fee estimate: each observation moves it `1/inertia` of the way
+    #[serde(default = "default_l1_base_fee_inertia")]
+    pub l1_base_fee_inertia: u64,
+    /// L2 block gas limit
+    #[serde(default = "default_l2_block_gas_limit")]
+    pub l2_block_gas_limit: u64,
     /// Gas price components
     pub gas_price_components: GasPriceComponents,
     /// 0x7e transaction support enabled
     pub tx7e_enabled: bool,
     /// Mock L1 bridge address
-    pub mock_l1_bridge: String,
+    pub mock_l1_bridge: Address,
+    /// Seconds a retryable ticket stays redeemable after it is created
+    #[serde(default = "default_retryable_lifetime_secs")]
+    pub retryable_lifetime_secs: u64,
+    /// Storage gas available at startup, drained by deposits and L2-to-L1 messages
+    #[serde(default = "default_storage_gas_available")]
+    pub storage_gas_available: u64,
+    /// Whether the ArbDebug precompile's functions are callable
+    #[serde(default)]
+    pub allow_debug_precompiles: bool,
+    /// Gas charged per call, by case-insensitive precompile name; unlisted precompiles keep their built-in costs
+    #[serde(default)]
+    pub precompile_gas: HashMap<String, u64>,
     /// Precompile addresses and their handlers
     pub precompiles: HashMap<String, PrecompileConfig>,
 }
 
 /// Gas price components for Arbitrum
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct GasPriceComponents {
     /// L2 base fee in wei
     pub l2_base_fee: u64,
@@ -33,13 +174,25 @@ pub struct GasPriceComponents {
     pub l1_storage_cost: u64,
     /// Congestion fee in wei
     pub congestion_fee: u64,
+    /// Minimum L2 gas price in wei; the L2 base fee is never priced below it
+    #[serde(default = "default_min_l2_gas_price")]
+    pub min_l2_gas_price: u64,
+    /// Compression model applied to calldata before pricing it on L1
+    #[serde(default)]
+    pub compression: CompressionModel,
+    /// Multiplier on L1 fees in parts per million (1_000_000 is 1.0x)
+    #[serde(default = "default_l1_fee_scalar")]
+    pub l1_fee_scalar: u64,
 }
 
+/// Precompiles registered outside `precompiles`, whose gas can still be set in `precompile_gas`
+pub const UNCONFIGURED_PRECOMPILES: &[&str] = &["ArbOwner", "ArbOwnerPublic", "ArbDebug", "L1Bridge"];
+
 /// Configuration for individual precompiles
-#[derive(Debug, Clone, Serialize, Deserialize)]
+#[derive(Debug, Clone, PartialEq, Serialize, Deserialize)]
 pub struct PrecompileConfig {
     /// Precompile address
-    pub address: String,
+    pub address: Address,
     /// Precompile name
     pub name: String,
     /// Whether this precompile is enabled
@@ -48,20 +201,131 @@ pub struct PrecompileConfig {
     pub config: HashMap<String, serde_json::Value>,
 }
 
+/// Partial Arbitrum configuration used to override selected fields
+///
+/// Every field is optional; `ArbitrumConfig::merge` applies only those that are set.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default)]
+pub struct PartialArbitrumConfig {
+    pub chain_id: Option<u64>,
+    pub arb_os_version: Option<u32>,
+    pub l1_base_fee: Option<u64>,
+    pub l1_base_fee_inertia: Option<u64>,
+    pub l2_block_gas_limit: Option<u64>,
+    pub gas_price_components: Option<PartialGasPriceComponents>,
+    pub tx7e_enabled: Option<bool>,
+    pub mock_l1_bridge: Option<Address>,
+    pub retryable_lifetime_secs: Option<u64>,
+    pub storage_gas_available: Option<u64>,
+    pub allow_debug_precompiles: Option<bool>,
+    /// Replaces the whole gas cost map when set
+    pub precompile_gas: Option<HashMap<String, u64>>,
+    /// Replaces the whole precompile map when set
+    pub precompiles: Option<HashMap<String, PrecompileConfig>>,
+}
+
+/// Partial gas price components used by `PartialArbitrumConfig`
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+#[serde(default, deny_unknown_fields)]
+pub struct PartialGasPriceComponents {
+    pub l2_base_fee: Option<u64>,
+    pub l1_calldata_cost: Option<u64>,
+    pub l1_storage_cost: Option<u64>,
+    pub congestion_fee: Option<u64>,
+    pub min_l2_gas_price: Option<u64>,
+    pub compression: Option<CompressionModel>,
+    pub l1_fee_scalar: Option<u64>,
+}
+
 impl Default for ArbitrumConfig {
     fn default() -> Self {
         Self {
             chain_id: 42161, // Arbitrum One
             arb_os_version: 20,
             l1_base_fee: 20_000_000_000, // 20 gwei
+            l1_base_fee_inertia: DEFAULT_L1_BASE_FEE_INERTIA,
+            l2_block_gas_limit: DEFAULT_L2_BLOCK_GAS_LIMIT,
             gas_price_components: GasPriceComponents::default(),
             tx7e_enabled: true,
-            mock_l1_bridge: "0x0000000000000000000000000000000000000064".to_string(),
+            mock_l1_bridge: Address::from_hex(DEFAULT_MOCK_L1_BRIDGE).expect("valid default bridge address"),
+            retryable_lifetime_secs: DEFAULT_RETRYABLE_LIFETIME_SECS,
+            storage_gas_available: DEFAULT_STORAGE_GAS_AVAILABLE,
+            allow_debug_precompiles: false,
+            precompile_gas: HashMap::new(),
             precompiles: Self::default_precompiles(),
         }
     }
 }
 
+/// Address of the precompile whose last byte is `slot`
+fn precompile_address(slot: u8) -> Address {
+    let mut bytes = [0u8; 20];
+    bytes[19] = slot;
+    Address::new(bytes)
+}
+
+fn default_l2_block_gas_limit() -> u64 {
+    DEFAULT_L2_BLOCK_GAS_LIMIT
+}
+
+fn default_min_l2_gas_price() -> u64 {
+    DEFAULT_MIN_L2_GAS_PRICE
+}
+
+fn default_retryable_lifetime_secs() -> u64 {
+    DEFAULT_RETRYABLE_LIFETIME_SECS
+}
+
+fn default_l1_fee_scalar() -> u64 {
+    DEFAULT_L1_FEE_SCALAR
+}
+
+fn default_l1_base_fee_inertia() -> u64 {
+    DEFAULT_L1_BASE_FEE_INERTIA
+}
+
+fn default_storage_gas_available() -> u64 {
+    DEFAULT_STORAGE_GAS_AVAILABLE
+}
+
+/// Unknown and missing keys of `object`, prefixing each key with `path`
+fn check_keys(
+    object: &serde_json::Map<String, serde_json::Value>,
+    path: &str,
+    fields: &[&str],
+    required: &[&str],
+) -> Vec<String> {
+    let unknown = object.keys().filter(|key| !fields.contains(&key.as_str())).map(|key| {
+        match fields.iter().min_by_key(|field| edit_distance(key, field)) {
+            Some(field) if edit_distance(key, field) <= 2 => {
+                format!("Unknown field '{}{}' (did you mean '{}{}'?)", path, key, path, field)
+            }
+            _ => format!("Unknown field '{}{}'", path, key),
+        }
+    });
+    let missing = required
+        .iter()
+        .filter(|field| !object.contains_key(**field))
+        .map(|field| format!("Missing required field '{}{}'", path, field));
+    unknown.chain(missing).collect()
+}
+
+/// Levenshtein distance between two keys
+fn edit_distance(a: &str, b: &str) -> usize {
+    let b: Vec<char> = b.chars().collect();
+    let mut row: Vec<usize> = (0..=b.len()).collect();
+    for (i, a_char) in a.chars().enumerate() {
+        let mut previous = row[0];
+        row[0] = i + 1;
+        for (j, b_char) in b.iter().enumerate() {
+            let substitution = previous + usize::from(a_char != *b_char);
+            previous = row[j + 1];
+            row[j + 1] = substitution.min(previous + 1).min(row[j] + 1);
+        }
+    }
+    row[b.len()]
+}
+
 impl Default for GasPriceComponents {
     fn default() -> Self {
         Self {
@@ -69,7 +333,51 @@ impl Default for GasPriceComponents {
             l1_calldata_cost: 16,        // 16 gas per byte
             l1_storage_cost: 0,          // No storage gas in Nitro
             congestion_fee: 0,           // No congestion fee by default
+            min_l2_gas_price: DEFAULT_MIN_L2_GAS_PRICE,
+            compression: CompressionModel::None,
+            l1_fee_scalar: DEFAULT_L1_FEE_SCALAR,
+        }
+    }
+}
+
+impl GasPriceComponents {
+    /// Apply the fields that are set in `partial`, leaving the rest untouched
+    pub fn merge(&mut self, partial: PartialGasPriceComponents) {
+        if let Some(l2_base_fee) = partial.l2_base_fee {
+            self.l2_base_fee = l2_base_fee;
+        }
+        if let Some(l1_calldata_cost) = partial.l1_calldata_cost {
+            self.l1_calldata_cost = l1_calldata_cost;
+        }
+        if let Some(l1_storage_cost) = partial.l1_storage_cost {
+            self.l1_storage_cost = l1_storage_cost;
         }
+        if let Some(congestion_fee) = partial.congestion_fee {
+            self.congestion_fee = congestion_fee;
+        }
+        if let Some(min_l2_gas_price) = partial.min_l2_gas_price {
+            self.min_l2_gas_price = min_l2_gas_price;
+        }
+        if let Some(compression) = partial.compression {
+            self.compression = compression;
+        }
+        if let Some(l1_fee_scalar) = partial.l1_fee_scalar {
+            self.l1_fee_scalar = l1_fee_scalar;
+        }
+    }
+
+    /// Scale an L1 fee in wei by `l1_fee_scalar`, saturating at `u64::MAX`
+    ///
+    /// Takes the fee as `u128` so the product of L1 gas and base fee can be
+    /// passed in unreduced.
+    pub fn scale_l1_fee(&self, fee: u128) -> u64 {
+        let scaled = fee.saturating_mul(self.l1_fee_scalar as u128) / DEFAULT_L1_FEE_SCALAR as u128;
+        scaled.min(u64::MAX as u128) as u64
+    }
+
+    /// L2 base fee clamped to at least the minimum L2 gas price
+    pub fn effective_l2_base_fee(&self) -> u64 {
+        self.l2_base_fee.max(self.min_l2_gas_price)
     }
 }
 
@@ -84,6 +392,143 @@ impl ArbitrumConfig {
         }
     }
 
+    /// Arbitrum One preset (chain ID 42161)
+    pub fn arbitrum_one() -> Self {
+        Self::default()
+    }
+
+    /// Arbitrum Nova preset (chain ID 42170)
+    pub fn arbitrum_nova() -> Self {
+        Self {
+            chain_id: 42170,
+            arb_os_version: 20,
+            l1_base_fee: 20_000_000_000, // 20 gwei
+            gas_price_components: GasPriceComponents {
+                l2_base_fee: 10_000_000,      // 0.01 gwei
+                min_l2_gas_price: 10_000_000, // 0.01 gwei
+                ..GasPriceComponents::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Arbitrum Sepolia testnet preset (chain ID 421614)
+    pub fn arbitrum_sepolia() -> Self {
+        Self {
+            chain_id: 421614,
+            arb_os_version: 20,
+            l1_base_fee: 1_000_000_000, // 1 gwei
+            gas_price_components: GasPriceComponents {
+                l2_base_fee: 100_000_000, // 0.1 gwei
+                ..GasPriceComponents::default()
+            },
+            ..Default::default()
+        }
+    }
+
+    /// Look up the preset for a known Arbitrum chain ID
+    pub fn for_chain_id(chain_id: u64) -> Option<Self> {
+        match chain_id {
+            42161 => Some(Self::arbitrum_one()),
+            42170 => Some(Self::arbitrum_nova()),
+            421614 => Some(Self::arbitrum_sepolia()),
+            _ => None,
+        }
+    }
+
+    /// Override fields from `OX_*` environment variables
+    ///
+    /// Recognized variables: `OX_ARB_CHAIN_ID`, `OX_ARB_OS_VERSION`, `OX_L1_BASE_FEE`,
+    /// `OX_L2_BASE_FEE`, `OX_L1_CALLDATA_COST`, `OX_CONGESTION_FEE` and `OX_TX7E_ENABLED`.
+    /// Unset variables leave their field untouched; unparsable values are an error.
+    pub fn apply_env_overrides(&mut self) -> Result<(), ConfigError> {
+        self.apply_overrides_from(|name| std::env::var(name).ok())
+    }
+
+    /// Override fields from `OX_*` variables resolved through `lookup`
+    pub fn apply_overrides_from<F>(&mut self, lookup: F) -> Result<(), ConfigError>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        fn parse<T: std::str::FromStr>(name: &str, value: &str) -> Result<T, ConfigError>
+        where
+            T::Err: std::fmt::Display,
+        {
+            value.trim().parse().map_err(|e: T::Err| ConfigError::InvalidOverride {
+                name: name.to_string(),
+                value: value.to_string(),
+                reason: e.to_string(),
+            })
+        }
+
+        if let Some(value) = lookup("OX_ARB_CHAIN_ID") {
+            self.chain_id = parse("OX_ARB_CHAIN_ID", &value)?;
+        }
+        if let Some(value) = lookup("OX_ARB_OS_VERSION") {
+            self.arb_os_version = parse("OX_ARB_OS_VERSION", &value)?;
+        }
+        if let Some(value) = lookup("OX_L1_BASE_FEE") {
+            self.l1_base_fee = parse("OX_L1_BASE_FEE", &value)?;
+        }
+        if let Some(value) = lookup("OX_L2_BASE_FEE") {
+            self.gas_price_components.l2_base_fee = parse("OX_L2_BASE_FEE", &value)?;
+        }
+        if let Some(value) = lookup("OX_L1_CALLDATA_COST") {
+            self.gas_price_components.l1_calldata_cost = parse("OX_L1_CALLDATA_COST", &value)?;
+        }
+        if let Some(value) = lookup("OX_CONGESTION_FEE") {
+            self.gas_price_components.congestion_fee = parse("OX_CONGESTION_FEE", &value)?;
+        }
+        if let Some(value) = lookup("OX_TX7E_ENABLED") {
+            self.tx7e_enabled = parse("OX_TX7E_ENABLED", &value)?;
+        }
+
+        Ok(())
+    }
+
+    /// Apply the fields that are set in `partial`, leaving the rest untouched
+    pub fn merge(&mut self, partial: PartialArbitrumConfig) {
+        if let Some(chain_id) = partial.chain_id {
+            self.chain_id = chain_id;
+        }
+        if let Some(arb_os_version) = partial.arb_os_version {
+            self.arb_os_version = arb_os_version;
+        }
+        if let Some(l1_base_fee) = partial.l1_base_fee {
+            self.l1_base_fee = l1_base_fee;
+        }
+        if let Some(l1_base_fee_inertia) = partial.l1_base_fee_inertia {
+            self.l1_base_fee_inertia = l1_base_fee_inertia;
+        }
+        if let Some(l2_block_gas_limit) = partial.l2_block_gas_limit {
+            self.l2_block_gas_limit = l2_block_gas_limit;
+        }
+        if let Some(gas) = partial.gas_price_components {
+            self.gas_price_components.merge(gas);
+        }
+        if let Some(tx7e_enabled) = partial.tx7e_enabled {
+            self.tx7e_enabled = tx7e_enabled;
+        }
+        if let Some(mock_l1_bridge) = partial.mock_l1_bridge {
+            self.mock_l1_bridge = mock_l1_bridge;
+        }
+        if let Some(retryable_lifetime_secs) = partial.retryable_lifetime_secs {
+            self.retryable_lifetime_secs = retryable_lifetime_secs;
+        }
+        if let Some(storage_gas_available) = partial.storage_gas_available {
+            self.storage_gas_available = storage_gas_available;
+        }
+        if let Some(allow_debug_precompiles) = partial.allow_debug_precompiles {
+            self.allow_debug_precompiles = allow_debug_precompiles;
+        }
+        if let Some(precompile_gas) = partial.precompile_gas {
+            self.precompile_gas = precompile_gas;
+        }
+        if let Some(precompiles) = partial.precompiles {
+            self.precompiles = precompiles;
+        }
+    }
+
     /// Create default precompile configurations
     fn default_precompiles() -> HashMap<String, PrecompileConfig> {
         let mut precompiles = HashMap::new();
@@ -92,7 +537,7 @@ impl ArbitrumConfig {
         precompiles.insert(
             "0x0000000000000000000000000000000000000064".to_string(),
             PrecompileConfig {
-                address: "0x0000000000000000000000000000000000000064".to_string(),
+                address: precompile_address(0x64),
                 name: "ArbSys".to_string(),
                 enabled: true,
                 config: HashMap::new(),
@@ -103,30 +548,189 @@ impl ArbitrumConfig {
         precompiles.insert(
             "0x000000000000000000000000000000000000006c".to_string(),
             PrecompileConfig {
-                address: "0x000000000000000000000000000000000000006c".to_string(),
+                address: precompile_address(0x6c),
                 name: "ArbGasInfo".to_string(),
                 enabled: true,
                 config: HashMap::new(),
             },
         );
 
+        // ArbRetryableTx precompile (0x6E)
+        precompiles.insert(
+            "0x000000000000000000000000000000000000006e".to_string(),
+            PrecompileConfig {
+                address: precompile_address(0x6e),
+                name: "ArbRetryableTx".to_string(),
+                enabled: true,
+                config: HashMap::new(),
+            },
+        );
+
         precompiles
     }
 
     /// Load configuration from a JSON file
-    pub fn from_file(path: &str) -> Result<Self, Box<dyn std::error::Error>> {
+    ///
+    /// The file is checked with `validate_json` first, so a misspelled key is
+    /// an error rather than a silently defaulted field.
+    pub fn from_file(path: &str) -> Result<Self, ConfigError> {
         let content = std::fs::read_to_string(path)?;
-        let config: ArbitrumConfig = serde_json::from_str(&content)?;
-        Ok(config)
+        Self::from_json_value(serde_json::from_str(&content)?)
+    }
+
+    /// Check the keys of a configuration object before it is deserialized
+    ///
+    /// Reports every unknown or missing key at the top level, in
+    /// `gas_price_components` and in each `precompiles` entry, suggesting the
+    /// closest known key for typos. Apart from the `compression` model, field
+    /// values are left to deserialization and `validate`.
+    pub fn validate_json(value: &serde_json::Value) -> Result<(), Vec<String>> {
+        let Some(object) = value.as_object() else {
+            return Err(vec!["Configuration must be a JSON object".to_string()]);
+        };
+
+        let mut errors = check_keys(object, "", CONFIG_FIELDS, REQUIRED_CONFIG_FIELDS);
+        if let Some(components) = object.get("gas_price_components") {
+            match components.as_object() {
+                Some(components) => {
+                    errors.extend(check_keys(
+                        components,
+                        "gas_price_components.",
+                        GAS_PRICE_COMPONENT_FIELDS,
+                        REQUIRED_GAS_PRICE_COMPONENT_FIELDS,
+                    ));
+                    let known_model = |model: &serde_json::Value| {
+                        model.as_str().is_some_and(|model| COMPRESSION_MODELS.contains(&model))
+                    };
+                    if let Some(model) = components.get("compression").filter(|model| !known_model(model)) {
+                        errors.push(format!(
+                            "Unknown compression model {} (expected one of: {})",
+                            model,
+                            COMPRESSION_MODELS.join(", ")
+                        ));
+                    }
+                }
+                None => errors.push("Field 'gas_price_components' must be an object".to_string()),
+            }
+        }
+        if let Some(precompiles) = object.get("precompiles") {
+            match precompiles.as_object() {
+                Some(precompiles) => {
+                    for (key, precompile) in precompiles {
+                        match precompile.as_object() {
+                            Some(precompile) => errors.extend(check_keys(
+                                precompile,
+                                &format!("precompiles.{}.", key),
+                                PRECOMPILE_CONFIG_FIELDS,
+                                PRECOMPILE_CONFIG_FIELDS,
+                            )),
+                            None => errors.push(format!("Field 'precompiles.{}' must be an object", key)),
+                        }
+                    }
+                }
+                None => errors.push("Field 'precompiles' must be an object".to_string()),
+            }
+        }
+
+        if errors.is_empty() {
+            Ok(())
+        } else {
+            Err(errors)
+        }
+    }
+
+    /// Deserialize a configuration object after checking it with `validate_json`
+    fn from_json_value(value: serde_json::Value) -> Result<Self, ConfigError> {
+        Self::validate_json(&value).map_err(|errors| ConfigError::Schema { errors })?;
+        Ok(serde_json::from_value(value)?)
+    }
+
+    /// Serialize the configuration as pretty-printed JSON
+    pub fn to_json_pretty(&self) -> Result<String, serde_json::Error> {
+        serde_json::to_string_pretty(self)
+    }
+
+    /// Build an Anvil-compatible (geth-style) genesis for the configured chain
+    ///
+    /// All forks up to London are active from block 0, and the genesis base fee
+    /// is the effective L2 base fee. Every enabled precompile gets a stub account
+    /// holding `PRECOMPILE_STUB_CODE`, so `extcodesize` sees it as a contract.
+    pub fn to_genesis_json(&self) -> serde_json::Value {
+        let alloc: serde_json::Map<String, serde_json::Value> = self
+            .precompiles
+            .values()
+            .filter(|precompile| precompile.enabled)
+            .map(|precompile| {
+                let account = json!({ "balance": "0x0", "code": PRECOMPILE_STUB_CODE });
+                (format!("0x{}", hex::encode(precompile.address.as_bytes())), account)
+            })
+            .collect();
+
+        json!({
+            "config": {
+                "chainId": self.chain_id,
+                "homesteadBlock": 0,
+                "eip150Block": 0,
+                "eip155Block": 0,
+                "eip158Block": 0,
+                "byzantiumBlock": 0,
+                "constantinopleBlock": 0,
+                "petersburgBlock": 0,
+                "istanbulBlock": 0,
+                "berlinBlock": 0,
+                "londonBlock": 0,
+            },
+            "nonce": "0x0",
+            "timestamp": "0x0",
+            "extraData": "0x",
+            "gasLimit": format!("{:#x}", self.l2_block_gas_limit),
+            "difficulty": "0x0",
+            "baseFeePerGas": format!("{:#x}", self.gas_price_components.effective_l2_base_fee()),
+            "alloc": alloc,
+        })
     }
 
     /// Save configuration to a JSON file
-    pub fn save_to_file(&self, path: &str) -> Result<(), Box<dyn std::error::Error>> {
-        let content = serde_json::to_string_pretty(self)?;
+    pub fn save_to_file(&self, path: &str) -> Result<(), ConfigError> {
+        let content = self.to_json_pretty()?;
         std::fs::write(path, content)?;
         Ok(())
     }
 
+    /// Load configuration from a TOML file
+    #[cfg(feature = "toml")]
+    pub fn from_toml_file(path: &str) -> Result<Self, ConfigError> {
+        let content = std::fs::read_to_string(path)?;
+        Self::from_json_value(toml::from_str(&content)?)
+    }
+
+    /// Save configuration to a TOML file
+    #[cfg(feature = "toml")]
+    pub fn to_toml_file(&self, path: &str) -> Result<(), ConfigError> {
+        let content = toml::to_string_pretty(self)?;
+        std::fs::write(path, content)?;
+        Ok(())
+    }
+
+    /// Load configuration from a file, choosing the format from its extension
+    ///
+    /// `.toml` files are parsed as TOML (requires the `toml` feature); anything
+    /// else is parsed as JSON.
+    pub fn from_path(path: &str) -> Result<Self, ConfigError> {
+        let extension = std::path::Path::new(path)
+            .extension()
+            .and_then(|ext| ext.to_str())
+            .map(|ext| ext.to_ascii_lowercase());
+
+        match extension.as_deref() {
+            #[cfg(feature = "toml")]
+            Some("toml") => Self::from_toml_file(path),
+            #[cfg(not(feature = "toml"))]
+            Some("toml") => Err(ConfigError::TomlUnsupported),
+            _ => Self::from_file(path),
+        }
+    }
+
     /// Get a precompile configuration by address
     pub fn get_precompile(&self, address: &str) -> Option<&PrecompileConfig> {
         self.precompiles.get(address)
@@ -140,38 +744,180 @@ impl ArbitrumConfig {
             .unwrap_or(false)
     }
 
-    /// Get the L1 gas cost for a given calldata size
-    pub fn calculate_l1_gas_cost(&self, calldata_size: usize) -> u64 {
-        calldata_size as u64 * self.gas_price_components.l1_calldata_cost
+    /// Enable only the named precompiles, disabling all others
+    ///
+    /// Names match `PrecompileConfig::name` case-insensitively, e.g. `arbsys`.
+    pub fn enable_only_precompiles<S: AsRef<str>>(&mut self, names: &[S]) -> Result<(), ConfigError> {
+        self.check_precompile_names(names, &[])?;
+        for precompile in self.precompiles.values_mut() {
+            precompile.enabled = names.iter().any(|name| name.as_ref().eq_ignore_ascii_case(&precompile.name));
+        }
+        Ok(())
+    }
+
+    /// Disable the named precompiles, leaving the others untouched
+    pub fn disable_precompiles<S: AsRef<str>>(&mut self, names: &[S]) -> Result<(), ConfigError> {
+        self.check_precompile_names(names, &[])?;
+        for precompile in self.precompiles.values_mut() {
+            if names.iter().any(|name| name.as_ref().eq_ignore_ascii_case(&precompile.name)) {
+                precompile.enabled = false;
+            }
+        }
+        Ok(())
+    }
+
+    /// Gas cost configured for the named precompile, overriding its built-in cost
+    pub fn precompile_gas_cost(&self, name: &str) -> Option<u64> {
+        self.precompile_gas
+            .iter()
+            .find(|(key, _)| key.eq_ignore_ascii_case(name))
+            .map(|(_, gas)| *gas)
+    }
+
+    /// Check that every name is a configured precompile or one of `extra`
+    fn check_precompile_names<S: AsRef<str>>(&self, names: &[S], extra: &[&str]) -> Result<(), ConfigError> {
+        for name in names {
+            let name = name.as_ref();
+            let configured = self.precompiles.values().any(|p| p.name.eq_ignore_ascii_case(name));
+            if !configured && !extra.iter().any(|known| known.eq_ignore_ascii_case(name)) {
+                let mut known: Vec<String> = self
+                    .precompiles
+                    .values()
+                    .map(|p| p.name.to_lowercase())
+                    .chain(extra.iter().map(|name| name.to_lowercase()))
+                    .collect();
+                known.sort();
+                return Err(ConfigError::UnknownPrecompile {
+                    name: name.to_string(),
+                    known: known.join(", "),
+                });
+            }
+        }
+        Ok(())
+    }
+
+    /// Get the L1 gas cost for the given calldata
+    ///
+    /// The per-byte cost is charged on the size estimated by the configured
+    /// compression model, which is the raw length unless compression is enabled.
+    /// The compression model is the only discount; there is no flat reduction.
+    pub fn calculate_l1_gas_cost(&self, calldata: &[u8]) -> u64 {
+        let size = self.gas_price_components.compression.estimate_compressed_len(calldata);
+        (size as u64).saturating_mul(self.gas_price_components.l1_calldata_cost)
     }
 
-    /// Get the total L1 gas cost in wei for a given calldata size
-    pub fn calculate_l1_gas_cost_wei(&self, calldata_size: usize) -> u64 {
-        self.calculate_l1_gas_cost(calldata_size) * self.l1_base_fee
+    /// Get the total L1 gas cost in wei for the given calldata, after the L1 fee scalar
+    ///
+    /// Saturates at `u64::MAX` rather than overflowing.
+    pub fn calculate_l1_gas_cost_wei(&self, calldata: &[u8]) -> u64 {
+        self.calculate_l1_gas_cost_wei_at(calldata, self.l1_base_fee)
+    }
+
+    /// Like `calculate_l1_gas_cost_wei`, but at the given L1 base fee (e.g. the session's estimate)
+    pub fn calculate_l1_gas_cost_wei_at(&self, calldata: &[u8], l1_base_fee: u64) -> u64 {
+        let fee = self.calculate_l1_gas_cost(calldata) as u128 * l1_base_fee as u128;
+        self.gas_price_components.scale_l1_fee(fee)
     }
 
     /// Validate the configuration
-    pub fn validate(&self) -> Result<(), String> {
+    pub fn validate(&self) -> Result<(), ConfigError> {
+        let zero = |field| Err(ConfigError::Zero { field });
         if self.chain_id == 0 {
-            return Err("Chain ID cannot be 0".to_string());
+            return zero("Chain ID");
         }
 
         if self.arb_os_version == 0 {
-            return Err("ArbOS version cannot be 0".to_string());
+            return zero("ArbOS version");
         }
 
         if self.l1_base_fee == 0 {
-            return Err("L1 base fee cannot be 0".to_string());
+            return zero("L1 base fee");
+        }
+
+        if self.l1_base_fee_inertia == 0 {
+            return zero("L1 base fee inertia");
+        }
+
+        if self.l2_block_gas_limit == 0 {
+            return zero("L2 block gas limit");
         }
 
         if self.gas_price_components.l2_base_fee == 0 {
-            return Err("L2 base fee cannot be 0".to_string());
+            return zero("L2 base fee");
         }
 
         if self.gas_price_components.l1_calldata_cost == 0 {
-            return Err("L1 calldata cost cannot be 0".to_string());
+            return zero("L1 calldata cost");
+        }
+
+        if self.retryable_lifetime_secs == 0 {
+            return zero("Retryable lifetime");
+        }
+
+        let gas = &self.gas_price_components;
+        if gas.l1_calldata_cost.checked_mul(self.l1_base_fee).is_none() {
+            return Err(ConfigError::Overflow {
+                lhs: "L1 calldata cost",
+                lhs_value: gas.l1_calldata_cost,
+                op: "times",
+                rhs: "L1 base fee",
+                rhs_value: self.l1_base_fee,
+            });
+        }
+
+        if gas.l2_base_fee.checked_add(gas.congestion_fee).is_none() {
+            return Err(ConfigError::Overflow {
+                lhs: "L2 base fee",
+                lhs_value: gas.l2_base_fee,
+                op: "plus",
+                rhs: "congestion fee",
+                rhs_value: gas.congestion_fee,
+            });
+        }
+
+        let exceeds = |field, value, max, unit| Err(ConfigError::ExceedsMax { field, value, max, unit });
+        if self.l1_base_fee > MAX_BASE_FEE {
+            return exceeds("L1 base fee", self.l1_base_fee, MAX_BASE_FEE, "wei");
+        }
+
+        if gas.l2_base_fee > MAX_BASE_FEE {
+            return exceeds("L2 base fee", gas.l2_base_fee, MAX_BASE_FEE, "wei");
+        }
+
+        if gas.min_l2_gas_price > MAX_BASE_FEE {
+            return exceeds("Minimum L2 gas price", gas.min_l2_gas_price, MAX_BASE_FEE, "wei");
+        }
+
+        if gas.effective_l2_base_fee().checked_add(gas.congestion_fee).is_none() {
+            return Err(ConfigError::Overflow {
+                lhs: "Minimum L2 gas price",
+                lhs_value: gas.min_l2_gas_price,
+                op: "plus",
+                rhs: "congestion fee",
+                rhs_value: gas.congestion_fee,
+            });
         }
 
+        if gas.l1_calldata_cost > MAX_L1_CALLDATA_COST {
+            return exceeds("L1 calldata cost", gas.l1_calldata_cost, MAX_L1_CALLDATA_COST, "gas per byte");
+        }
+
+        for (key, precompile) in &self.precompiles {
+            let key_address = Address::from_hex(key).map_err(|e| ConfigError::InvalidPrecompileKey {
+                key: key.clone(),
+                reason: e.to_string(),
+            })?;
+            if key_address != precompile.address {
+                return Err(ConfigError::PrecompileKeyMismatch {
+                    key: key.clone(),
+                    address: precompile.address,
+                });
+            }
+        }
+
+        let gas_names: Vec<&String> = self.precompile_gas.keys().collect();
+        self.check_precompile_names(&gas_names, UNCONFIGURED_PRECOMPILES)?;
+
         Ok(())
     }
 }
@@ -187,7 +933,17 @@ mod tests {
         assert_eq!(config.arb_os_version, 20);
         assert_eq!(config.l1_base_fee, 20_000_000_000);
         assert!(config.tx7e_enabled);
-        assert_eq!(config.mock_l1_bridge, "0x0000000000000000000000000000000000000064");
+        assert_eq!(config.mock_l1_bridge, Address::from_hex(DEFAULT_MOCK_L1_BRIDGE).unwrap());
+        assert!(!config.precompiles.values().any(|p| p.address == config.mock_l1_bridge));
+    }
+
+    #[test]
+    fn test_l2_block_gas_limit() {
+        let mut config = ArbitrumConfig::default();
+        assert_eq!(config.l2_block_gas_limit, DEFAULT_L2_BLOCK_GAS_LIMIT);
+
+        config.l2_block_gas_limit = 0;
+        assert!(config.validate().is_err());
     }
 
     #[test]
@@ -198,6 +954,86 @@ mod tests {
         assert_eq!(config.l1_base_fee, 15_000_000_000);
     }
 
+    #[test]
+    fn test_chain_presets() {
+        assert_eq!(ArbitrumConfig::arbitrum_one().chain_id, 42161);
+        assert_eq!(ArbitrumConfig::arbitrum_nova().chain_id, 42170);
+        assert_eq!(ArbitrumConfig::arbitrum_sepolia().chain_id, 421614);
+
+        for preset in [
+            ArbitrumConfig::arbitrum_one(),
+            ArbitrumConfig::arbitrum_nova(),
+            ArbitrumConfig::arbitrum_sepolia(),
+        ] {
+            assert!(preset.validate().is_ok());
+        }
+    }
+
+    #[test]
+    fn test_for_chain_id() {
+        let nova = ArbitrumConfig::for_chain_id(42170).unwrap();
+        assert_eq!(nova.chain_id, 42170);
+        assert_eq!(
+            nova.gas_price_components.l2_base_fee,
+            ArbitrumConfig::arbitrum_nova().gas_price_components.l2_base_fee
+        );
+        assert!(ArbitrumConfig::for_chain_id(1).is_none());
+    }
+
+    #[test]
+    fn test_merge_partial_chain_id() {
+        let mut config = ArbitrumConfig::arbitrum_sepolia();
+        let before = config.clone();
+        config.merge(PartialArbitrumConfig {
+            chain_id: Some(1234),
+            ..Default::default()
+        });
+
+        assert_eq!(config.chain_id, 1234);
+        assert_eq!(config.arb_os_version, before.arb_os_version);
+        assert_eq!(config.l1_base_fee, before.l1_base_fee);
+        assert_eq!(config.l2_block_gas_limit, before.l2_block_gas_limit);
+        assert_eq!(
+            config.gas_price_components.l2_base_fee,
+            before.gas_price_components.l2_base_fee
+        );
+        assert_eq!(config.tx7e_enabled, before.tx7e_enabled);
+        assert_eq!(config.mock_l1_bridge, before.mock_l1_bridge);
+        assert_eq!(config.precompiles.len(), before.precompiles.len());
+    }
+
+    #[test]
+    fn test_merge_partial_from_json() {
+        let partial: PartialArbitrumConfig = serde_json::from_str(
+            r#"{"l1_base_fee": 5, "gas_price_components": {"congestion_fee": 7}}"#,
+        )
+        .unwrap();
+        let mut config = ArbitrumConfig::default();
+        config.merge(partial);
+
+        assert_eq!(config.l1_base_fee, 5);
+        assert_eq!(config.gas_price_components.congestion_fee, 7);
+        assert_eq!(config.gas_price_components.l2_base_fee, 1_000_000_000);
+        assert_eq!(config.chain_id, 42161);
+    }
+
+    #[test]
+    fn test_addresses_deserialize_from_hex_strings() {
+        let partial: PartialArbitrumConfig = serde_json::from_str(
+            r#"{"mock_l1_bridge": "0x0000000000000000000000000000000000002002"}"#,
+        )
+        .unwrap();
+        let mut config = ArbitrumConfig::default();
+        config.merge(partial);
+        assert_eq!(config.mock_l1_bridge.as_bytes()[18..], [0x20, 0x02]);
+
+        let json = serde_json::to_string(&config).unwrap();
+        assert_eq!(serde_json::from_str::<ArbitrumConfig>(&json).unwrap(), config);
+
+        // Malformed addresses fail at parse time rather than in `validate`
+        assert!(serde_json::from_str::<PartialArbitrumConfig>(r#"{"mock_l1_bridge": "0x2002"}"#).is_err());
+    }
+
     #[test]
     fn test_precompile_config() {
         let config = ArbitrumConfig::default();
@@ -206,17 +1042,115 @@ mod tests {
         assert!(!config.is_precompile_enabled("0x0000000000000000000000000000000000000000"));
     }
 
+    #[test]
+    fn test_genesis_json() {
+        let config = ArbitrumConfig {
+            chain_id: 421614,
+            ..ArbitrumConfig::default()
+        };
+        let genesis = config.to_genesis_json();
+        assert_eq!(genesis["config"]["chainId"], 421614);
+        assert_eq!(genesis["config"]["londonBlock"], 0);
+        assert_eq!(
+            genesis["baseFeePerGas"],
+            format!("{:#x}", config.gas_price_components.effective_l2_base_fee())
+        );
+
+        let alloc = genesis["alloc"].as_object().unwrap();
+        assert_eq!(alloc.len(), 3);
+        for address in [
+            "0x0000000000000000000000000000000000000064",
+            "0x000000000000000000000000000000000000006c",
+        ] {
+            assert_eq!(alloc[address]["code"], PRECOMPILE_STUB_CODE);
+        }
+
+        let mut disabled = config.clone();
+        disabled
+            .precompiles
+            .get_mut("0x000000000000000000000000000000000000006e")
+            .unwrap()
+            .enabled = false;
+        assert!(!disabled.to_genesis_json()["alloc"]
+            .as_object()
+            .unwrap()
+            .contains_key("0x000000000000000000000000000000000000006e"));
+    }
+
     #[test]
     fn test_l1_gas_calculation() {
         let config = ArbitrumConfig::default();
-        let calldata_size = 1000;
-        let gas_cost = config.calculate_l1_gas_cost(calldata_size);
+        let calldata = vec![0xabu8; 1000];
+        let gas_cost = config.calculate_l1_gas_cost(&calldata);
         assert_eq!(gas_cost, 16000); // 1000 * 16
 
-        let wei_cost = config.calculate_l1_gas_cost_wei(calldata_size);
+        let wei_cost = config.calculate_l1_gas_cost_wei(&calldata);
         assert_eq!(wei_cost, 320_000_000_000_000); // 16000 * 20_000_000_000
     }
 
+    #[test]
+    fn test_l1_gas_cost_wei_saturates_at_validated_maxima() {
+        let mut config = ArbitrumConfig {
+            l1_base_fee: MAX_BASE_FEE,
+            ..ArbitrumConfig::default()
+        };
+        config.gas_price_components.l1_calldata_cost = MAX_L1_CALLDATA_COST;
+        assert!(config.validate().is_ok());
+
+        // 4 KB at the maxima is 4_096_000 gas * 10^13 wei, past u64::MAX
+        let calldata = vec![0xabu8; 4096];
+        assert_eq!(config.calculate_l1_gas_cost(&calldata), 4_096_000);
+        assert_eq!(config.calculate_l1_gas_cost_wei(&calldata), u64::MAX);
+
+        // The scalar applies to the unreduced product
+        config.gas_price_components.l1_fee_scalar = DEFAULT_L1_FEE_SCALAR / 4;
+        assert_eq!(config.calculate_l1_gas_cost_wei(&calldata), 10_240_000_000_000_000_000);
+    }
+
+    #[test]
+    fn test_l1_base_fee_inertia_validation() {
+        let mut config = ArbitrumConfig::default();
+        assert_eq!(config.l1_base_fee_inertia, DEFAULT_L1_BASE_FEE_INERTIA);
+        config.l1_base_fee_inertia = 0;
+        assert!(config.validate().unwrap_err().to_string().contains("L1 base fee inertia"));
+    }
+
+    #[test]
+    fn test_l1_fee_scalar() {
+        let mut config = ArbitrumConfig::default();
+        assert_eq!(config.gas_price_components.l1_fee_scalar, DEFAULT_L1_FEE_SCALAR);
+        let calldata = vec![0xabu8; 1000];
+        let unscaled = config.calculate_l1_gas_cost_wei(&calldata);
+
+        config.gas_price_components.l1_fee_scalar = 1_500_000;
+        assert_eq!(config.calculate_l1_gas_cost_wei(&calldata), unscaled * 3 / 2);
+        // The scalar applies to the fee, not the L1 gas
+        assert_eq!(config.calculate_l1_gas_cost(&calldata), 16000);
+
+        config.gas_price_components.l1_fee_scalar = 0;
+        assert_eq!(config.calculate_l1_gas_cost_wei(&calldata), 0);
+        config.gas_price_components.l1_fee_scalar = u64::MAX;
+        assert_eq!(config.calculate_l1_gas_cost_wei(&calldata), u64::MAX);
+
+        // Configs written before the field existed load as 1.0x
+        let mut json = serde_json::to_value(ArbitrumConfig::default()).unwrap();
+        json["gas_price_components"].as_object_mut().unwrap().remove("l1_fee_scalar");
+        let config: ArbitrumConfig = serde_json::from_value(json).unwrap();
+        assert_eq!(config.gas_price_components.l1_fee_scalar, DEFAULT_L1_FEE_SCALAR);
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_l1_gas_calculation_with_compression() {
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.compression = CompressionModel::Brotli;
+
+        let calldata = vec![0xabu8; 1000];
+        let gas_cost = config.calculate_l1_gas_cost(&calldata);
+        assert!(gas_cost > 0);
+        assert!(gas_cost < 16000 / 10);
+    }
+
     #[test]
     fn test_config_validation() {
         let mut config = ArbitrumConfig::default();
@@ -233,4 +1167,267 @@ mod tests {
         config.l1_base_fee = 0;
         assert!(config.validate().is_err());
     }
+
+    #[test]
+    fn test_gas_component_bounds() {
+        let config = ArbitrumConfig::new(42161, 20, MAX_BASE_FEE + 1);
+        assert!(config.validate().unwrap_err().to_string().contains("L1 base fee"));
+
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.l2_base_fee = MAX_BASE_FEE + 1;
+        assert!(config.validate().unwrap_err().to_string().contains("L2 base fee"));
+
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.l1_calldata_cost = MAX_L1_CALLDATA_COST + 1;
+        assert!(matches!(
+            config.validate(),
+            Err(ConfigError::ExceedsMax { field: "L1 calldata cost", max: MAX_L1_CALLDATA_COST, .. })
+        ));
+
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.min_l2_gas_price = MAX_BASE_FEE + 1;
+        assert!(config.validate().unwrap_err().to_string().contains("Minimum L2 gas price"));
+    }
+
+    #[test]
+    fn test_effective_l2_base_fee_respects_floor() {
+        let mut gas = GasPriceComponents::default();
+        assert_eq!(gas.min_l2_gas_price, DEFAULT_MIN_L2_GAS_PRICE);
+        assert_eq!(gas.effective_l2_base_fee(), gas.l2_base_fee);
+
+        gas.l2_base_fee = 1;
+        assert_eq!(gas.effective_l2_base_fee(), DEFAULT_MIN_L2_GAS_PRICE);
+
+        // Configs written before the floor existed pick up the default
+        let config: ArbitrumConfig = serde_json::from_str(
+            &serde_json::to_string(&ArbitrumConfig::default())
+                .unwrap()
+                .replace(r#","min_l2_gas_price":100000000"#, ""),
+        )
+        .unwrap();
+        assert_eq!(config.gas_price_components.min_l2_gas_price, DEFAULT_MIN_L2_GAS_PRICE);
+    }
+
+    #[test]
+    fn test_gas_component_overflow_rejected() {
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.congestion_fee = u64::MAX;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("overflows u64"));
+
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.l1_calldata_cost = u64::MAX / 2;
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("times L1 base fee"));
+        assert!(err.contains("overflows u64"));
+
+        let mut config = ArbitrumConfig::new(42161, 20, MAX_BASE_FEE);
+        config.gas_price_components.l1_calldata_cost = MAX_L1_CALLDATA_COST;
+        assert!(config.validate().is_ok());
+    }
+
+    #[test]
+    fn test_precompile_key_mismatch_rejected() {
+        let mut config = ArbitrumConfig::default();
+        let mut arbsys = config.precompiles["0x0000000000000000000000000000000000000064"].clone();
+        arbsys.address = Address::from_hex("0x0000000000000000000000000000000000000065").unwrap();
+        config
+            .precompiles
+            .insert("0x0000000000000000000000000000000000000064".to_string(), arbsys);
+
+        let err = config.validate().unwrap_err();
+        assert!(matches!(&err, ConfigError::PrecompileKeyMismatch { key, .. } if key.ends_with("64")));
+        let err = err.to_string();
+        assert!(err.contains("0x0000000000000000000000000000000000000064"));
+        assert!(err.contains("does not match"));
+    }
+
+    #[test]
+    fn test_precompile_key_invalid_length_rejected() {
+        let mut config = ArbitrumConfig::default();
+        let arbsys = config
+            .precompiles
+            .remove("0x0000000000000000000000000000000000000064")
+            .unwrap();
+        config.precompiles.insert("0x64".to_string(), arbsys);
+
+        let err = config.validate().unwrap_err().to_string();
+        assert!(err.contains("Invalid precompile address key '0x64'"));
+    }
+
+    #[cfg(feature = "toml")]
+    #[test]
+    fn test_toml_round_trip() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.toml");
+        let path = path.to_str().unwrap();
+
+        let mut config = ArbitrumConfig::arbitrum_sepolia();
+        config
+            .precompiles
+            .get_mut("0x0000000000000000000000000000000000000064")
+            .unwrap()
+            .config
+            .insert("mock_block_number".to_string(), serde_json::json!(7));
+        config.to_toml_file(path).unwrap();
+
+        let loaded = ArbitrumConfig::from_path(path).unwrap();
+        assert_eq!(loaded.chain_id, 421614);
+        assert_eq!(loaded.l1_base_fee, config.l1_base_fee);
+        assert_eq!(loaded.precompiles.len(), config.precompiles.len());
+        assert_eq!(
+            loaded.precompiles["0x0000000000000000000000000000000000000064"].config["mock_block_number"],
+            serde_json::json!(7)
+        );
+        assert!(loaded.validate().is_ok());
+    }
+
+    #[test]
+    fn test_validate_json_reports_misspelled_keys() {
+        let mut json = serde_json::to_value(ArbitrumConfig::default()).unwrap();
+        assert_eq!(ArbitrumConfig::validate_json(&json), Ok(()));
+
+        // The field lists match what the config serializes
+        let mut keys: Vec<&str> = json.as_object().unwrap().keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        let mut fields = CONFIG_FIELDS.to_vec();
+        fields.sort_unstable();
+        assert_eq!(keys, fields);
+        assert_eq!(json["gas_price_components"].as_object().unwrap().len(), GAS_PRICE_COMPONENT_FIELDS.len());
+
+        let object = json.as_object_mut().unwrap();
+        let chain_id = object.remove("chain_id").unwrap();
+        object.insert("chian_id".to_string(), chain_id);
+        object.insert("verbose".to_string(), serde_json::json!(true));
+        let components = object["gas_price_components"].as_object_mut().unwrap();
+        let fee = components.remove("l2_base_fee").unwrap();
+        components.insert("l2_basefee".to_string(), fee);
+        assert_eq!(
+            ArbitrumConfig::validate_json(&json),
+            Err(vec![
+                "Unknown field 'chian_id' (did you mean 'chain_id'?)".to_string(),
+                "Unknown field 'verbose'".to_string(),
+                "Missing required field 'chain_id'".to_string(),
+                "Unknown field 'gas_price_components.l2_basefee' (did you mean 'gas_price_components.l2_base_fee'?)"
+                    .to_string(),
+                "Missing required field 'gas_price_components.l2_base_fee'".to_string(),
+            ])
+        );
+
+        // Loading the file reports the same errors instead of defaulting the fields
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, r#"{"chain_id": 1, "arb_os_version": 20, "l1_base_fee": 1, "tx7e_enabled": true,
+            "mock_l1_bridge": "0x0000000000000000000000000000000000001001", "precompiles": {},
+            "gas_price_components": {"l2_base_fee": 1, "l1_calldata_cost": 16, "l1_storage_cost": 0,
+            "congestion_fee": 0}, "storage_gas_availible": 5}"#)
+        .unwrap();
+        let err = ArbitrumConfig::from_path(path.to_str().unwrap()).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Invalid configuration: Unknown field 'storage_gas_availible' (did you mean 'storage_gas_available'?)"
+        );
+        assert_eq!(ArbitrumConfig::validate_json(&serde_json::json!([])).unwrap_err().len(), 1);
+    }
+
+    #[test]
+    fn test_validate_json_checks_nested_objects() {
+        let mut json = serde_json::to_value(ArbitrumConfig::default()).unwrap();
+        let arb_sys = "0x0000000000000000000000000000000000000064";
+        let precompile = json["precompiles"][arb_sys].as_object().unwrap();
+        let mut keys: Vec<&str> = precompile.keys().map(String::as_str).collect();
+        keys.sort_unstable();
+        let mut fields = PRECOMPILE_CONFIG_FIELDS.to_vec();
+        fields.sort_unstable();
+        assert_eq!(keys, fields);
+
+        let precompile = json["precompiles"][arb_sys].as_object_mut().unwrap();
+        let enabled = precompile.remove("enabled").unwrap();
+        precompile.insert("enabeld".to_string(), enabled);
+        json["gas_price_components"]["compression"] = serde_json::json!("brotly");
+        assert_eq!(
+            ArbitrumConfig::validate_json(&json),
+            Err(vec![
+                "Unknown compression model \"brotly\" (expected one of: none, brotli)".to_string(),
+                format!("Unknown field 'precompiles.{}.enabeld' (did you mean 'precompiles.{}.enabled'?)", arb_sys, arb_sys),
+                format!("Missing required field 'precompiles.{}.enabled'", arb_sys),
+            ])
+        );
+
+        json["precompiles"][arb_sys] = serde_json::json!(true);
+        json["gas_price_components"]["compression"] = serde_json::json!("brotli");
+        assert_eq!(
+            ArbitrumConfig::validate_json(&json),
+            Err(vec![format!("Field 'precompiles.{}' must be an object", arb_sys)])
+        );
+    }
+
+    #[test]
+    fn test_readme_example_config_loads() {
+        let readme = include_str!("../README.md");
+        let start = readme.find("```json").expect("README has a JSON config example") + "```json".len();
+        let end = start + readme[start..].find("```").unwrap();
+
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        std::fs::write(&path, &readme[start..end]).unwrap();
+        let config = ArbitrumConfig::from_path(path.to_str().unwrap()).unwrap();
+        config.validate().unwrap();
+        assert_eq!(config.precompiles, ArbitrumConfig::default().precompiles);
+    }
+
+    #[test]
+    fn test_from_path_defaults_to_json() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let path = path.to_str().unwrap();
+
+        ArbitrumConfig::arbitrum_nova().save_to_file(path).unwrap();
+        assert_eq!(ArbitrumConfig::from_path(path).unwrap().chain_id, 42170);
+    }
+
+    #[test]
+    fn test_overrides_from_lookup() {
+        let mut config = ArbitrumConfig::default();
+        config
+            .apply_overrides_from(|name| match name {
+                "OX_ARB_CHAIN_ID" => Some("421614".to_string()),
+                "OX_L2_BASE_FEE" => Some("2000000000".to_string()),
+                _ => None,
+            })
+            .unwrap();
+
+        assert_eq!(config.chain_id, 421614);
+        assert_eq!(config.gas_price_components.l2_base_fee, 2_000_000_000);
+        assert_eq!(config.arb_os_version, 20);
+        assert_eq!(config.l1_base_fee, 20_000_000_000);
+    }
+
+    #[test]
+    fn test_overrides_parse_values() {
+        // Resolved through a closure rather than the process environment, which
+        // tests running in parallel would share
+        let variables = |l1_base_fee: &'static str| {
+            move |name: &str| match name {
+                "OX_ARB_OS_VERSION" => Some("31".to_string()),
+                "OX_L1_BASE_FEE" => Some(l1_base_fee.to_string()),
+                _ => None,
+            }
+        };
+        let mut config = ArbitrumConfig::default();
+        let result = config.apply_overrides_from(variables(" 15000000000 "));
+
+        let mut invalid = ArbitrumConfig::default();
+        let invalid_result = invalid.apply_overrides_from(variables("fifteen gwei"));
+
+        result.unwrap();
+        assert_eq!(config.arb_os_version, 31);
+        assert_eq!(config.l1_base_fee, 15_000_000_000);
+        assert_eq!(config.chain_id, 42161);
+
+        assert!(matches!(
+            invalid_result,
+            Err(ConfigError::InvalidOverride { name, .. }) if name == "OX_L1_BASE_FEE"
+        ));
+    }
 }
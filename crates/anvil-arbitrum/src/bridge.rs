@@ -0,0 +1,254 @@
+//! Mock L1 bridge endpoint that turns deposits into 0x7e transactions
+
+use crate::arbitrum::ArbitrumConfig;
+use crate::mock_chain::MockChain;
+use crate::precompiles::{Address, FunctionTable, PrecompileError, PrecompileHandler, U256};
+use crate::tx7e::{Tx7eParser, Tx7eTransaction, TX_BASE_GAS};
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt, U256Ext};
+use sha3::{Digest, Keccak256};
+use std::sync::{Arc, Mutex};
+
+type Result<T, E = PrecompileError> = std::result::Result<T, E>;
+
+/// Deposits synthesized by the mock bridge, in message order
+///
+/// Cloning the queue shares the underlying list, so the bridge handler and
+/// whatever drains deposits into the chain observe the same entries.
+#[derive(Debug, Clone, Default)]
+pub struct DepositQueue {
+    deposits: Arc<Mutex<Vec<Tx7eTransaction>>>,
+}
+
+impl DepositQueue {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a deposit, returning its message number
+    pub fn push(&self, deposit: Tx7eTransaction) -> u64 {
+        let mut deposits = self.deposits.lock().unwrap();
+        deposits.push(deposit);
+        deposits.len() as u64 - 1
+    }
+
+    /// Get a deposit by message number
+    pub fn get(&self, message_number: u64) -> Option<Tx7eTransaction> {
+        let index = usize::try_from(message_number).ok()?;
+        self.deposits.lock().unwrap().get(index).cloned()
+    }
+
+    /// Number of deposits made through the bridge
+    pub fn len(&self) -> usize {
+        self.deposits.lock().unwrap().len()
+    }
+
+    /// Check if no deposits have been made
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Functions implemented by `L1BridgeHandler`
+#[derive(Debug, Clone, Copy)]
+enum L1BridgeFunction {
+    DepositEth,
+}
+
+const L1BRIDGE_FUNCTIONS: &[(&str, L1BridgeFunction)] =
+    &[("depositEth(address,uint256)", L1BridgeFunction::DepositEth)];
+
+/// Mock L1 bridge handler, registered at `ArbitrumConfig::mock_l1_bridge`
+pub struct L1BridgeHandler {
+    address: Address,
+    functions: FunctionTable<L1BridgeFunction>,
+    deposits: DepositQueue,
+    chain: MockChain,
+}
+
+impl L1BridgeHandler {
+    pub fn new(address: Address) -> Self {
+        Self::with_deposits(address, DepositQueue::new())
+    }
+
+    /// Create a handler at the bridge address configured in `config`
+    pub fn from_config(config: &ArbitrumConfig) -> Self {
+        Self::new(config.mock_l1_bridge)
+    }
+
+    /// Create a handler backed by an existing deposit queue
+    pub fn with_deposits(address: Address, deposits: DepositQueue) -> Self {
+        Self {
+            address,
+            functions: FunctionTable::new(L1BRIDGE_FUNCTIONS),
+            deposits,
+            chain: MockChain::default(),
+        }
+    }
+
+    /// Timestamp deposits from the given mock chain
+    pub fn with_mock_chain(mut self, chain: MockChain) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Get the deposit queue backing this handler
+    pub fn deposits(&self) -> &DepositQueue {
+        &self.deposits
+    }
+}
+
+impl PrecompileHandler for L1BridgeHandler {
+    fn address_ref(&self) -> &Address {
+        &self.address
+    }
+
+    fn name(&self) -> &str {
+        "L1Bridge"
+    }
+
+    fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        match self.functions.lookup(input)? {
+            L1BridgeFunction::DepositEth => self.handle_deposit_eth(input, config),
+        }
+    }
+
+    fn gas_cost(&self, _input: &[u8], config: &ArbitrumConfig) -> u64 {
+        config.precompile_gas_cost(self.name()).unwrap_or(10)
+    }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
+    }
+}
+
+impl L1BridgeHandler {
+    /// Handle depositEth(address,uint256) call
+    ///
+    /// Precompile calls carry no sender or value, so the mock takes the L2
+    /// recipient and the deposit value as arguments. Returns the message number
+    /// of the queued deposit.
+    fn handle_deposit_eth(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        // The function table has already checked that both head words are present
+        let args = &input[4..68];
+        let recipient = Address::new(args[12..32].try_into().unwrap());
+        let value = U256::from_big_endian(&args[32..64]);
+
+        let message_number = self.deposits.len() as u64;
+        let l1_block_number = message_number + 1;
+        let l1_timestamp = self.chain.timestamp();
+
+        let mut hasher = Keccak256::new();
+        hasher.update(self.address.as_bytes());
+        hasher.update(message_number.to_be_bytes());
+        let l1_tx_hash: [u8; 32] = hasher.finalize().into();
+        let source_hash =
+            Tx7eParser::new().generate_source_hash(&l1_tx_hash, l1_block_number, message_number);
+
+        let deposit = Tx7eTransaction::new(
+            config.chain_id,
+            recipient,
+            value,
+            Vec::new(),
+            TX_BASE_GAS,
+            l1_block_number,
+            l1_timestamp,
+            U256::from_u64(config.l1_base_fee),
+            U256::from_u64(config.l1_base_fee),
+            0,
+            U256::zero(),
+            recipient,
+            source_hash,
+        );
+
+        let message_number = self.deposits.push(deposit);
+        Ok(U256::from_u64(message_number).to_big_endian())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precompiles::selector;
+
+    fn deposit_call(recipient: &Address, value: u64) -> Vec<u8> {
+        let mut input = selector("depositEth(address,uint256)").to_vec();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(recipient.as_bytes());
+        input.extend_from_slice(&U256::from_u64(value).to_big_endian());
+        input
+    }
+
+    #[test]
+    fn test_deposit_produces_parseable_tx7e() {
+        let config = ArbitrumConfig::default();
+        let handler = L1BridgeHandler::from_config(&config);
+        assert_eq!(handler.address(), config.mock_l1_bridge);
+
+        let recipient = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        let result = handler
+            .handle_call(&deposit_call(&recipient, 1_000_000_000_000_000_000), &config)
+            .unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(0));
+
+        let deposit = handler.deposits().get(0).unwrap();
+        let raw = deposit.rlp_encode_typed();
+
+        let parser = Tx7eParser::from_config(&config);
+        let parsed = parser.parse(&raw).unwrap();
+        assert_eq!(parsed, deposit);
+        assert_eq!(parsed.target, recipient);
+        assert_eq!(parsed.value, U256::from_u64(1_000_000_000_000_000_000));
+        assert!(parser.validate_transaction(&parsed).is_valid);
+    }
+
+    #[test]
+    fn test_deposits_get_distinct_message_numbers() {
+        let config = ArbitrumConfig::default();
+        let handler = L1BridgeHandler::from_config(&config);
+        let recipient = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+
+        handler.handle_call(&deposit_call(&recipient, 1), &config).unwrap();
+        let result = handler.handle_call(&deposit_call(&recipient, 1), &config).unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(1));
+
+        let first = handler.deposits().get(0).unwrap();
+        let second = handler.deposits().get(1).unwrap();
+        assert_ne!(first.hash(), second.hash());
+    }
+
+    #[test]
+    fn test_deposit_requires_arguments() {
+        let config = ArbitrumConfig::default();
+        let handler = L1BridgeHandler::from_config(&config);
+        let input = selector("depositEth(address,uint256)");
+        assert!(matches!(
+            handler.handle_call(&input, &config),
+            Err(PrecompileError::InvalidInput("Input too short for function arguments"))
+        ));
+        assert!(handler.deposits().is_empty());
+    }
+
+    #[test]
+    fn test_declared_selector_dispatches() {
+        let config = ArbitrumConfig::default();
+        let handler = L1BridgeHandler::from_config(&config);
+        assert_eq!(handler.signatures(), ["depositEth(address,uint256)"]);
+        assert_eq!(hex::encode(selector(handler.signatures()[0])), "39ed5123");
+
+        let recipient = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        handler.handle_call(&deposit_call(&recipient, 1), &config).unwrap();
+        assert_eq!(handler.deposits().len(), 1);
+    }
+
+    #[test]
+    fn test_configured_gas_cost_overrides_default() {
+        let mut config = ArbitrumConfig::default();
+        let handler = L1BridgeHandler::from_config(&config);
+        assert_eq!(handler.gas_cost(&[], &config), 10);
+
+        config.precompile_gas.insert("l1bridge".to_string(), 500);
+        config.validate().unwrap();
+        assert_eq!(handler.gas_cost(&[], &config), 500);
+    }
+}
@@ -1,9 +1,45 @@
 //! CLI argument parsing for Anvil with Arbitrum extensions
 
+use crate::accounts::{self, AccountError, DevAccount};
+use crate::arbitrum::{ArbitrumConfig, PartialGasPriceComponents};
+use crate::logging::LogFormat;
+use crate::rpc;
+use crate::tx7e::{TransactionValidation, Tx7eParser, Tx7eTransaction};
+use anyhow::{anyhow, Result};
 use clap::Parser;
+use serde_json::{json, Value};
+use tracing_subscriber::filter::LevelFilter;
+
+/// Chain ID Anvil uses when neither `--chain-id` nor `--arbitrum` is given
+pub const DEFAULT_ANVIL_CHAIN_ID: u64 = 31337;
+
+/// Arbitrum chain ID used when `--arb-chain-id` is not given (Arbitrum One)
+pub const DEFAULT_ARBITRUM_CHAIN_ID: u64 = 42161;
+
+/// Named Arbitrum chain whose `ArbitrumConfig` preset seeds the configuration
+#[derive(Debug, Clone, Copy, PartialEq, Eq, clap::ValueEnum)]
+pub enum ChainPreset {
+    /// Arbitrum One (chain ID 42161)
+    ArbitrumOne,
+    /// Arbitrum Nova (chain ID 42170)
+    ArbitrumNova,
+    /// Arbitrum Sepolia testnet (chain ID 421614)
+    ArbitrumSepolia,
+}
+
+impl ChainPreset {
+    /// The preset configuration for this chain
+    pub fn config(self) -> ArbitrumConfig {
+        match self {
+            Self::ArbitrumOne => ArbitrumConfig::arbitrum_one(),
+            Self::ArbitrumNova => ArbitrumConfig::arbitrum_nova(),
+            Self::ArbitrumSepolia => ArbitrumConfig::arbitrum_sepolia(),
+        }
+    }
+}
 
 /// Anvil with Arbitrum precompile support and 0x7e transaction parsing
-#[derive(Parser, Debug)]
+#[derive(Parser, Debug, Clone)]
 #[command(
     name = "anvil-arbitrum",
     about = "Anvil with Arbitrum precompile support and 0x7e transaction parsing",
@@ -14,18 +50,30 @@ pub struct AnvilArbitrumArgs {
     #[arg(long = "arbitrum", default_value = "false")]
     pub arbitrum: bool,
 
-    /// Arbitrum chain ID
-    #[arg(long = "arb-chain-id", default_value = "42161")]
+    /// Arbitrum chain ID [default: 42161]
+    #[arg(long = "arb-chain-id")]
     pub chain_id: Option<u64>,
 
-    /// ArbOS version
-    #[arg(long = "arb-os-version", default_value = "20")]
+    /// Start from a named chain's preset instead of the Arbitrum One defaults
+    #[arg(long = "chain-preset", value_enum, conflicts_with = "config_file")]
+    pub chain_preset: Option<ChainPreset>,
+
+    /// ArbOS version [default: 20]
+    #[arg(long = "arb-os-version")]
     pub arb_os_version: Option<u32>,
 
-    /// L1 base fee in wei
-    #[arg(long = "l1-base-fee", default_value = "20000000000")]
+    /// L1 base fee in wei [default: 20000000000]
+    #[arg(long = "l1-base-fee")]
     pub l1_base_fee: Option<u64>,
 
+    /// Load the Arbitrum configuration from a JSON or TOML file
+    #[arg(long = "config-file")]
+    pub config_file: Option<String>,
+
+    /// Reload --config-file into the RPC server whenever it changes
+    #[arg(long = "watch-config", default_value = "false", requires = "config_file")]
+    pub watch_config: bool,
+
     /// Gas price configuration (JSON string)
     #[arg(long = "gas-config")]
     pub gas_config: Option<String>,
@@ -38,6 +86,54 @@ pub struct AnvilArbitrumArgs {
     #[arg(long = "mock-l1-bridge", default_value = "false")]
     pub mock_l1_bridge: bool,
 
+    /// Spawn the upstream `anvil` binary (from `ANVIL_PATH` or `PATH`) with the forwarded arguments
+    #[arg(long = "spawn-anvil", default_value = "false")]
+    pub spawn_anvil: bool,
+
+    /// Serve JSON-RPC on --host/--port, handling 0x7e transactions locally
+    #[arg(long = "rpc", default_value = "false")]
+    pub rpc: bool,
+
+    /// Upstream Anvil URL that the RPC server forwards other requests to
+    #[arg(long = "upstream-url")]
+    pub upstream_url: Option<String>,
+
+    /// Serve Prometheus metrics at /metrics on the RPC server
+    #[arg(long = "metrics", default_value = "false")]
+    pub metrics: bool,
+
+    /// Register only these precompiles (comma-separated names, e.g. arbsys,arbretryabletx)
+    #[arg(long = "precompiles", value_delimiter = ',')]
+    pub precompiles: Option<Vec<String>>,
+
+    /// Do not register these precompiles (comma-separated names, e.g. arbgasinfo)
+    #[arg(long = "disable-precompiles", value_delimiter = ',')]
+    pub disable_precompiles: Vec<String>,
+
+    /// Register ArbDebug and enable its functions
+    #[arg(long = "allow-debug", default_value = "false")]
+    pub allow_debug: bool,
+
+    /// Print the registered precompiles and their selectors, then exit
+    #[arg(long = "list-precompiles", default_value = "false")]
+    pub list_precompiles: bool,
+
+    /// Seed the mock chain's starting block number and timestamp for reproducible runs
+    #[arg(long = "seed")]
+    pub seed: Option<u64>,
+
+    /// Print a JSON ABI for every registered precompile, then exit
+    #[arg(long = "dump-abi", default_value = "false")]
+    pub dump_abi: bool,
+
+    /// Print the fully resolved Arbitrum configuration as JSON, then exit
+    #[arg(long = "dump-config", default_value = "false")]
+    pub dump_config: bool,
+
+    /// Parse and validate a raw 0x7e transaction (a hex file or hex string), print it, then exit
+    #[arg(long = "dry-run", value_name = "HEXFILE_OR_HEX")]
+    pub dry_run: Option<String>,
+
     // Standard Anvil arguments (forwarded)
     /// Host to bind to
     #[arg(long = "host", default_value = "127.0.0.1")]
@@ -55,9 +151,9 @@ pub struct AnvilArbitrumArgs {
     #[arg(long = "balance", default_value = "10000")]
     pub balance: u64,
 
-    /// Gas limit
-    #[arg(long = "gas-limit", default_value = "30000000")]
-    pub gas_limit: u64,
+    /// Block gas limit [default: 30000000]
+    #[arg(long = "gas-limit")]
+    pub gas_limit: Option<u64>,
 
     /// Gas price
     #[arg(long = "gas-price", default_value = "20000000000")]
@@ -67,9 +163,9 @@ pub struct AnvilArbitrumArgs {
     #[arg(long = "block-time", default_value = "0")]
     pub block_time: u64,
 
-    /// Chain ID
-    #[arg(long = "chain-id", default_value = "31337")]
-    pub anvil_chain_id: u64,
+    /// Chain ID forwarded to Anvil [default: the Arbitrum chain ID with --arbitrum, otherwise 31337]
+    #[arg(long = "chain-id")]
+    pub anvil_chain_id: Option<u64>,
 
     /// Base fee
     #[arg(long = "base-fee", default_value = "1000000000")]
@@ -79,7 +175,7 @@ pub struct AnvilArbitrumArgs {
     #[arg(long = "timestamp")]
     pub timestamp: Option<u64>,
 
-    /// Fork from a remote endpoint
+    /// Fork from a remote endpoint, seeding the L1 base fee from its latest block
     #[arg(long = "fork")]
     pub fork: Option<String>,
 
@@ -99,12 +195,155 @@ pub struct AnvilArbitrumArgs {
     #[arg(long = "verbose", default_value = "false")]
     pub verbose: bool,
 
+    /// Log line format: human-readable text or one JSON object per line
+    #[arg(long = "log-format", value_enum, default_value = "text")]
+    pub log_format: LogFormat,
+
     /// Show help
     #[arg(long = "help", short = 'h')]
     pub help: bool,
 }
 
 impl AnvilArbitrumArgs {
+    /// Build the Arbitrum configuration from the flags and `OX_*` environment variables
+    pub fn arbitrum_config(&self) -> Result<ArbitrumConfig> {
+        self.arbitrum_config_from(|name| std::env::var(name).ok())
+    }
+
+    /// Build the Arbitrum configuration, resolving `OX_*` variables through `lookup`
+    ///
+    /// Later sources win: `--config-file` or `--chain-preset`, then environment variables,
+    /// then individual flags, then `--gas-config`. The result is validated before it is returned.
+    pub fn arbitrum_config_from<F>(&self, lookup: F) -> Result<ArbitrumConfig>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        self.arbitrum_config_with_fork_base_fee_from(lookup, None)
+    }
+
+    /// Build the Arbitrum configuration, seeding the L1 base fee from `--fork`
+    ///
+    /// When forking without an explicit `--l1-base-fee`, the base fee of the
+    /// forked chain's block (`--fork-block-number`, or the latest) is fetched
+    /// from the fork URL.
+    pub async fn arbitrum_config_with_fork(&self) -> Result<ArbitrumConfig> {
+        let fork_base_fee = match (&self.fork, self.l1_base_fee) {
+            (Some(url), None) => Some(
+                rpc::fetch_block_base_fee(url, self.fork_block_number)
+                    .await
+                    .map_err(|e| anyhow!("Failed to fetch L1 base fee from fork '{}': {}", url, e))?,
+            ),
+            _ => None,
+        };
+        self.arbitrum_config_with_fork_base_fee_from(|name| std::env::var(name).ok(), fork_base_fee)
+    }
+
+    /// Build the Arbitrum configuration with an L1 base fee taken from the forked chain
+    ///
+    /// The fork's base fee replaces the one from `--config-file` or the default,
+    /// but `OX_L1_BASE_FEE` and `--l1-base-fee` still win.
+    pub fn arbitrum_config_with_fork_base_fee_from<F>(
+        &self,
+        lookup: F,
+        fork_base_fee: Option<u64>,
+    ) -> Result<ArbitrumConfig>
+    where
+        F: Fn(&str) -> Option<String>,
+    {
+        let mut config = match &self.config_file {
+            Some(path) => ArbitrumConfig::from_path(path)
+                .map_err(|e| anyhow!("Failed to load config file '{}': {}", path, e))?,
+            None => self.chain_preset.map(ChainPreset::config).unwrap_or_default(),
+        };
+
+        if let Some(l1_base_fee) = fork_base_fee {
+            config.l1_base_fee = l1_base_fee;
+        }
+        config.apply_overrides_from(lookup)?;
+
+        if let Some(chain_id) = self.chain_id {
+            config.chain_id = chain_id;
+        }
+        if let Some(arb_os_version) = self.arb_os_version {
+            config.arb_os_version = arb_os_version;
+        }
+        if let Some(l1_base_fee) = self.l1_base_fee {
+            config.l1_base_fee = l1_base_fee;
+        }
+        if let Some(gas_limit) = self.gas_limit {
+            config.l2_block_gas_limit = gas_limit;
+        }
+        if self.allow_debug {
+            config.allow_debug_precompiles = true;
+        }
+        if let Some(names) = &self.precompiles {
+            config
+                .enable_only_precompiles(names)
+                .map_err(|e| anyhow!("Invalid --precompiles: {}", e))?;
+        }
+        config
+            .disable_precompiles(&self.disable_precompiles)
+            .map_err(|e| anyhow!("Invalid --disable-precompiles: {}", e))?;
+
+        if let Some(gas_config) = &self.gas_config {
+            let partial: PartialGasPriceComponents = serde_json::from_str(gas_config)
+                .map_err(|e| anyhow!("Invalid --gas-config JSON: {}", e))?;
+            config.gas_price_components.merge(partial);
+        }
+
+        config
+            .validate()
+            .map_err(|e| anyhow!("Invalid Arbitrum configuration: {}", e))?;
+        Ok(config)
+    }
+
+    /// Arbitrum chain ID from `--arb-chain-id`, then `--chain-preset`, then the default
+    pub fn arbitrum_chain_id(&self) -> u64 {
+        self.chain_id
+            .or_else(|| self.chain_preset.map(|preset| preset.config().chain_id))
+            .unwrap_or(DEFAULT_ARBITRUM_CHAIN_ID)
+    }
+
+    /// Chain ID forwarded to Anvil
+    ///
+    /// An explicit `--chain-id` always wins. Otherwise Arbitrum mode uses the
+    /// Arbitrum chain ID so both layers agree, and plain mode uses Anvil's 31337.
+    pub fn effective_anvil_chain_id(&self) -> u64 {
+        match self.anvil_chain_id {
+            Some(chain_id) => chain_id,
+            None if self.arbitrum => self.arbitrum_chain_id(),
+            None => DEFAULT_ANVIL_CHAIN_ID,
+        }
+    }
+
+    /// Warning to show when `--chain-id` and the Arbitrum chain ID disagree in Arbitrum mode
+    pub fn chain_id_conflict(&self) -> Option<String> {
+        let anvil_chain_id = self.anvil_chain_id?;
+        let arbitrum_chain_id = self.arbitrum_chain_id();
+        (self.arbitrum && anvil_chain_id != arbitrum_chain_id).then(|| {
+            format!(
+                "--chain-id {} differs from the Arbitrum chain ID {}; Anvil and ArbSys.arbChainID() will disagree",
+                anvil_chain_id, arbitrum_chain_id
+            )
+        })
+    }
+
+    /// Log level implied by `--silent` and `--verbose` (`--silent` wins if both are set)
+    pub fn log_level(&self) -> LevelFilter {
+        if self.silent {
+            LevelFilter::ERROR
+        } else if self.verbose {
+            LevelFilter::DEBUG
+        } else {
+            LevelFilter::INFO
+        }
+    }
+
+    /// Anvil's dev accounts for `--accounts` and `--balance`, for running without Anvil
+    pub fn dev_accounts(&self) -> Result<Vec<DevAccount>, AccountError> {
+        accounts::dev_accounts(self.accounts, self.balance)
+    }
+
     /// Get the standard Anvil arguments as a vector
     pub fn get_anvil_args(&self) -> Vec<String> {
         let mut args = Vec::new();
@@ -113,10 +352,15 @@ impl AnvilArbitrumArgs {
         args.push(format!("--port={}", self.port));
         args.push(format!("--accounts={}", self.accounts));
         args.push(format!("--balance={}", self.balance));
-        args.push(format!("--gas-limit={}", self.gas_limit));
+        if let Some(gas_limit) = self.gas_limit {
+            args.push(format!("--gas-limit={}", gas_limit));
+        }
         args.push(format!("--gas-price={}", self.gas_price));
-        args.push(format!("--block-time={}", self.block_time));
-        args.push(format!("--chain-id={}", self.anvil_chain_id));
+        // Anvil rejects a zero block time; omitting it keeps instant mining
+        if self.block_time > 0 {
+            args.push(format!("--block-time={}", self.block_time));
+        }
+        args.push(format!("--chain-id={}", self.effective_anvil_chain_id()));
         args.push(format!("--base-fee={}", self.base_fee));
         
         if let Some(timestamp) = self.timestamp {
@@ -146,3 +390,325 @@ impl AnvilArbitrumArgs {
         args
     }
 }
+
+/// A transaction decoded by `--dry-run` and its validation result
+#[derive(Debug)]
+pub struct DryRunReport {
+    pub transaction: Tx7eTransaction,
+    pub validation: TransactionValidation,
+}
+
+impl DryRunReport {
+    /// The decoded transaction (as `Tx7eTransaction::to_rpc_json`) with its validation errors
+    pub fn to_json(&self) -> Value {
+        json!({
+            "transaction": self.transaction.to_rpc_json(),
+            "valid": self.validation.is_valid,
+            "errors": self.validation.errors.iter().map(|e| e.to_string()).collect::<Vec<_>>(),
+        })
+    }
+}
+
+/// Decode and validate a raw 0x7e transaction for `--dry-run`
+///
+/// `input` is the path of a file holding the hex-encoded transaction, or the
+/// hex itself; either may carry a `0x` prefix and surrounding whitespace.
+pub fn dry_run(input: &str, config: &ArbitrumConfig) -> Result<DryRunReport> {
+    let hex_input = if std::path::Path::new(input).is_file() {
+        std::fs::read_to_string(input).map_err(|e| anyhow!("Failed to read '{}': {}", input, e))?
+    } else {
+        input.to_string()
+    };
+    let hex_input = hex_input.trim();
+    let raw_tx = hex::decode(hex_input.strip_prefix("0x").unwrap_or(hex_input))
+        .map_err(|e| anyhow!("Invalid hex transaction: {}", e))?;
+
+    let parser = Tx7eParser::from_config(config);
+    let transaction = parser.parse(&raw_tx).map_err(|e| anyhow!("Parsing failed: {}", e))?;
+    let validation = parser.validate_transaction(&transaction);
+    Ok(DryRunReport { transaction, validation })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrum::DEFAULT_L2_BLOCK_GAS_LIMIT;
+    use axum::routing::post;
+    use axum::{Json, Router};
+    use serde_json::{json, Value};
+    use std::sync::{Arc, Mutex};
+    use tokio::net::TcpListener;
+
+    fn parse(args: &[&str]) -> AnvilArbitrumArgs {
+        AnvilArbitrumArgs::try_parse_from(std::iter::once("anvil-arbitrum").chain(args.iter().copied())).unwrap()
+    }
+
+    #[test]
+    fn test_gas_config_applied() {
+        let args = parse(&[
+            "--arbitrum",
+            "--gas-config",
+            r#"{"l2_base_fee":2000000000,"congestion_fee":5}"#,
+        ]);
+        let config = args.arbitrum_config_from(|_| None).unwrap();
+        assert_eq!(config.gas_price_components.l2_base_fee, 2_000_000_000);
+        assert_eq!(config.gas_price_components.congestion_fee, 5);
+        assert_eq!(config.gas_price_components.l1_calldata_cost, 16);
+    }
+
+    #[test]
+    fn test_chain_preset_seeds_config() {
+        let args = parse(&["--arbitrum", "--chain-preset", "arbitrum-nova"]);
+        let config = args.arbitrum_config_from(|_| None).unwrap();
+        assert_eq!(config.chain_id, 42170);
+        assert_eq!(config, ArbitrumConfig::arbitrum_nova());
+        assert_eq!(args.effective_anvil_chain_id(), 42170);
+
+        // Individual flags still apply on top of the preset
+        let config = parse(&["--chain-preset", "arbitrum-sepolia", "--l1-base-fee", "7"])
+            .arbitrum_config_from(|_| None)
+            .unwrap();
+        assert_eq!(config.chain_id, 421614);
+        assert_eq!(config.l1_base_fee, 7);
+
+        let err = AnvilArbitrumArgs::try_parse_from(["anvil-arbitrum", "--chain-preset", "arbitrum-classic"])
+            .unwrap_err()
+            .to_string();
+        assert!(err.contains("arbitrum-one, arbitrum-nova, arbitrum-sepolia"), "{}", err);
+    }
+
+    #[test]
+    fn test_precompile_selection_flags() {
+        let config = parse(&["--precompiles", "arbsys,ArbGasInfo", "--disable-precompiles", "arbgasinfo"])
+            .arbitrum_config_from(|_| None)
+            .unwrap();
+        assert!(config.is_precompile_enabled("0x0000000000000000000000000000000000000064"));
+        assert!(!config.is_precompile_enabled("0x000000000000000000000000000000000000006c"));
+        assert!(!config.is_precompile_enabled("0x000000000000000000000000000000000000006e"));
+
+        let err = parse(&["--disable-precompiles", "arbfoo"])
+            .arbitrum_config_from(|_| None)
+            .unwrap_err();
+        assert!(err.to_string().contains("Unknown precompile 'arbfoo'"));
+    }
+
+    #[test]
+    fn test_gas_config_overrides_env() {
+        let args = parse(&["--gas-config", r#"{"l2_base_fee":3}"#]);
+        let config = args
+            .arbitrum_config_from(|name| (name == "OX_L2_BASE_FEE").then(|| "7".to_string()))
+            .unwrap();
+        assert_eq!(config.gas_price_components.l2_base_fee, 3);
+    }
+
+    #[test]
+    fn test_gas_config_malformed() {
+        let err = parse(&["--gas-config", "{l2_base_fee: 1"])
+            .arbitrum_config_from(|_| None)
+            .unwrap_err();
+        assert!(err.to_string().contains("Invalid --gas-config JSON"));
+
+        let err = parse(&["--gas-config", r#"{"l2_basefee":1}"#])
+            .arbitrum_config_from(|_| None)
+            .unwrap_err();
+        assert!(err.to_string().contains("l2_basefee"));
+    }
+
+    #[test]
+    fn test_config_file_with_flag_override() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let mut file_config = ArbitrumConfig::arbitrum_sepolia();
+        file_config.l2_block_gas_limit = 50_000_000;
+        file_config.save_to_file(path.to_str().unwrap()).unwrap();
+
+        let config = parse(&[
+            "--config-file",
+            path.to_str().unwrap(),
+            "--l1-base-fee",
+            "7",
+            "--gas-config",
+            r#"{"congestion_fee":9}"#,
+        ])
+        .arbitrum_config_from(|name| match name {
+            "OX_L1_BASE_FEE" => Some("8".to_string()),
+            "OX_ARB_OS_VERSION" => Some("31".to_string()),
+            _ => None,
+        })
+        .unwrap();
+
+        // Flags win over environment variables, which win over the file
+        assert_eq!(config.chain_id, 421614);
+        assert_eq!(config.l2_block_gas_limit, 50_000_000);
+        assert_eq!(config.l1_base_fee, 7);
+        assert_eq!(config.arb_os_version, 31);
+        assert_eq!(config.gas_price_components.congestion_fee, 9);
+        assert_eq!(
+            config.gas_price_components.l2_base_fee,
+            file_config.gas_price_components.l2_base_fee
+        );
+    }
+
+    #[test]
+    fn test_config_file_errors() {
+        let err = parse(&["--config-file", "/nonexistent/config.json"])
+            .arbitrum_config_from(|_| None)
+            .unwrap_err();
+        assert!(err.to_string().contains("Failed to load config file"));
+
+        let err = parse(&["--l1-base-fee", "0"]).arbitrum_config_from(|_| None).unwrap_err();
+        assert!(err.to_string().contains("Invalid Arbitrum configuration"));
+    }
+
+    #[test]
+    fn test_gas_limit_flag() {
+        let config = parse(&[]).arbitrum_config_from(|_| None).unwrap();
+        assert_eq!(config.l2_block_gas_limit, DEFAULT_L2_BLOCK_GAS_LIMIT);
+
+        let args = parse(&["--gas-limit", "1000000"]);
+        assert_eq!(args.arbitrum_config_from(|_| None).unwrap().l2_block_gas_limit, 1_000_000);
+        assert!(args.get_anvil_args().contains(&"--gas-limit=1000000".to_string()));
+        assert!(!parse(&[]).get_anvil_args().iter().any(|arg| arg.starts_with("--gas-limit")));
+    }
+
+    #[test]
+    fn test_dumped_config_round_trips() {
+        let config = parse(&["--arb-chain-id", "421614", "--gas-config", r#"{"congestion_fee":3}"#])
+            .arbitrum_config_from(|name| (name == "OX_ARB_OS_VERSION").then(|| "31".to_string()))
+            .unwrap();
+
+        let dumped = config.to_json_pretty().unwrap();
+        let parsed: ArbitrumConfig = serde_json::from_str(&dumped).unwrap();
+        assert_eq!(parsed, config);
+        assert_eq!(parsed.arb_os_version, 31);
+    }
+
+    #[test]
+    fn test_anvil_chain_id_defaults() {
+        let chain_id_arg = |args: &AnvilArbitrumArgs| {
+            args.get_anvil_args()
+                .into_iter()
+                .find(|arg| arg.starts_with("--chain-id="))
+                .unwrap()
+        };
+
+        assert_eq!(chain_id_arg(&parse(&[])), "--chain-id=31337");
+        assert_eq!(chain_id_arg(&parse(&["--arbitrum"])), "--chain-id=42161");
+        assert_eq!(
+            chain_id_arg(&parse(&["--arbitrum", "--arb-chain-id", "421614"])),
+            "--chain-id=421614"
+        );
+        assert_eq!(chain_id_arg(&parse(&["--arbitrum", "--chain-id", "1337"])), "--chain-id=1337");
+    }
+
+    #[test]
+    fn test_chain_id_conflict_warning() {
+        assert!(parse(&["--arbitrum"]).chain_id_conflict().is_none());
+        assert!(parse(&["--arbitrum", "--chain-id", "42161"]).chain_id_conflict().is_none());
+        assert!(parse(&["--chain-id", "1337"]).chain_id_conflict().is_none());
+
+        let warning = parse(&["--arbitrum", "--chain-id", "1337"]).chain_id_conflict().unwrap();
+        assert!(warning.contains("1337"));
+        assert!(warning.contains("42161"));
+    }
+
+    /// Serve `eth_getBlockByNumber` with a fixed base fee, recording the requested block tags
+    async fn spawn_fork_endpoint(base_fee: &'static str) -> (String, Arc<Mutex<Vec<Value>>>) {
+        let requested = Arc::new(Mutex::new(Vec::new()));
+        let seen = requested.clone();
+        let app = Router::new().route(
+            "/",
+            post(move |Json(request): Json<Value>| {
+                let seen = seen.clone();
+                async move {
+                    seen.lock().unwrap().push(request["params"][0].clone());
+                    Json(json!({ "jsonrpc": "2.0", "id": 1, "result": { "baseFeePerGas": base_fee } }))
+                }
+            }),
+        );
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move { axum::serve(listener, app).await });
+        (url, requested)
+    }
+
+    #[tokio::test]
+    async fn test_fork_seeds_l1_base_fee() {
+        let (url, requested) = spawn_fork_endpoint("0x3b9aca07").await;
+
+        let config = parse(&["--fork", &url]).arbitrum_config_with_fork().await.unwrap();
+        assert_eq!(config.l1_base_fee, 1_000_000_007);
+
+        parse(&["--fork", &url, "--fork-block-number", "255"])
+            .arbitrum_config_with_fork()
+            .await
+            .unwrap();
+        assert_eq!(*requested.lock().unwrap(), vec![json!("latest"), json!("0xff")]);
+
+        // An explicit --l1-base-fee wins and the fork is not queried
+        let config = parse(&["--fork", &url, "--l1-base-fee", "7"])
+            .arbitrum_config_with_fork()
+            .await
+            .unwrap();
+        assert_eq!(config.l1_base_fee, 7);
+        assert_eq!(requested.lock().unwrap().len(), 2);
+
+        // OX_L1_BASE_FEE also overrides the forked value
+        let config = parse(&["--fork", &url])
+            .arbitrum_config_with_fork_base_fee_from(
+                |name| (name == "OX_L1_BASE_FEE").then(|| "9".to_string()),
+                Some(1_000_000_007),
+            )
+            .unwrap();
+        assert_eq!(config.l1_base_fee, 9);
+    }
+
+    #[tokio::test]
+    async fn test_fork_without_base_fee_fails() {
+        let (url, _) = spawn_fork_endpoint("").await;
+        let err = parse(&["--fork", &url]).arbitrum_config_with_fork().await.unwrap_err();
+        assert!(err.to_string().contains("Failed to fetch L1 base fee from fork"));
+    }
+
+    #[test]
+    fn test_dry_run() {
+        let config = ArbitrumConfig::default();
+        let tx = crate::tx7e::mock_deposit([1u8; 32]).build().unwrap();
+        let raw_hex = format!("0x{}", hex::encode(tx.rlp_encode_typed()));
+
+        // A known-good transaction, read from a file
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("tx.hex");
+        std::fs::write(&path, format!("{}\n", raw_hex)).unwrap();
+        let args = parse(&["--dry-run", path.to_str().unwrap()]);
+        let report = dry_run(args.dry_run.as_deref().unwrap(), &config).unwrap();
+        assert_eq!(report.transaction, tx);
+        let json = report.to_json();
+        assert_eq!(json["valid"], true);
+        assert_eq!(json["errors"], json!([]));
+        assert_eq!(json["transaction"], tx.to_rpc_json());
+
+        // The same transaction passed inline, with a zero L1 block number, fails validation
+        let mut invalid = tx.clone();
+        invalid.l1_block_number = 0;
+        let report = dry_run(&hex::encode(invalid.rlp_encode_typed()), &config).unwrap();
+        assert!(!report.validation.is_valid);
+        assert_eq!(report.to_json()["errors"], json!(["Invalid L1 block number: cannot be zero"]));
+
+        // Malformed input does not decode
+        let err = dry_run("0x7ezz", &config).unwrap_err();
+        assert!(err.to_string().starts_with("Invalid hex transaction"), "{}", err);
+        let err = dry_run(&format!("0x02{}", hex::encode(tx.rlp_encode())), &config).unwrap_err();
+        assert_eq!(err.to_string(), "Parsing failed: Invalid transaction type: expected 0x7e, got 0x02");
+    }
+
+    #[test]
+    fn test_log_level() {
+        assert_eq!(parse(&[]).log_level(), LevelFilter::INFO);
+        assert_eq!(parse(&["--silent"]).log_level(), LevelFilter::ERROR);
+        assert_eq!(parse(&["--verbose"]).log_level(), LevelFilter::DEBUG);
+        assert_eq!(parse(&[]).log_format, LogFormat::Text);
+        assert_eq!(parse(&["--log-format", "json"]).log_format, LogFormat::Json);
+        assert_eq!(parse(&["--silent", "--verbose"]).log_level(), LevelFilter::ERROR);
+    }
+}
@@ -0,0 +1,132 @@
+//! Calldata compression models for L1 cost estimation
+//!
+//! Arbitrum posts batches to L1 Brotli-compressed, so the L1 cost of a transaction
+//! depends on its compressed size rather than its raw calldata length.
+
+use serde::{Deserialize, Serialize};
+
+/// Brotli quality used for estimation (Nitro prices calldata at a fast level)
+#[cfg(feature = "brotli")]
+pub const BROTLI_ESTIMATION_QUALITY: u32 = 1;
+
+/// Estimates the number of bytes calldata occupies once posted to L1
+pub trait CompressionEstimator {
+    /// Estimate the compressed length of `data` in bytes
+    fn estimate_compressed_len(&self, data: &[u8]) -> usize;
+}
+
+/// No compression: the estimate is the raw length
+#[derive(Debug, Clone, Copy, Default)]
+pub struct IdentityCompression;
+
+impl CompressionEstimator for IdentityCompression {
+    fn estimate_compressed_len(&self, data: &[u8]) -> usize {
+        data.len()
+    }
+}
+
+/// Estimate by actually Brotli-compressing the data
+#[cfg(feature = "brotli")]
+#[derive(Debug, Clone, Copy)]
+pub struct BrotliCompression {
+    quality: u32,
+}
+
+#[cfg(feature = "brotli")]
+impl BrotliCompression {
+    pub fn new(quality: u32) -> Self {
+        Self { quality }
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl Default for BrotliCompression {
+    fn default() -> Self {
+        Self::new(BROTLI_ESTIMATION_QUALITY)
+    }
+}
+
+#[cfg(feature = "brotli")]
+impl CompressionEstimator for BrotliCompression {
+    fn estimate_compressed_len(&self, data: &[u8]) -> usize {
+        use std::io::Write;
+
+        if data.is_empty() {
+            return 0;
+        }
+
+        let mut compressed = Vec::new();
+        {
+            let mut writer = brotli::CompressorWriter::new(&mut compressed, 4096, self.quality, 22);
+            // Writing into a Vec cannot fail; fall back to the raw length if it somehow does
+            if writer.write_all(data).is_err() {
+                return data.len();
+            }
+        }
+        compressed.len()
+    }
+}
+
+/// Compression model selected in the configuration
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(rename_all = "lowercase")]
+pub enum CompressionModel {
+    /// Price raw calldata length
+    #[default]
+    None,
+    /// Price Brotli-compressed length (requires the `brotli` feature)
+    Brotli,
+}
+
+impl CompressionModel {
+    /// Estimate the compressed length of `data` under this model
+    ///
+    /// Without the `brotli` feature, `Brotli` falls back to the raw length.
+    pub fn estimate_compressed_len(&self, data: &[u8]) -> usize {
+        match self {
+            CompressionModel::None => IdentityCompression.estimate_compressed_len(data),
+            #[cfg(feature = "brotli")]
+            CompressionModel::Brotli => BrotliCompression::default().estimate_compressed_len(data),
+            #[cfg(not(feature = "brotli"))]
+            CompressionModel::Brotli => IdentityCompression.estimate_compressed_len(data),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Deterministic pseudo-random bytes (incompressible in practice)
+    fn random_bytes(len: usize) -> Vec<u8> {
+        let mut state: u64 = 0x9e37_79b9_7f4a_7c15;
+        (0..len)
+            .map(|_| {
+                state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                (state >> 33) as u8
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_identity_compression() {
+        assert_eq!(IdentityCompression.estimate_compressed_len(&[0u8; 100]), 100);
+        assert_eq!(CompressionModel::None.estimate_compressed_len(&random_bytes(100)), 100);
+    }
+
+    #[cfg(feature = "brotli")]
+    #[test]
+    fn test_brotli_compressible_vs_random() {
+        let estimator = BrotliCompression::default();
+
+        let compressible: Vec<u8> = b"transfer(address,uint256)".iter().cycle().take(4096).copied().collect();
+        let random = random_bytes(4096);
+
+        let compressible_len = estimator.estimate_compressed_len(&compressible);
+        let random_len = estimator.estimate_compressed_len(&random);
+
+        assert!(compressible_len < compressible.len() / 10);
+        assert!(random_len >= random.len() * 9 / 10);
+        assert_eq!(estimator.estimate_compressed_len(&[]), 0);
+    }
+}
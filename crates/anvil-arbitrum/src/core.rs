@@ -0,0 +1,437 @@
+//! Transaction types and primitives that build without `std`
+//!
+//! Everything here needs only `alloc`, so the deposit and submit-retryable
+//! transaction types, their RLP encoding and hashing can be used from `no_std`
+//! targets. Parsing limits, validation, JSON conversion and execution live in
+//! [`crate::tx7e`], which re-exports these types.
+
+pub use crate::primitives::{Address, HexBytes, U256};
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt, U256Ext};
+use alloc::vec;
+use alloc::vec::Vec;
+use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+use sha3::{Digest, Keccak256};
+
+/// Transaction type for Arbitrum deposit transactions
+pub const TX_TYPE_0X7E: u8 = 0x7e;
+
+/// Inner discriminator for submit-retryable transactions (`0x7e || 0x69 || rlp`)
+pub const TX_SUBTYPE_SUBMIT_RETRYABLE: u8 = 0x69;
+
+/// RLP field count of a deposit carrying a single L1 fee
+pub const DEPOSIT_FIELDS_SINGLE_FEE: usize = 13;
+/// RLP field count of a deposit that also carries the poster/network fee split
+pub const DEPOSIT_FIELDS_SPLIT_FEE: usize = 15;
+
+/// Base intrinsic gas for any transaction
+pub const TX_BASE_GAS: u64 = 21000;
+/// Intrinsic gas per zero calldata byte
+pub const TX_DATA_ZERO_GAS: u64 = 4;
+/// Intrinsic gas per non-zero calldata byte (EIP-2028)
+pub const TX_DATA_NON_ZERO_GAS: u64 = 16;
+
+/// Intrinsic gas charged for calldata: 4 per zero byte and 16 per non-zero byte
+pub fn calldata_gas(data: &[u8]) -> u64 {
+    let zero_bytes = data.iter().filter(|&&b| b == 0).count() as u64;
+    let non_zero_bytes = data.len() as u64 - zero_bytes;
+    zero_bytes * TX_DATA_ZERO_GAS + non_zero_bytes * TX_DATA_NON_ZERO_GAS
+}
+
+/// Arbitrum deposit transaction (0x7e)
+#[derive(Clone, PartialEq)]
+pub struct Tx7eTransaction {
+    /// Chain ID
+    pub chain_id: u64,
+    /// Target address
+    pub target: Address,
+    /// Value in wei
+    pub value: U256,
+    /// Calldata
+    pub data: Vec<u8>,
+    /// Gas limit
+    pub gas_limit: u64,
+    /// L1 block number
+    pub l1_block_number: u64,
+    /// L1 timestamp
+    pub l1_timestamp: u64,
+    /// L1 base fee
+    pub l1_base_fee: U256,
+    /// L1 gas price
+    pub l1_gas_price: U256,
+    /// L1 gas used
+    pub l1_gas_used: u64,
+    /// L1 fee, the sum of the poster and network fees when those are set
+    pub l1_fee: U256,
+    /// Refund address
+    pub refund_address: Address,
+    /// Source hash
+    pub source_hash: [u8; 32],
+    /// Share of the L1 fee paid to the batch poster
+    pub l1_poster_fee: Option<U256>,
+    /// Share of the L1 fee paid to the network fee account
+    pub l1_network_fee: Option<U256>,
+}
+
+impl ::core::fmt::Debug for Tx7eTransaction {
+    fn fmt(&self, f: &mut ::core::fmt::Formatter<'_>) -> ::core::fmt::Result {
+        f.debug_struct("Tx7eTransaction")
+            .field("chain_id", &self.chain_id)
+            .field("target", &self.target)
+            .field("value", &self.value)
+            .field("data", &HexBytes(&self.data))
+            .field("gas_limit", &self.gas_limit)
+            .field("l1_block_number", &self.l1_block_number)
+            .field("l1_timestamp", &self.l1_timestamp)
+            .field("l1_base_fee", &self.l1_base_fee)
+            .field("l1_gas_price", &self.l1_gas_price)
+            .field("l1_gas_used", &self.l1_gas_used)
+            .field("l1_fee", &self.l1_fee)
+            .field("refund_address", &self.refund_address)
+            .field("source_hash", &HexBytes(self.source_hash))
+            .field("l1_poster_fee", &self.l1_poster_fee)
+            .field("l1_network_fee", &self.l1_network_fee)
+            .finish()
+    }
+}
+
+impl Tx7eTransaction {
+    /// Create a new deposit transaction
+    #[allow(clippy::too_many_arguments)]
+    pub fn new(
+        chain_id: u64,
+        target: Address,
+        value: U256,
+        data: Vec<u8>,
+        gas_limit: u64,
+        l1_block_number: u64,
+        l1_timestamp: u64,
+        l1_base_fee: U256,
+        l1_gas_price: U256,
+        l1_gas_used: u64,
+        l1_fee: U256,
+        refund_address: Address,
+        source_hash: [u8; 32],
+    ) -> Self {
+        Self {
+            chain_id,
+            target,
+            value,
+            data,
+            gas_limit,
+            l1_block_number,
+            l1_timestamp,
+            l1_base_fee,
+            l1_gas_price,
+            l1_gas_used,
+            l1_fee,
+            refund_address,
+            source_hash,
+            l1_poster_fee: None,
+            l1_network_fee: None,
+        }
+    }
+
+    /// Split the L1 fee into poster and network shares, setting `l1_fee` to their sum
+    ///
+    /// Returns `None` if the sum overflows, since no `l1_fee` could match it.
+    pub fn with_l1_fee_split(mut self, poster_fee: U256, network_fee: U256) -> Option<Self> {
+        self.l1_fee = poster_fee.checked_add(network_fee)?;
+        self.l1_poster_fee = Some(poster_fee);
+        self.l1_network_fee = Some(network_fee);
+        Some(self)
+    }
+
+    /// Poster and network shares of the L1 fee, if both are set
+    pub fn l1_fee_split(&self) -> Option<(U256, U256)> {
+        Some((self.l1_poster_fee?, self.l1_network_fee?))
+    }
+
+    /// Get the transaction hash: keccak256 of the typed raw transaction
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.rlp_encode_typed());
+        hasher.finalize().into()
+    }
+
+    /// RLP encode the transaction (inner payload only, without the type byte)
+    ///
+    /// Use `rlp_encode_typed` for the raw transaction that `Tx7eParser::parse`
+    /// and `eth_sendRawTransaction` accept, and which `hash` covers.
+    pub fn rlp_encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new();
+        self.rlp_append(&mut stream);
+        stream.out().to_vec()
+    }
+
+    /// Encode the transaction with its type byte: `0x7e || rlp`
+    pub fn rlp_encode_typed(&self) -> Vec<u8> {
+        let mut raw = vec![TX_TYPE_0X7E];
+        raw.extend_from_slice(&self.rlp_encode());
+        raw
+    }
+
+    /// Intrinsic gas of the transaction
+    ///
+    /// The base cost plus per-byte calldata costs (zero and non-zero bytes are
+    /// priced differently). Transferring value adds nothing. Not capped by
+    /// `gas_limit`.
+    pub fn intrinsic_gas(&self) -> u64 {
+        TX_BASE_GAS + calldata_gas(&self.data)
+    }
+
+    /// Get the total L1 cost
+    pub fn total_l1_cost(&self) -> U256 {
+        self.l1_fee
+    }
+
+    /// Get the effective gas price
+    pub fn effective_gas_price(&self) -> U256 {
+        if self.l1_gas_used == 0 {
+            return U256::zero();
+        }
+        
+        let _total_cost = self.l1_fee;
+        let gas_used = U256::from_u64(self.l1_gas_used);
+        
+        // Simple division (in a real implementation, this would be more sophisticated)
+        if gas_used == U256::zero() {
+            U256::zero()
+        } else {
+            // For simplicity, return the L1 base fee
+            self.l1_base_fee
+        }
+    }
+
+    /// Get the balance credit applied when the deposit succeeds: `value` goes to `target`
+    pub fn credit_target(&self) -> (Address, U256) {
+        (self.target, self.value)
+    }
+
+    /// Get the balance credit applied when the deposit fails: `value` goes to `refund_address`
+    pub fn refund_recipient(&self) -> (Address, U256) {
+        (self.refund_address, self.value)
+    }
+
+    /// Mock request id of the deposit: `keccak256(source_hash || uint256(chain_id))`
+    ///
+    /// This is not Nitro's request id, which comes from the L1 delayed inbox
+    /// message sequence number that deposits here do not carry. Do not compare
+    /// it with ids reported by a Nitro node. It only gives each deposit a
+    /// stable, per-chain identifier that survives RLP round-trips.
+    pub fn request_id(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.source_hash);
+        hasher.update(U256::from_u64(self.chain_id).to_big_endian());
+        hasher.finalize().into()
+    }
+
+    /// Whether `other` is the same deposit: equal source hash, chain ID, target, value and data
+    ///
+    /// Unlike `==`, the gas limit, L1 pricing fields and refund address are
+    /// ignored, since they can be re-derived differently for one L1 message.
+    pub fn same_deposit(&self, other: &Self) -> bool {
+        self.source_hash == other.source_hash
+            && self.chain_id == other.chain_id
+            && self.target == other.target
+            && self.value == other.value
+            && self.data == other.data
+    }
+}
+
+// Deposits without a fee split keep the original 13-field layout, so they
+// encode and hash exactly as before; a split appends the poster and network
+// fees as fields 13 and 14.
+impl Encodable for Tx7eTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        let split = self.l1_fee_split();
+        s.begin_list(if split.is_some() { DEPOSIT_FIELDS_SPLIT_FEE } else { DEPOSIT_FIELDS_SINGLE_FEE });
+        s.append(&self.chain_id);
+        s.append(&self.target.as_bytes().to_vec());
+        s.append(&self.value.to_big_endian_minimal());
+        s.append(&self.data);
+        s.append(&self.gas_limit);
+        s.append(&self.l1_block_number);
+        s.append(&self.l1_timestamp);
+        s.append(&self.l1_base_fee.to_big_endian_minimal());
+        s.append(&self.l1_gas_price.to_big_endian_minimal());
+        s.append(&self.l1_gas_used);
+        s.append(&self.l1_fee.to_big_endian_minimal());
+        s.append(&self.refund_address.as_bytes().to_vec());
+        s.append(&self.source_hash.to_vec());
+        if let Some((poster_fee, network_fee)) = split {
+            s.append(&poster_fee.to_big_endian_minimal());
+            s.append(&network_fee.to_big_endian_minimal());
+        }
+    }
+}
+
+impl Decodable for Tx7eTransaction {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        let item_count = rlp.item_count()?;
+        if item_count != DEPOSIT_FIELDS_SINGLE_FEE && item_count != DEPOSIT_FIELDS_SPLIT_FEE {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+        let l1_fee = u256_at(rlp, 10)?;
+        let (l1_poster_fee, l1_network_fee) = if item_count == DEPOSIT_FIELDS_SPLIT_FEE {
+            let poster_fee = u256_at(rlp, 13)?;
+            let network_fee = u256_at(rlp, 14)?;
+            if poster_fee.checked_add(network_fee) != Some(l1_fee) {
+                return Err(DecoderError::Custom("L1 fee is not the sum of the poster and network fees"));
+            }
+            (Some(poster_fee), Some(network_fee))
+        } else {
+            (None, None)
+        };
+
+        // Every conversion below returns a `DecoderError` rather than panicking,
+        // so arbitrary untrusted bytes can be fed through the decoder.
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            target: address_at(rlp, 1, "Invalid target address length")?,
+            value: u256_at(rlp, 2)?,
+            data: rlp.val_at(3)?,
+            gas_limit: rlp.val_at(4)?,
+            l1_block_number: rlp.val_at(5)?,
+            l1_timestamp: rlp.val_at(6)?,
+            l1_base_fee: u256_at(rlp, 7)?,
+            l1_gas_price: u256_at(rlp, 8)?,
+            l1_gas_used: rlp.val_at(9)?,
+            l1_fee,
+            refund_address: address_at(rlp, 11, "Invalid refund address length")?,
+            source_hash: hash_at(rlp, 12, "Invalid source hash length")?,
+            l1_poster_fee,
+            l1_network_fee,
+        })
+    }
+}
+
+/// Arbitrum submit-retryable transaction, carried as `0x7e || 0x69 || rlp`
+#[derive(Debug, Clone, PartialEq)]
+pub struct Tx7eRetryableTransaction {
+    /// Chain ID
+    pub chain_id: u64,
+    /// L1 request ID
+    pub request_id: [u8; 32],
+    /// L1 sender
+    pub from: Address,
+    /// L1 base fee
+    pub l1_base_fee: U256,
+    /// Value deposited to the sender on L2
+    pub deposit_value: U256,
+    /// L2 gas price bid
+    pub gas_fee_cap: U256,
+    /// Gas limit for the auto-redeem
+    pub gas_limit: u64,
+    /// Destination of the retry call
+    pub retry_to: Address,
+    /// Value sent with the retry call
+    pub retry_value: U256,
+    /// Address that receives the ticket's callvalue on cancel or expiry
+    pub beneficiary: Address,
+    /// Maximum submission fee
+    pub max_submission_fee: U256,
+    /// Address that receives excess fees
+    pub fee_refund_address: Address,
+    /// Calldata of the retry call
+    pub retry_data: Vec<u8>,
+}
+
+impl Tx7eRetryableTransaction {
+    /// Get the transaction hash, which also serves as the retryable ticket id
+    pub fn hash(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.rlp_encode_typed());
+        hasher.finalize().into()
+    }
+
+    /// Get the id of the ticket this transaction creates
+    pub fn ticket_id(&self) -> [u8; 32] {
+        self.hash()
+    }
+
+    /// RLP encode the transaction (inner payload only)
+    pub fn rlp_encode(&self) -> Vec<u8> {
+        let mut stream = RlpStream::new();
+        self.rlp_append(&mut stream);
+        stream.out().to_vec()
+    }
+
+    /// Encode the transaction with its type and subtype bytes
+    pub fn rlp_encode_typed(&self) -> Vec<u8> {
+        let mut raw = vec![TX_TYPE_0X7E, TX_SUBTYPE_SUBMIT_RETRYABLE];
+        raw.extend_from_slice(&self.rlp_encode());
+        raw
+    }
+}
+
+impl Encodable for Tx7eRetryableTransaction {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(13);
+        s.append(&self.chain_id);
+        s.append(&self.request_id.to_vec());
+        s.append(&self.from.as_bytes().to_vec());
+        s.append(&self.l1_base_fee.to_big_endian_minimal());
+        s.append(&self.deposit_value.to_big_endian_minimal());
+        s.append(&self.gas_fee_cap.to_big_endian_minimal());
+        s.append(&self.gas_limit);
+        s.append(&self.retry_to.as_bytes().to_vec());
+        s.append(&self.retry_value.to_big_endian_minimal());
+        s.append(&self.beneficiary.as_bytes().to_vec());
+        s.append(&self.max_submission_fee.to_big_endian_minimal());
+        s.append(&self.fee_refund_address.as_bytes().to_vec());
+        s.append(&self.retry_data);
+    }
+}
+
+impl Decodable for Tx7eRetryableTransaction {
+    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
+        if rlp.item_count()? != 13 {
+            return Err(DecoderError::RlpIncorrectListLen);
+        }
+
+        Ok(Self {
+            chain_id: rlp.val_at(0)?,
+            request_id: hash_at(rlp, 1, "Invalid request ID length")?,
+            from: address_at(rlp, 2, "Invalid from address length")?,
+            l1_base_fee: u256_at(rlp, 3)?,
+            deposit_value: u256_at(rlp, 4)?,
+            gas_fee_cap: u256_at(rlp, 5)?,
+            gas_limit: rlp.val_at(6)?,
+            retry_to: address_at(rlp, 7, "Invalid retry-to address length")?,
+            retry_value: u256_at(rlp, 8)?,
+            beneficiary: address_at(rlp, 9, "Invalid beneficiary address length")?,
+            max_submission_fee: u256_at(rlp, 10)?,
+            fee_refund_address: address_at(rlp, 11, "Invalid fee refund address length")?,
+            retry_data: rlp.val_at(12)?,
+        })
+    }
+}
+
+/// Decode a 20-byte address at the given list index
+///
+/// Addresses are RLP byte strings, which are never trimmed, so exactly 20 bytes
+/// are required even when the address has leading zero bytes.
+fn address_at(rlp: &Rlp, index: usize, err: &'static str) -> Result<Address, DecoderError> {
+    let bytes: Vec<u8> = rlp.val_at(index)?;
+    let bytes: [u8; 20] = bytes.try_into().map_err(|_| DecoderError::Custom(err))?;
+    Ok(Address::new(bytes))
+}
+
+/// Decode a 32-byte hash at the given list index
+fn hash_at(rlp: &Rlp, index: usize, err: &'static str) -> Result<[u8; 32], DecoderError> {
+    let bytes: Vec<u8> = rlp.val_at(index)?;
+    bytes.try_into().map_err(|_| DecoderError::Custom(err))
+}
+
+/// Decode a big-endian `U256` at the given list index
+///
+/// Accepts both the minimal integer encoding (leading zeros stripped, so zero is
+/// the empty string) and the zero-padded 32-byte form.
+fn u256_at(rlp: &Rlp, index: usize) -> Result<U256, DecoderError> {
+    let bytes: Vec<u8> = rlp.val_at(index)?;
+    if bytes.len() > 32 {
+        return Err(DecoderError::RlpIsTooBig);
+    }
+    Ok(U256::from_big_endian(&bytes))
+}
@@ -0,0 +1,309 @@
+//! Simulated L2 gas pool driving the congestion fee, remaining storage gas, collected L1 fees
+//! and the smoothed L1 base fee estimate
+
+use crate::arbitrum::{GasPriceComponents, DEFAULT_L1_BASE_FEE_INERTIA, DEFAULT_STORAGE_GAS_AVAILABLE};
+use crate::precompiles::U256;
+#[cfg(feature = "alloy")]
+use crate::primitives::U256Ext;
+use std::sync::{Arc, Mutex};
+
+/// Default gas pool capacity
+pub const DEFAULT_GAS_POOL_MAX: u64 = 32_000_000;
+
+/// Default gas the pool regains per second
+pub const DEFAULT_SPEED_LIMIT_PER_SECOND: u64 = 120_000_000;
+
+/// Storage gas consumed by one new storage slot (the `SSTORE` set cost)
+pub const STORAGE_WRITE_GAS: u64 = 20_000;
+
+#[derive(Debug)]
+struct GasPoolState {
+    max: u64,
+    speed_limit_per_second: u64,
+    available: u64,
+    /// Block timestamp of the last `refill_at`
+    refilled_at: Option<u64>,
+}
+
+impl GasPoolState {
+    fn refill(&mut self, seconds: u64) {
+        let refill = self.speed_limit_per_second.saturating_mul(seconds);
+        self.available = self.available.saturating_add(refill).min(self.max);
+    }
+}
+
+/// Gas pool drained by executed transactions and refilled at the speed limit
+///
+/// The congestion fee grows with the drained fraction of the pool: a full pool
+/// charges only the configured `congestion_fee`, and an empty one adds a full
+/// L2 base fee on top. Cloning the pool shares the underlying state.
+#[derive(Debug, Clone)]
+pub struct GasPool {
+    state: Arc<Mutex<GasPoolState>>,
+}
+
+impl GasPool {
+    /// Create a full pool with the given capacity and refill rate
+    pub fn new(max: u64, speed_limit_per_second: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(GasPoolState {
+                max,
+                speed_limit_per_second,
+                available: max,
+                refilled_at: None,
+            })),
+        }
+    }
+
+    /// Pool capacity
+    pub fn max(&self) -> u64 {
+        self.state.lock().unwrap().max
+    }
+
+    /// Gas the pool regains per second
+    pub fn speed_limit_per_second(&self) -> u64 {
+        self.state.lock().unwrap().speed_limit_per_second
+    }
+
+    /// Gas currently left in the pool
+    pub fn available(&self) -> u64 {
+        self.state.lock().unwrap().available
+    }
+
+    /// Drain `gas` from the pool, stopping at empty
+    pub fn consume(&self, gas: u64) {
+        let mut state = self.state.lock().unwrap();
+        state.available = state.available.saturating_sub(gas);
+    }
+
+    /// Refill the pool for `seconds` of elapsed time, up to its capacity
+    pub fn refill(&self, seconds: u64) {
+        self.state.lock().unwrap().refill(seconds);
+    }
+
+    /// Refill the pool for the time since the previous call, given the current block timestamp
+    ///
+    /// The first call only records the timestamp.
+    pub fn refill_at(&self, timestamp: u64) {
+        let mut state = self.state.lock().unwrap();
+        let previous = state.refilled_at.unwrap_or(timestamp);
+        state.refill(timestamp.saturating_sub(previous));
+        state.refilled_at = Some(previous.max(timestamp));
+    }
+
+    /// Congestion fee in wei for the current pool fill
+    pub fn congestion_fee(&self, gas: &GasPriceComponents) -> u64 {
+        let state = self.state.lock().unwrap();
+        if state.max == 0 {
+            return gas.congestion_fee;
+        }
+        let drained = (state.max - state.available) as u128;
+        let surcharge = gas.effective_l2_base_fee() as u128 * drained / state.max as u128;
+        gas.congestion_fee.saturating_add(surcharge as u64)
+    }
+}
+
+impl Default for GasPool {
+    fn default() -> Self {
+        Self::new(DEFAULT_GAS_POOL_MAX, DEFAULT_SPEED_LIMIT_PER_SECOND)
+    }
+}
+
+/// Storage gas left for the session, reported by `ArbSys.getStorageGasAvailable`
+///
+/// Unlike the gas pool it never refills. Cloning shares the underlying counter.
+#[derive(Debug, Clone)]
+pub struct StorageGas {
+    available: Arc<Mutex<u64>>,
+}
+
+impl StorageGas {
+    /// Start with `available` storage gas
+    pub fn new(available: u64) -> Self {
+        Self {
+            available: Arc::new(Mutex::new(available)),
+        }
+    }
+
+    /// Storage gas left
+    pub fn available(&self) -> u64 {
+        *self.available.lock().unwrap()
+    }
+
+    /// Consume `gas`, stopping at zero
+    pub fn consume(&self, gas: u64) {
+        let mut available = self.available.lock().unwrap();
+        *available = available.saturating_sub(gas);
+    }
+}
+
+impl Default for StorageGas {
+    fn default() -> Self {
+        Self::new(DEFAULT_STORAGE_GAS_AVAILABLE)
+    }
+}
+
+/// L1 fees collected from processed deposits, reported by `ArbGasInfo.getL1FeesAvailable`
+///
+/// Cloning shares the underlying balance.
+#[derive(Debug, Clone)]
+pub struct L1FeePool {
+    balance: Arc<Mutex<U256>>,
+}
+
+impl L1FeePool {
+    /// Start with an empty pool
+    pub fn new() -> Self {
+        Self {
+            balance: Arc::new(Mutex::new(U256::zero())),
+        }
+    }
+
+    /// Fees collected so far
+    pub fn available(&self) -> U256 {
+        *self.balance.lock().unwrap()
+    }
+
+    /// Add `fee` to the pool, saturating at `U256::MAX`
+    pub fn add(&self, fee: U256) {
+        let mut balance = self.balance.lock().unwrap();
+        *balance = balance.saturating_add(fee);
+    }
+}
+
+impl Default for L1FeePool {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[derive(Debug)]
+struct L1BaseFeeEstimateState {
+    inertia: u64,
+    /// `None` until the first observation
+    estimate: Option<u64>,
+}
+
+/// Smoothed L1 base fee reported by `ArbGasInfo.getL1BaseFeeEstimate`
+///
+/// Each processed deposit blends the L1 base fee it carries into the estimate,
+/// moving it `1/inertia` of the way. Cloning shares the underlying estimate.
+#[derive(Debug, Clone)]
+pub struct L1BaseFeeEstimate {
+    state: Arc<Mutex<L1BaseFeeEstimateState>>,
+}
+
+impl L1BaseFeeEstimate {
+    /// Start without an estimate, smoothing observations with the given inertia
+    pub fn new(inertia: u64) -> Self {
+        Self {
+            state: Arc::new(Mutex::new(L1BaseFeeEstimateState { inertia, estimate: None })),
+        }
+    }
+
+    /// Weight of the previous estimate
+    pub fn inertia(&self) -> u64 {
+        self.state.lock().unwrap().inertia
+    }
+
+    /// Change the weight of the previous estimate for later observations
+    pub fn set_inertia(&self, inertia: u64) {
+        self.state.lock().unwrap().inertia = inertia;
+    }
+
+    /// Current estimate, or `l1_base_fee` before any observation
+    pub fn current(&self, l1_base_fee: u64) -> u64 {
+        self.state.lock().unwrap().estimate.unwrap_or(l1_base_fee)
+    }
+
+    /// Blend an observed L1 base fee into the estimate, starting from `l1_base_fee`
+    ///
+    /// The new estimate is `(estimate * (inertia - 1) + observed) / inertia`, so an
+    /// inertia of 1 (or 0) takes the observed fee as is.
+    pub fn observe(&self, observed: u64, l1_base_fee: u64) {
+        let mut state = self.state.lock().unwrap();
+        let inertia = state.inertia.max(1) as u128;
+        let previous = state.estimate.unwrap_or(l1_base_fee) as u128;
+        state.estimate = Some(((previous * (inertia - 1) + observed as u128) / inertia) as u64);
+    }
+
+    /// Forget every observation and restart the estimate at `l1_base_fee`
+    pub fn restart(&self, l1_base_fee: u64) {
+        self.state.lock().unwrap().estimate = Some(l1_base_fee);
+    }
+}
+
+impl Default for L1BaseFeeEstimate {
+    fn default() -> Self {
+        Self::new(DEFAULT_L1_BASE_FEE_INERTIA)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_congestion_fee_rises_as_pool_drains() {
+        let gas = GasPriceComponents {
+            l2_base_fee: 1_000,
+            congestion_fee: 5,
+            min_l2_gas_price: 0,
+            ..GasPriceComponents::default()
+        };
+        let pool = GasPool::new(1_000_000, 250_000);
+        assert_eq!(pool.congestion_fee(&gas), 5);
+
+        pool.consume(500_000);
+        assert_eq!(pool.available(), 500_000);
+        assert_eq!(pool.congestion_fee(&gas), 505);
+
+        pool.consume(2_000_000);
+        assert_eq!(pool.available(), 0);
+        assert_eq!(pool.congestion_fee(&gas), 1_005);
+
+        pool.refill(2);
+        assert_eq!(pool.congestion_fee(&gas), 505);
+        pool.refill(10);
+        assert_eq!(pool.available(), 1_000_000);
+        assert_eq!(pool.congestion_fee(&gas), 5);
+
+        // Refilling by block timestamp only counts the time since the last block
+        pool.refill_at(100);
+        pool.consume(1_000_000);
+        pool.refill_at(100);
+        assert_eq!(pool.available(), 0);
+        pool.refill_at(101);
+        assert_eq!(pool.available(), 250_000);
+        pool.refill_at(99);
+        assert_eq!(pool.available(), 250_000);
+    }
+
+    #[test]
+    fn test_l1_base_fee_estimate_smoothing() {
+        let l1_base_fee = 20_000_000_000;
+        let estimate = L1BaseFeeEstimate::default();
+        assert_eq!(estimate.inertia(), DEFAULT_L1_BASE_FEE_INERTIA);
+        assert_eq!(estimate.current(l1_base_fee), 20_000_000_000);
+
+        // Each observation closes a tenth of the gap
+        estimate.observe(30_000_000_000, l1_base_fee);
+        assert_eq!(estimate.current(l1_base_fee), 21_000_000_000);
+        estimate.clone().observe(30_000_000_000, l1_base_fee);
+        assert_eq!(estimate.current(l1_base_fee), 21_900_000_000);
+        estimate.observe(10_000_000_000, l1_base_fee);
+        assert_eq!(estimate.current(l1_base_fee), 20_710_000_000);
+
+        estimate.set_inertia(4);
+        estimate.observe(0, l1_base_fee);
+        assert_eq!(estimate.current(l1_base_fee), 15_532_500_000);
+
+        // An inertia of 1 follows the observed fee exactly
+        estimate.set_inertia(1);
+        estimate.observe(u64::MAX, l1_base_fee);
+        assert_eq!(estimate.current(l1_base_fee), u64::MAX);
+
+        estimate.restart(2_000_000_000);
+        assert_eq!(estimate.current(l1_base_fee), 2_000_000_000);
+    }
+}
@@ -0,0 +1,47 @@
+//! Anvil-Arbitrum: Arbitrum precompile and 0x7e transaction support for Anvil
+//!
+//! With the default `std` feature disabled only [`core`] and [`primitives`] are
+//! built, and the crate is `no_std` + `alloc`.
+
+#![cfg_attr(not(feature = "std"), no_std)]
+
+extern crate alloc;
+
+#[cfg(feature = "std")]
+pub mod accounts;
+#[cfg(feature = "std")]
+pub mod anvil;
+#[cfg(feature = "std")]
+pub mod arbitrum;
+#[cfg(feature = "std")]
+pub mod bridge;
+#[cfg(feature = "std")]
+pub mod cli;
+#[cfg(feature = "std")]
+pub mod compression;
+pub mod core;
+#[cfg(feature = "std")]
+pub mod gas_pool;
+#[cfg(feature = "std")]
+pub mod logging;
+#[cfg(feature = "std")]
+pub mod metrics;
+#[cfg(feature = "std")]
+pub mod mock_chain;
+#[cfg(feature = "std")]
+pub mod outbox;
+#[cfg(feature = "std")]
+pub mod owner;
+#[cfg(feature = "std")]
+pub mod precompiles;
+pub mod primitives;
+#[cfg(feature = "std")]
+pub mod rpc;
+#[cfg(feature = "std")]
+pub mod selector_registry;
+#[cfg(feature = "std")]
+pub mod state;
+#[cfg(feature = "std")]
+pub mod tx7e;
+#[cfg(feature = "std")]
+pub mod watch;
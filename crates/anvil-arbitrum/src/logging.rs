@@ -0,0 +1,210 @@
+//! Log output formats for the tracing subscriber
+
+use serde_json::{json, Map, Value};
+use std::fmt;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::field::{Field, Visit};
+use tracing::{Event, Subscriber};
+use tracing_subscriber::fmt::format::Writer;
+use tracing_subscriber::fmt::{FmtContext, FormatEvent, FormatFields};
+use tracing_subscriber::registry::LookupSpan;
+use tracing_subscriber::EnvFilter;
+
+/// Format of log lines written to stderr
+#[derive(Debug, Clone, Copy, Default, PartialEq, Eq, clap::ValueEnum)]
+pub enum LogFormat {
+    /// Human-readable lines
+    #[default]
+    Text,
+    /// One JSON object per line
+    Json,
+}
+
+/// Install the global subscriber writing `format` lines to stderr
+pub fn init(format: LogFormat, filter: EnvFilter) {
+    let builder = tracing_subscriber::fmt()
+        .with_env_filter(filter)
+        .with_writer(std::io::stderr);
+    match format {
+        LogFormat::Text => builder.init(),
+        LogFormat::Json => builder.event_format(JsonFormat).init(),
+    }
+}
+
+/// Event formatter writing `{"timestamp", "level", "target", "fields"}` objects
+///
+/// `timestamp` is in milliseconds since the Unix epoch. Integer and boolean
+/// fields keep their JSON types; everything else is recorded as a string.
+#[derive(Debug, Clone, Copy, Default)]
+pub struct JsonFormat;
+
+impl<S, N> FormatEvent<S, N> for JsonFormat
+where
+    S: Subscriber + for<'a> LookupSpan<'a>,
+    N: for<'a> FormatFields<'a> + 'static,
+{
+    fn format_event(&self, _ctx: &FmtContext<'_, S, N>, mut writer: Writer<'_>, event: &Event<'_>) -> fmt::Result {
+        let mut fields = JsonFields::default();
+        event.record(&mut fields);
+        let timestamp = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_millis() as u64)
+            .unwrap_or(0);
+        let metadata = event.metadata();
+        let line = json!({
+            "timestamp": timestamp,
+            "level": metadata.level().as_str(),
+            "target": metadata.target(),
+            "fields": Value::Object(fields.0),
+        });
+        writeln!(writer, "{}", line)
+    }
+}
+
+#[derive(Default)]
+struct JsonFields(Map<String, Value>);
+
+impl Visit for JsonFields {
+    fn record_debug(&mut self, field: &Field, value: &dyn fmt::Debug) {
+        self.0.insert(field.name().to_string(), json!(format!("{:?}", value)));
+    }
+
+    fn record_str(&mut self, field: &Field, value: &str) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_u64(&mut self, field: &Field, value: u64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_i64(&mut self, field: &Field, value: i64) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+
+    fn record_bool(&mut self, field: &Field, value: bool) {
+        self.0.insert(field.name().to_string(), json!(value));
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::tx7e::{mock_deposit, Tx7eProcessor, TX_TYPE_0X7E};
+    #[cfg(feature = "alloy")]
+    use crate::primitives::{AddressExt, U256Ext};
+    use rlp::RlpStream;
+    use sha3::{Digest, Keccak256};
+    use std::collections::BTreeSet;
+    use std::io;
+    use std::sync::{Arc, Mutex};
+
+    #[derive(Clone, Default)]
+    struct Buffer(Arc<Mutex<Vec<u8>>>);
+
+    impl io::Write for Buffer {
+        fn write(&mut self, bytes: &[u8]) -> io::Result<usize> {
+            self.0.lock().unwrap().extend_from_slice(bytes);
+            Ok(bytes.len())
+        }
+
+        fn flush(&mut self) -> io::Result<()> {
+            Ok(())
+        }
+    }
+
+    #[test]
+    fn test_processed_transaction_event_fields() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .event_format(JsonFormat)
+            .finish();
+
+        let tx = mock_deposit([5u8; 32]).with_chain_id(421614).build().unwrap();
+        let raw_tx = tx.rlp_encode_typed();
+        let processor = Tx7eProcessor::new();
+        tracing::subscriber::with_default(subscriber, || {
+            futures::executor::block_on(processor.process_transaction(&raw_tx));
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let events: Vec<Value> = output.lines().map(|line| serde_json::from_str(line).unwrap()).collect();
+        let event = events
+            .iter()
+            .find(|event| event["target"] == "anvil_arbitrum::tx7e")
+            .expect("no transaction event");
+        let fields = &event["fields"];
+        let names: BTreeSet<&str> = fields.as_object().unwrap().keys().map(String::as_str).collect();
+        assert_eq!(
+            names,
+            BTreeSet::from([
+                "message",
+                "hash",
+                "chain_id",
+                "gas_used",
+                "l1_gas_used",
+                "l2_gas_used",
+                "l1_cost",
+                "success"
+            ])
+        );
+        assert_eq!(event["level"], "INFO");
+        assert_eq!(fields["hash"], format!("0x{}", hex::encode(tx.hash())));
+        assert_eq!(fields["chain_id"], 421614);
+        assert_eq!(fields["gas_used"], tx.intrinsic_gas());
+        assert_eq!(fields["l1_gas_used"].as_u64().unwrap() + fields["l2_gas_used"].as_u64().unwrap(), tx.intrinsic_gas());
+        assert_eq!(fields["success"], true);
+    }
+
+    #[test]
+    fn test_processed_transaction_event_hashes_decoded_transaction() {
+        let buffer = Buffer::default();
+        let writer = buffer.clone();
+        let subscriber = tracing_subscriber::fmt()
+            .with_writer(move || writer.clone())
+            .event_format(JsonFormat)
+            .finish();
+
+        // A zero-padded value still decodes, but changes the keccak of the raw payload
+        let tx = mock_deposit([6u8; 32]).build().unwrap();
+        let mut stream = RlpStream::new_list(13);
+        stream.append(&tx.chain_id);
+        stream.append(&tx.target.as_bytes().to_vec());
+        stream.append(&tx.value.to_big_endian().to_vec());
+        stream.append(&tx.data);
+        stream.append(&tx.gas_limit);
+        stream.append(&tx.l1_block_number);
+        stream.append(&tx.l1_timestamp);
+        stream.append(&tx.l1_base_fee.to_big_endian().to_vec());
+        stream.append(&tx.l1_gas_price.to_big_endian().to_vec());
+        stream.append(&tx.l1_gas_used);
+        stream.append(&tx.l1_fee.to_big_endian().to_vec());
+        stream.append(&tx.refund_address.as_bytes().to_vec());
+        stream.append(&tx.source_hash.to_vec());
+        let mut raw_tx = vec![TX_TYPE_0X7E];
+        raw_tx.extend_from_slice(&stream.out());
+        assert_ne!(raw_tx, tx.rlp_encode_typed());
+        let processor = Tx7eProcessor::new();
+        tracing::subscriber::with_default(subscriber, || {
+            futures::executor::block_on(processor.process_transaction(&raw_tx));
+            futures::executor::block_on(processor.process_transaction(&[TX_TYPE_0X7E, 0x01]));
+        });
+
+        let output = String::from_utf8(buffer.0.lock().unwrap().clone()).unwrap();
+        let hashes: Vec<Value> = output
+            .lines()
+            .map(|line| serde_json::from_str::<Value>(line).unwrap())
+            .filter(|event| event["target"] == "anvil_arbitrum::tx7e")
+            .map(|event| event["fields"]["hash"].clone())
+            .collect();
+        let undecodable: [u8; 32] = Keccak256::digest([TX_TYPE_0X7E, 0x01]).into();
+        assert_eq!(
+            hashes,
+            [
+                format!("0x{}", hex::encode(tx.hash())),
+                format!("0x{}", hex::encode(undecodable))
+            ]
+        );
+    }
+}
@@ -1,67 +1,189 @@
 //! Anvil-Arbitrum: Arbitrum precompile and 0x7e transaction support for Anvil
 
-use crate::arbitrum::ArbitrumConfig;
-use crate::cli::AnvilArbitrumArgs;
-use crate::precompiles::{Address, PrecompileRegistry, U256};
-use crate::tx7e::Tx7eProcessor;
-use anyhow::Result;
+use anvil_arbitrum::anvil::{find_anvil_binary, write_genesis_file, AnvilProcess, ANVIL_PATH_ENV};
+use anvil_arbitrum::arbitrum::ArbitrumConfig;
+use anvil_arbitrum::bridge::L1BridgeHandler;
+use anvil_arbitrum::cli::{self, AnvilArbitrumArgs};
+use anvil_arbitrum::gas_pool::GasPool;
+use anvil_arbitrum::logging;
+use anvil_arbitrum::mock_chain::MockChain;
+use anvil_arbitrum::owner::{ArbDebugHandler, ArbOwnerHandler, ArbOwnerPublicHandler};
+use anvil_arbitrum::precompiles::{selectors, Address, PrecompileHandler, PrecompileRegistry, U256};
+use anvil_arbitrum::primitives::HexBytes;
+#[cfg(feature = "alloy")]
+use anvil_arbitrum::primitives::{AddressExt, U256Ext};
+use anvil_arbitrum::rpc::{self, RpcState, Server};
+use anvil_arbitrum::tx7e::{Tx7eParser, Tx7eProcessor, Tx7eTransaction};
+use anvil_arbitrum::watch::ConfigWatcher;
+use anyhow::{anyhow, Result};
 use clap::Parser;
+use std::sync::Arc;
 use tracing::{info, warn};
-
-mod arbitrum;
-mod cli;
-mod precompiles;
-mod tx7e;
+use tracing_subscriber::EnvFilter;
 
 #[tokio::main]
 async fn main() -> Result<()> {
     // Parse command line arguments
     let args = AnvilArbitrumArgs::parse();
 
-    // Initialize tracing
-    tracing_subscriber::fmt::init();
+    // Initialize tracing, letting RUST_LOG override the level chosen by --silent/--verbose
+    let filter = EnvFilter::builder()
+        .with_default_directive(args.log_level().into())
+        .from_env_lossy();
+    // Logs go to stderr so --dump-config, --dry-run and --list-precompiles output can be piped
+    logging::init(args.log_format, filter);
 
     info!("Starting Anvil-Arbitrum...");
 
     // Create Arbitrum configuration
-    let config = if args.arbitrum {
-        ArbitrumConfig::new(
-            args.chain_id.unwrap_or(42161),
-            args.arb_os_version.unwrap_or(20),
-            args.l1_base_fee.unwrap_or(20_000_000_000),
-        )
-    } else {
-        ArbitrumConfig::default()
-    };
+    let config = args.arbitrum_config_with_fork().await?;
+    if let Some(warning) = args.chain_id_conflict() {
+        warn!("{}", warning);
+    }
+
+    if args.dump_config {
+        println!("{}", config.to_json_pretty()?);
+        return Ok(());
+    }
+
+    if let Some(input) = &args.dry_run {
+        let report = cli::dry_run(input, &config)?;
+        println!("{}", serde_json::to_string_pretty(&report.to_json())?);
+        return report
+            .validation
+            .into_result()
+            .map_err(|errors| anyhow!("Transaction failed validation with {} error(s)", errors.len()));
+    }
 
     info!("Arbitrum configuration: {:?}", config);
 
+    if args.watch_config && !args.rpc {
+        warn!("--watch-config has no effect without --rpc");
+    }
+    // Reloads reuse the base fee fetched from --fork rather than querying it again
+    let fork_base_fee = match (&args.fork, args.l1_base_fee) {
+        (Some(_), None) => Some(config.l1_base_fee),
+        _ => None,
+    };
+
+    // Initialize precompile registry; the RPC transaction processor shares its gas pool and chain
+    let chain = args.seed.map(MockChain::from_seed).unwrap_or_default();
+    let mut precompile_registry = PrecompileRegistry::from_config(&config, GasPool::default(), chain.clone());
+    if args.mock_l1_bridge {
+        let bridge = L1BridgeHandler::from_config(&config).with_mock_chain(chain.clone());
+        info!("Mock L1 bridge registered at {}", bridge.address());
+        precompile_registry.register(Box::new(bridge));
+    }
+    let owners = precompile_registry.chain_owners().clone();
+    precompile_registry.register(Box::new(ArbOwnerHandler::new(owners.clone())));
+    precompile_registry.register(Box::new(ArbOwnerPublicHandler::new(owners.clone())));
+    if config.allow_debug_precompiles {
+        precompile_registry.register(Box::new(ArbDebugHandler::new(owners)));
+        info!("Debug precompiles enabled: ArbDebug registered");
+    }
+    info!("Precompile registry initialized with {} handlers", precompile_registry.get_addresses().len());
+
+    if args.list_precompiles {
+        print!("{}", precompile_registry.selector_table());
+        return Ok(());
+    }
+
+    if args.dump_abi {
+        println!("{}", serde_json::to_string_pretty(&precompile_registry.abi_json())?);
+        return Ok(());
+    }
+
     // Demonstrate Arbitrum features
-    demonstrate_arbitrum_features(&config, &args).await?;
+    demonstrate_arbitrum_features(&config, &precompile_registry, &args).await?;
 
-    // In a real implementation, this would start Anvil with the Arbitrum configuration
-    // For now, we just demonstrate the capabilities
     info!("Anvil-Arbitrum demonstration completed successfully");
 
+    // When serving RPC ourselves, a spawned Anvil moves to the next port and becomes the upstream
+    let mut anvil_args = args.clone();
+    let mut upstream_url = args.upstream_url.clone();
+    if args.rpc && args.spawn_anvil {
+        anvil_args.port = args.port + 1;
+        upstream_url.get_or_insert_with(|| format!("http://{}:{}", args.host, anvil_args.port));
+    }
+
+    // Standalone, list the accounts Anvil would have generated
+    if !args.spawn_anvil {
+        for (index, account) in args.dev_accounts()?.iter().enumerate() {
+            info!(
+                "Account ({}) {} ({} ETH), private key {}",
+                index,
+                account.address,
+                args.balance,
+                account.private_key_hex()
+            );
+        }
+    }
+
+    // The genesis file stays on disk until `_genesis` is dropped at exit
+    let (anvil, _genesis) = if args.spawn_anvil {
+        let binary = find_anvil_binary().ok_or_else(|| {
+            anyhow!("Could not find the anvil binary; set {} or add it to PATH", ANVIL_PATH_ENV)
+        })?;
+        let genesis = write_genesis_file(&config)?;
+        let mut spawn_args = anvil_args.get_anvil_args();
+        spawn_args.push(format!("--init={}", genesis.path().display()));
+        (Some(AnvilProcess::spawn(&binary, &spawn_args)?), Some(genesis))
+    } else {
+        (None, None)
+    };
+
+    if args.rpc {
+        let mut state = RpcState::new(config).with_registry(precompile_registry);
+        if let Some(url) = upstream_url {
+            state = state.with_upstream(url);
+        }
+        if args.metrics {
+            state = state.with_metrics_endpoint();
+        }
+        let state = Arc::new(state);
+        let watcher = match (&args.config_file, args.watch_config) {
+            (Some(path), true) => {
+                info!("Watching {} for configuration changes", path);
+                let reload_args = args.clone();
+                let watcher = ConfigWatcher::new(path, move || {
+                    reload_args.arbitrum_config_with_fork_base_fee_from(|name| std::env::var(name).ok(), fork_base_fee)
+                });
+                Some(watcher.spawn(state.clone()))
+            }
+            _ => None,
+        };
+        let server = Server::bind(&args.host, args.port, state).await?;
+        server.run(rpc::shutdown_signal()).await?;
+        if let Some(watcher) = watcher {
+            watcher.abort();
+        }
+        if let Some(mut anvil) = anvil {
+            info!("Stopping Anvil");
+            anvil.kill().await?;
+        }
+    } else if let Some(anvil) = anvil {
+        anvil.run_until_shutdown().await?;
+    }
+
     Ok(())
 }
 
 /// Demonstrate Arbitrum precompile and 0x7e transaction features
-async fn demonstrate_arbitrum_features(config: &ArbitrumConfig, args: &AnvilArbitrumArgs) -> Result<()> {
+async fn demonstrate_arbitrum_features(
+    config: &ArbitrumConfig,
+    precompile_registry: &PrecompileRegistry,
+    args: &AnvilArbitrumArgs,
+) -> Result<()> {
     info!("Demonstrating Arbitrum features...");
 
-    // Initialize precompile registry
-    let precompile_registry = PrecompileRegistry::default();
-    info!("Precompile registry initialized with {} handlers", precompile_registry.get_addresses().len());
-
     // Test ArbSys precompile calls
     let arbsys_address = Address::from_hex("0x0000000000000000000000000000000000000064")?;
     if precompile_registry.has_handler(&arbsys_address) {
         info!("Testing ArbSys precompile...");
         
         // Test arbChainID()
-        let chain_id_input = hex::decode("a3b1b31d")?;
-        match precompile_registry.handle_call(arbsys_address.clone(), &chain_id_input, config) {
+        let chain_id_input = hex::decode(selectors::ARB_CHAIN_ID)?;
+        match precompile_registry.handle_call(arbsys_address, &chain_id_input, config) {
             Ok(result) => {
                 let chain_id = U256::from_big_endian(&result);
                 info!("ArbSys.arbChainID() returned: {}", chain_id);
@@ -70,8 +192,8 @@ async fn demonstrate_arbitrum_features(config: &ArbitrumConfig, args: &AnvilArbi
         }
 
         // Test arbOSVersion()
-        let version_input = hex::decode("4d2301cc")?;
-        match precompile_registry.handle_call(arbsys_address.clone(), &version_input, config) {
+        let version_input = hex::decode(selectors::ARB_OS_VERSION)?;
+        match precompile_registry.handle_call(arbsys_address, &version_input, config) {
             Ok(result) => {
                 let version = U256::from_big_endian(&result);
                 info!("ArbSys.arbOSVersion() returned: {}", version);
@@ -86,8 +208,8 @@ async fn demonstrate_arbitrum_features(config: &ArbitrumConfig, args: &AnvilArbi
         info!("Testing ArbGasInfo precompile...");
         
         // Test getL1BaseFeeEstimate()
-        let base_fee_input = hex::decode("4d2301cc")?;
-        match precompile_registry.handle_call(arbgasinfo_address.clone(), &base_fee_input, config) {
+        let base_fee_input = hex::decode(selectors::GET_L1_BASE_FEE_ESTIMATE)?;
+        match precompile_registry.handle_call(arbgasinfo_address, &base_fee_input, config) {
             Ok(result) => {
                 let base_fee = U256::from_big_endian(&result);
                 info!("ArbGasInfo.getL1BaseFeeEstimate() returned: {}", base_fee);
@@ -100,18 +222,22 @@ async fn demonstrate_arbitrum_features(config: &ArbitrumConfig, args: &AnvilArbi
     if args.enable_tx7e {
         info!("Testing 0x7e transaction processing...");
         
-        let processor = Tx7eProcessor::new();
+        let processor = Tx7eProcessor::new()
+            .with_parser(Tx7eParser::from_config(config))
+            .with_config(config.clone());
         
         // Create a mock 0x7e transaction
         let mock_tx = create_mock_tx7e_transaction(config)?;
-        let encoded = mock_tx.rlp_encode();
-        let mut raw_tx = vec![0x7e]; // Transaction type
-        raw_tx.extend_from_slice(&encoded);
+        info!("Mock 0x7e transaction {}: {:?}", HexBytes(mock_tx.hash()), mock_tx);
+        let raw_tx = mock_tx.rlp_encode_typed();
         
         let result = processor.process_transaction(&raw_tx).await;
         if result.success {
             info!("0x7e transaction processed successfully");
-            info!("Gas used: {}", result.gas_used);
+            info!(
+                "Gas used: {} ({} for L1 calldata, {} for L2 execution)",
+                result.gas_used, result.l1_gas_used, result.l2_gas_used
+            );
             info!("L1 cost: {}", result.l1_cost);
         } else {
             warn!("0x7e transaction processing failed: {}", result.error);
@@ -122,25 +248,23 @@ async fn demonstrate_arbitrum_features(config: &ArbitrumConfig, args: &AnvilArbi
 }
 
 /// Create a mock 0x7e transaction for testing
-fn create_mock_tx7e_transaction(config: &ArbitrumConfig) -> Result<crate::tx7e::Tx7eTransaction> {
-    use crate::tx7e::Tx7eTransaction;
-    
+fn create_mock_tx7e_transaction(config: &ArbitrumConfig) -> Result<Tx7eTransaction> {
     let target = Address::from_hex("0x1234567890123456789012345678901234567890")?;
     let refund_address = Address::from_hex("0xabcdefabcdefabcdefabcdefabcdefabcdefabcd")?;
     
-    Ok(Tx7eTransaction::new(
-        config.chain_id,
-        target,
-        U256::from_u64(1000000000000000000), // 1 ETH
-        vec![0x60, 0x2b, 0x57, 0xfd], // Some calldata
-        100000, // Gas limit
-        12345, // L1 block number
-        1640995200, // L1 timestamp
-        U256::from_u64(config.l1_base_fee),
-        U256::from_u64(25000000000), // L1 gas price
-        50000, // L1 gas used
-        U256::from_u64(1000000000000000), // L1 fee
-        refund_address,
-        [1u8; 32], // Source hash
-    ))
+    Ok(Tx7eTransaction::builder()
+        .with_chain_id(config.chain_id)
+        .with_target(target)
+        .with_value(U256::from_u64(1000000000000000000)) // 1 ETH
+        .with_data(vec![0x60, 0x2b, 0x57, 0xfd]) // Some calldata
+        .with_gas_limit(100000)
+        .with_l1_block_number(12345)
+        .with_l1_timestamp(1640995200)
+        .with_l1_base_fee(U256::from_u64(config.l1_base_fee))
+        .with_l1_gas_price(U256::from_u64(25000000000))
+        .with_l1_gas_used(50000)
+        .with_l1_fee(U256::from_u64(1000000000000000))
+        .with_refund_address(refund_address)
+        .with_source_hash([1u8; 32])
+        .build()?)
 }
@@ -0,0 +1,117 @@
+//! Counters for processed 0x7e transactions and precompile calls
+
+use std::collections::BTreeMap;
+use std::fmt::Write;
+use std::sync::atomic::{AtomicU64, Ordering};
+use std::sync::{Arc, Mutex};
+
+#[derive(Debug, Default)]
+struct MetricsState {
+    transactions_succeeded: AtomicU64,
+    transactions_failed: AtomicU64,
+    /// Calls per precompile, keyed by handler name
+    precompile_calls: Mutex<BTreeMap<String, u64>>,
+}
+
+/// Counters updated by `Tx7eProcessor` and `PrecompileRegistry`
+///
+/// Cloning the metrics shares the underlying counters, so one instance can be
+/// handed to both and read back from either.
+#[derive(Debug, Clone, Default)]
+pub struct Metrics {
+    state: Arc<MetricsState>,
+}
+
+impl Metrics {
+    /// Create zeroed counters
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Count a processed 0x7e transaction
+    pub fn record_transaction(&self, success: bool) {
+        let counter = if success {
+            &self.state.transactions_succeeded
+        } else {
+            &self.state.transactions_failed
+        };
+        counter.fetch_add(1, Ordering::Relaxed);
+    }
+
+    /// Count a call to the named precompile
+    pub fn record_precompile_call(&self, name: &str) {
+        let mut calls = self.state.precompile_calls.lock().unwrap();
+        *calls.entry(name.to_string()).or_insert(0) += 1;
+    }
+
+    /// Copy the current counter values
+    pub fn snapshot(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            transactions_succeeded: self.state.transactions_succeeded.load(Ordering::Relaxed),
+            transactions_failed: self.state.transactions_failed.load(Ordering::Relaxed),
+            precompile_calls: self.state.precompile_calls.lock().unwrap().clone(),
+        }
+    }
+}
+
+/// Point-in-time copy of the counters in [`Metrics`]
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct MetricsSnapshot {
+    pub transactions_succeeded: u64,
+    pub transactions_failed: u64,
+    pub precompile_calls: BTreeMap<String, u64>,
+}
+
+impl MetricsSnapshot {
+    /// Render the counters in the Prometheus text exposition format
+    pub fn to_prometheus(&self) -> String {
+        let mut out = String::new();
+        out.push_str("# HELP anvil_arbitrum_tx7e_processed_total 0x7e transactions processed, by outcome\n");
+        out.push_str("# TYPE anvil_arbitrum_tx7e_processed_total counter\n");
+        for (result, count) in [("success", self.transactions_succeeded), ("failure", self.transactions_failed)] {
+            let _ = writeln!(out, "anvil_arbitrum_tx7e_processed_total{{result=\"{}\"}} {}", result, count);
+        }
+        out.push_str("# HELP anvil_arbitrum_precompile_calls_total Precompile calls, by precompile\n");
+        out.push_str("# TYPE anvil_arbitrum_precompile_calls_total counter\n");
+        for (name, count) in &self.precompile_calls {
+            let _ = writeln!(out, "anvil_arbitrum_precompile_calls_total{{precompile=\"{}\"}} {}", name, count);
+        }
+        out
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrum::ArbitrumConfig;
+    use crate::precompiles::{selectors, Address, PrecompileRegistry};
+    #[cfg(feature = "alloy")]
+    use crate::primitives::AddressExt;
+    use crate::tx7e::{mock_deposit, Tx7eProcessor};
+
+    #[test]
+    fn test_counters_increment() {
+        let metrics = Metrics::new();
+        let processor = Tx7eProcessor::new().with_metrics(metrics.clone());
+        let registry = PrecompileRegistry::default().with_metrics(metrics.clone());
+
+        let tx = mock_deposit([3u8; 32]).build().unwrap();
+        let raw_tx = tx.rlp_encode_typed();
+        assert!(futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+        assert!(!futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+
+        let arb_sys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let input = hex::decode(selectors::ARB_CHAIN_ID).unwrap();
+        registry.handle_call(arb_sys, &input, &ArbitrumConfig::default()).unwrap();
+
+        let snapshot = processor.metrics().snapshot();
+        assert_eq!(snapshot, registry.metrics().snapshot());
+        assert_eq!(snapshot.transactions_succeeded, 1);
+        assert_eq!(snapshot.transactions_failed, 1);
+        assert_eq!(snapshot.precompile_calls.get("ArbSys"), Some(&1));
+
+        let text = snapshot.to_prometheus();
+        assert!(text.contains("anvil_arbitrum_tx7e_processed_total{result=\"success\"} 1\n"));
+        assert!(text.contains("anvil_arbitrum_precompile_calls_total{precompile=\"ArbSys\"} 1\n"));
+    }
+}
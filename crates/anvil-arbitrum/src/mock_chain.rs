@@ -0,0 +1,117 @@
+//! Mock L2 chain head supplying block numbers and timestamps
+
+use sha3::{Digest, Keccak256};
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+
+/// Earliest timestamp a seeded chain starts at (2020-09-13)
+const SEEDED_TIMESTAMP_BASE: u64 = 1_600_000_000;
+
+/// Range seeded start block numbers and timestamp offsets are drawn from
+const SEEDED_RANGE: u64 = 100_000_000;
+
+#[derive(Debug)]
+struct ChainHead {
+    block_number: u64,
+    /// `None` follows the wall clock
+    timestamp: Option<u64>,
+}
+
+/// Current block number and timestamp of the mock L2 chain
+///
+/// An unseeded chain starts at block 1 and reports the wall-clock time. A
+/// seeded chain starts at a block number and timestamp derived from the seed,
+/// and each new block advances the timestamp by one second, so every run with
+/// the same seed sees the same sequence. Cloning the chain shares its head.
+#[derive(Debug, Clone)]
+pub struct MockChain {
+    head: Arc<Mutex<ChainHead>>,
+}
+
+impl MockChain {
+    /// Create an unseeded chain at block 1
+    pub fn new() -> Self {
+        Self::with_head(1, None)
+    }
+
+    /// Create a chain whose starting block number and timestamp derive from `seed`
+    pub fn from_seed(seed: u64) -> Self {
+        let hash: [u8; 32] = Keccak256::digest(seed.to_be_bytes()).into();
+        let word = |i: usize| u64::from_be_bytes(hash[i * 8..(i + 1) * 8].try_into().unwrap());
+        Self::with_head(
+            1 + word(0) % SEEDED_RANGE,
+            Some(SEEDED_TIMESTAMP_BASE + word(1) % SEEDED_RANGE),
+        )
+    }
+
+    fn with_head(block_number: u64, timestamp: Option<u64>) -> Self {
+        Self {
+            head: Arc::new(Mutex::new(ChainHead { block_number, timestamp })),
+        }
+    }
+
+    /// Current block number
+    pub fn block_number(&self) -> u64 {
+        self.head.lock().unwrap().block_number
+    }
+
+    /// Current block timestamp in seconds
+    pub fn timestamp(&self) -> u64 {
+        self.head.lock().unwrap().timestamp.unwrap_or_else(|| {
+            SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .map(|d| d.as_secs())
+                .unwrap_or(1)
+        })
+    }
+
+    /// Start a new block, returning its number
+    pub fn advance(&self) -> u64 {
+        let mut head = self.head.lock().unwrap();
+        head.block_number += 1;
+        if let Some(timestamp) = head.timestamp.as_mut() {
+            *timestamp += 1;
+        }
+        head.block_number
+    }
+}
+
+impl Default for MockChain {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrum::ArbitrumConfig;
+    use crate::precompiles::{selectors, ArbSysHandler, PrecompileHandler, U256};
+    #[cfg(feature = "alloy")]
+    use crate::primitives::U256Ext;
+
+    fn block_numbers(chain: MockChain) -> Vec<U256> {
+        let handler = ArbSysHandler::new().with_mock_chain(chain.clone());
+        let input = hex::decode(selectors::ARB_BLOCK_NUMBER).unwrap();
+        let config = ArbitrumConfig::default();
+        (0..4)
+            .map(|_| {
+                let output = handler.handle_call(&input, &config).unwrap();
+                chain.advance();
+                U256::from_big_endian(&output)
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_same_seed_gives_same_block_numbers() {
+        let first = block_numbers(MockChain::from_seed(7));
+        assert_eq!(first, block_numbers(MockChain::from_seed(7)));
+        assert_ne!(first, block_numbers(MockChain::from_seed(8)));
+        assert_ne!(first[0], U256::from_u64(1));
+        assert_eq!(first[3], first[0].saturating_add(U256::from_u64(3)));
+
+        assert_eq!(MockChain::from_seed(7).timestamp(), MockChain::from_seed(7).timestamp());
+        assert_eq!(block_numbers(MockChain::new())[0], U256::from_u64(1));
+    }
+}
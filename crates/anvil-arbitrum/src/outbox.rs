@@ -0,0 +1,186 @@
+//! Append-only Merkle accumulator over L2-to-L1 messages
+
+use crate::precompiles::PrecompileError;
+use sha3::{Digest, Keccak256};
+use std::sync::{Arc, Mutex};
+
+/// Hash two sibling nodes into their parent
+fn hash_pair(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    let mut hasher = Keccak256::new();
+    hasher.update(left);
+    hasher.update(right);
+    hasher.finalize().into()
+}
+
+/// Reduce one tree level to the next, pairing a trailing odd node with zero
+fn parent_level(level: &[[u8; 32]]) -> Vec<[u8; 32]> {
+    level
+        .chunks(2)
+        .map(|pair| hash_pair(&pair[0], pair.get(1).unwrap_or(&[0u8; 32])))
+        .collect()
+}
+
+/// Append-only Merkle tree of outbox leaves
+///
+/// Empty subtrees hash as zero, matching Nitro's send Merkle accumulator, so
+/// the root of a single-leaf tree is the leaf itself. Cloning the accumulator
+/// shares the underlying leaves.
+#[derive(Debug, Clone, Default)]
+pub struct OutboxAccumulator {
+    leaves: Arc<Mutex<Vec<[u8; 32]>>>,
+}
+
+impl OutboxAccumulator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a leaf, returning its index
+    pub fn append(&self, leaf: [u8; 32]) -> u64 {
+        let mut leaves = self.leaves.lock().unwrap();
+        leaves.push(leaf);
+        leaves.len() as u64 - 1
+    }
+
+    /// Number of leaves in the tree
+    pub fn len(&self) -> usize {
+        self.leaves.lock().unwrap().len()
+    }
+
+    /// Check if the tree has no leaves
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    /// Current Merkle root, or zero for an empty tree
+    pub fn root(&self) -> [u8; 32] {
+        let mut level = self.leaves.lock().unwrap().clone();
+        if level.is_empty() {
+            return [0u8; 32];
+        }
+        while level.len() > 1 {
+            level = parent_level(&level);
+        }
+        level[0]
+    }
+
+    /// Frontier of the tree, as `ArbSys.sendMerkleTreeState` reports it
+    ///
+    /// Entry `i` is the root of the complete subtree of `2^i` leaves when bit `i`
+    /// of the size is set, and zero otherwise; there is one entry per bit of the size.
+    pub fn partials(&self) -> Vec<[u8; 32]> {
+        let mut level = self.leaves.lock().unwrap().clone();
+        let mut size = level.len();
+        let mut partials = Vec::new();
+        while size > 0 {
+            partials.push(if size & 1 == 1 { level[size - 1] } else { [0u8; 32] });
+            level = parent_level(&level);
+            size >>= 1;
+        }
+        partials
+    }
+
+    /// Sibling hashes from the leaf at `index` up to the root
+    pub fn proof(&self, index: u64) -> Result<Vec<[u8; 32]>, PrecompileError> {
+        let mut level = self.leaves.lock().unwrap().clone();
+        let mut position = usize::try_from(index)
+            .ok()
+            .filter(|i| *i < level.len())
+            .ok_or(PrecompileError::OutboxLeafOutOfRange { index, leaves: level.len() })?;
+
+        let mut proof = Vec::new();
+        while level.len() > 1 {
+            proof.push(level.get(position ^ 1).copied().unwrap_or([0u8; 32]));
+            level = parent_level(&level);
+            position /= 2;
+        }
+        Ok(proof)
+    }
+
+    /// Check that `proof` places `leaf` at `index` in the tree with `root`
+    pub fn verify_proof(leaf: &[u8; 32], index: u64, proof: &[[u8; 32]], root: &[u8; 32]) -> bool {
+        let mut hash = *leaf;
+        let mut position = index;
+        for sibling in proof {
+            hash = if position & 1 == 0 {
+                hash_pair(&hash, sibling)
+            } else {
+                hash_pair(sibling, &hash)
+            };
+            position >>= 1;
+        }
+        position == 0 && hash == *root
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_two_leaf_proofs_validate() {
+        let outbox = OutboxAccumulator::new();
+        assert_eq!(outbox.root(), [0u8; 32]);
+
+        let first = [1u8; 32];
+        let second = [2u8; 32];
+        assert_eq!(outbox.append(first), 0);
+        assert_eq!(outbox.root(), first);
+        assert_eq!(outbox.append(second), 1);
+
+        let root = outbox.root();
+        assert_eq!(root, hash_pair(&first, &second));
+
+        let proof = outbox.proof(0).unwrap();
+        assert_eq!(proof, vec![second]);
+        assert!(OutboxAccumulator::verify_proof(&first, 0, &proof, &root));
+        assert!(OutboxAccumulator::verify_proof(&second, 1, &outbox.proof(1).unwrap(), &root));
+
+        // A proof does not validate for the wrong leaf or position
+        assert!(!OutboxAccumulator::verify_proof(&second, 0, &proof, &root));
+        assert!(!OutboxAccumulator::verify_proof(&first, 1, &proof, &root));
+        assert!(outbox.proof(2).is_err());
+    }
+
+    #[test]
+    fn test_partials_fold_into_root() {
+        let outbox = OutboxAccumulator::new();
+        assert!(outbox.partials().is_empty());
+        for i in 0..6u8 {
+            outbox.append([i + 1; 32]);
+        }
+        let partials = outbox.partials();
+        assert_eq!(partials, vec![[0u8; 32], hash_pair(&[5u8; 32], &[6u8; 32]), outbox.proof(5).unwrap()[2]]);
+
+        // Fold the frontier the way Nitro does, padding lower subtrees with zero
+        let mut root: Option<([u8; 32], u64)> = None;
+        for (level, partial) in partials.iter().enumerate().filter(|(_, p)| **p != [0u8; 32]) {
+            let capacity = 1u64 << level;
+            root = Some(match root {
+                None => (*partial, capacity),
+                Some((mut hash, mut hash_capacity)) => {
+                    while hash_capacity < capacity {
+                        hash = hash_pair(&hash, &[0u8; 32]);
+                        hash_capacity *= 2;
+                    }
+                    (hash_pair(partial, &hash), capacity * 2)
+                }
+            });
+        }
+        assert_eq!(root.unwrap().0, outbox.root());
+    }
+
+    #[test]
+    fn test_odd_sized_tree_proofs_validate() {
+        let outbox = OutboxAccumulator::new();
+        for i in 0..5u8 {
+            outbox.append([i + 1; 32]);
+        }
+        let root = outbox.root();
+        for i in 0..5u8 {
+            let proof = outbox.proof(i as u64).unwrap();
+            assert_eq!(proof.len(), 3);
+            assert!(OutboxAccumulator::verify_proof(&[i + 1; 32], i as u64, &proof, &root));
+        }
+    }
+}
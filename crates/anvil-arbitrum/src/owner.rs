@@ -0,0 +1,621 @@
+//! ArbOwner, ArbOwnerPublic and ArbDebug precompiles for simulating chain governance
+
+use crate::arbitrum::ArbitrumConfig;
+use crate::gas_pool::L1BaseFeeEstimate;
+use crate::precompiles::{
+    decode_u64_word, Address, CallContext, FunctionTable, LogBuffer, PrecompileError, PrecompileHandler,
+    PrecompileLog, U256,
+};
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt, U256Ext};
+use sha3::{Digest, Keccak256};
+use std::sync::{Arc, Mutex};
+
+type Result<T, E = PrecompileError> = std::result::Result<T, E>;
+
+/// Chain owners and the parameters they have set
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct ChainOwnerSettings {
+    /// Chain owners, in the order they were added
+    pub owners: Vec<Address>,
+    /// L2 base fee set by an owner, overriding the configured one
+    pub l2_base_fee: Option<u64>,
+}
+
+/// Owner state shared by `ArbOwner` and `ArbOwnerPublic`
+///
+/// Cloning the state shares the underlying settings, so changes made through
+/// `ArbOwner` are visible through `ArbOwnerPublic`.
+#[derive(Debug, Clone, Default)]
+pub struct ChainOwnerState {
+    settings: Arc<Mutex<ChainOwnerSettings>>,
+    l1_base_fee_estimate: L1BaseFeeEstimate,
+}
+
+impl ChainOwnerState {
+    /// Create state with the given initial chain owners
+    pub fn new(owners: impl IntoIterator<Item = Address>) -> Self {
+        let state = Self::default();
+        for owner in owners {
+            state.add_owner(owner);
+        }
+        state
+    }
+
+    /// Let `setL1BaseFeeEstimateInertia` drive the given estimate (e.g. the one `ArbGasInfo` reports)
+    pub fn with_l1_base_fee_estimate(mut self, l1_base_fee_estimate: L1BaseFeeEstimate) -> Self {
+        self.l1_base_fee_estimate = l1_base_fee_estimate;
+        self
+    }
+
+    /// Get the estimate whose inertia owners set
+    pub fn l1_base_fee_estimate(&self) -> &L1BaseFeeEstimate {
+        &self.l1_base_fee_estimate
+    }
+
+    /// Check if `address` is a chain owner
+    pub fn is_owner(&self, address: &Address) -> bool {
+        self.settings.lock().unwrap().owners.contains(address)
+    }
+
+    /// Get all chain owners
+    pub fn owners(&self) -> Vec<Address> {
+        self.settings.lock().unwrap().owners.clone()
+    }
+
+    /// Add a chain owner; adding an existing owner is a no-op
+    pub fn add_owner(&self, owner: Address) {
+        let mut settings = self.settings.lock().unwrap();
+        if !settings.owners.contains(&owner) {
+            settings.owners.push(owner);
+        }
+    }
+
+    /// Remove a chain owner, returning whether it was an owner
+    pub fn remove_owner(&self, owner: &Address) -> bool {
+        let mut settings = self.settings.lock().unwrap();
+        let before = settings.owners.len();
+        settings.owners.retain(|o| o != owner);
+        settings.owners.len() != before
+    }
+
+    /// Get a snapshot of the current settings
+    pub fn settings(&self) -> ChainOwnerSettings {
+        self.settings.lock().unwrap().clone()
+    }
+
+    /// Apply owner-set parameters on top of `config`
+    pub fn apply_to(&self, config: &mut ArbitrumConfig) {
+        if let Some(l2_base_fee) = self.settings.lock().unwrap().l2_base_fee {
+            config.gas_price_components.l2_base_fee = l2_base_fee;
+        }
+    }
+}
+
+/// Decode the `address` argument following the selector
+fn decode_address_arg(input: &[u8]) -> Result<Address> {
+    let arg = input
+        .get(4..36)
+        .ok_or(PrecompileError::InvalidInput("Input too short for address argument"))?;
+    Ok(Address::new(arg[12..32].try_into().unwrap()))
+}
+
+/// Decode a `uint256` argument following the selector that must fit in a u64
+fn decode_u64_arg(input: &[u8]) -> Result<u64> {
+    let arg = input
+        .get(4..36)
+        .ok_or(PrecompileError::InvalidInput("Input too short for integer argument"))?;
+    decode_u64_word(arg).ok_or(PrecompileError::InvalidInput("Integer argument exceeds 64 bits"))
+}
+
+/// Functions implemented by `ArbOwnerHandler`
+#[derive(Debug, Clone, Copy)]
+enum ArbOwnerFunction {
+    AddChainOwner,
+    RemoveChainOwner,
+    SetL1BaseFeeEstimateInertia,
+    SetL2BaseFee,
+}
+
+const ARBOWNER_FUNCTIONS: &[(&str, ArbOwnerFunction)] = &[
+    ("addChainOwner(address)", ArbOwnerFunction::AddChainOwner),
+    ("removeChainOwner(address)", ArbOwnerFunction::RemoveChainOwner),
+    ("setL1BaseFeeEstimateInertia(uint64)", ArbOwnerFunction::SetL1BaseFeeEstimateInertia),
+    ("setL2BaseFee(uint256)", ArbOwnerFunction::SetL2BaseFee),
+];
+
+/// ArbOwner precompile handler (0x70)
+///
+/// Every function reverts unless the caller is already a chain owner.
+pub struct ArbOwnerHandler {
+    address: Address,
+    functions: FunctionTable<ArbOwnerFunction>,
+    state: ChainOwnerState,
+}
+
+impl ArbOwnerHandler {
+    /// Create a handler backed by shared owner state
+    pub fn new(state: ChainOwnerState) -> Self {
+        Self {
+            address: Address::from_hex("0x0000000000000000000000000000000000000070").unwrap(),
+            functions: FunctionTable::new(ARBOWNER_FUNCTIONS),
+            state,
+        }
+    }
+
+    /// Get the owner state backing this handler
+    pub fn state(&self) -> &ChainOwnerState {
+        &self.state
+    }
+}
+
+impl PrecompileHandler for ArbOwnerHandler {
+    fn address_ref(&self) -> &Address {
+        &self.address
+    }
+
+    fn name(&self) -> &str {
+        "ArbOwner"
+    }
+
+    fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        self.handle_call_with_context(input, &CallContext::default(), config)
+    }
+
+    fn handle_call_with_context(
+        &self,
+        input: &[u8],
+        context: &CallContext,
+        _config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
+        let function = self.functions.lookup(input)?;
+        if !self.state.is_owner(&context.caller) {
+            return Err(PrecompileError::Unauthorized { caller: context.caller });
+        }
+
+        match function {
+            ArbOwnerFunction::AddChainOwner => self.handle_add_chain_owner(input),
+            ArbOwnerFunction::RemoveChainOwner => self.handle_remove_chain_owner(input),
+            ArbOwnerFunction::SetL1BaseFeeEstimateInertia => self.handle_set_inertia(input),
+            ArbOwnerFunction::SetL2BaseFee => self.handle_set_l2_base_fee(input),
+        }
+    }
+
+    fn gas_cost(&self, _input: &[u8], config: &ArbitrumConfig) -> u64 {
+        config.precompile_gas_cost(self.name()).unwrap_or(10)
+    }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
+    }
+}
+
+impl ArbOwnerHandler {
+    /// Handle addChainOwner(address) call
+    fn handle_add_chain_owner(&self, input: &[u8]) -> Result<Vec<u8>> {
+        self.state.add_owner(decode_address_arg(input)?);
+        Ok(Vec::new())
+    }
+
+    /// Handle removeChainOwner(address) call
+    fn handle_remove_chain_owner(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let owner = decode_address_arg(input)?;
+        if !self.state.remove_owner(&owner) {
+            return Err(PrecompileError::NotOwner { owner });
+        }
+        Ok(Vec::new())
+    }
+
+    /// Handle setL1BaseFeeEstimateInertia(uint64) call
+    fn handle_set_inertia(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let inertia = decode_u64_arg(input)?;
+        self.state.l1_base_fee_estimate.set_inertia(inertia);
+        Ok(Vec::new())
+    }
+
+    /// Handle setL2BaseFee(uint256) call
+    fn handle_set_l2_base_fee(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let l2_base_fee = decode_u64_arg(input)?;
+        self.state.settings.lock().unwrap().l2_base_fee = Some(l2_base_fee);
+        Ok(Vec::new())
+    }
+}
+
+/// Functions implemented by `ArbOwnerPublicHandler`
+#[derive(Debug, Clone, Copy)]
+enum ArbOwnerPublicFunction {
+    IsChainOwner,
+    GetAllChainOwners,
+}
+
+const ARBOWNERPUBLIC_FUNCTIONS: &[(&str, ArbOwnerPublicFunction)] = &[
+    ("isChainOwner(address)", ArbOwnerPublicFunction::IsChainOwner),
+    ("getAllChainOwners()", ArbOwnerPublicFunction::GetAllChainOwners),
+];
+
+/// ArbOwnerPublic precompile handler (0x6B)
+pub struct ArbOwnerPublicHandler {
+    address: Address,
+    functions: FunctionTable<ArbOwnerPublicFunction>,
+    state: ChainOwnerState,
+}
+
+impl ArbOwnerPublicHandler {
+    /// Create a handler backed by shared owner state
+    pub fn new(state: ChainOwnerState) -> Self {
+        Self {
+            address: Address::from_hex("0x000000000000000000000000000000000000006b").unwrap(),
+            functions: FunctionTable::new(ARBOWNERPUBLIC_FUNCTIONS),
+            state,
+        }
+    }
+}
+
+impl PrecompileHandler for ArbOwnerPublicHandler {
+    fn address_ref(&self) -> &Address {
+        &self.address
+    }
+
+    fn name(&self) -> &str {
+        "ArbOwnerPublic"
+    }
+
+    fn handle_call(&self, input: &[u8], _config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        match self.functions.lookup(input)? {
+            ArbOwnerPublicFunction::IsChainOwner => self.handle_is_chain_owner(input),
+            ArbOwnerPublicFunction::GetAllChainOwners => self.handle_get_all_chain_owners(),
+        }
+    }
+
+    fn gas_cost(&self, _input: &[u8], config: &ArbitrumConfig) -> u64 {
+        config.precompile_gas_cost(self.name()).unwrap_or(3)
+    }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
+    }
+}
+
+impl ArbOwnerPublicHandler {
+    /// Handle isChainOwner(address) call
+    fn handle_is_chain_owner(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let address = decode_address_arg(input)?;
+        Ok(U256::from_u64(self.state.is_owner(&address) as u64).to_big_endian())
+    }
+
+    /// Handle getAllChainOwners() call, returning an ABI-encoded `address[]`
+    fn handle_get_all_chain_owners(&self) -> Result<Vec<u8>> {
+        let owners = self.state.owners();
+        let mut result = U256::from_u64(32).to_big_endian();
+        result.extend_from_slice(&U256::from_u64(owners.len() as u64).to_big_endian());
+        for owner in owners {
+            result.extend_from_slice(&[0u8; 12]);
+            result.extend_from_slice(owner.as_bytes());
+        }
+        Ok(result)
+    }
+}
+
+/// Functions implemented by `ArbDebugHandler`
+#[derive(Debug, Clone, Copy)]
+enum ArbDebugFunction {
+    BecomeChainOwner,
+    Events,
+}
+
+const ARBDEBUG_FUNCTIONS: &[(&str, ArbDebugFunction)] = &[
+    ("becomeChainOwner()", ArbDebugFunction::BecomeChainOwner),
+    ("events(bool,bytes32)", ArbDebugFunction::Events),
+];
+
+/// ArbDebug precompile handler (0xFF)
+///
+/// Every function reverts unless `allow_debug_precompiles` is set in the config.
+pub struct ArbDebugHandler {
+    address: Address,
+    functions: FunctionTable<ArbDebugFunction>,
+    state: ChainOwnerState,
+    logs: LogBuffer,
+}
+
+impl ArbDebugHandler {
+    /// Create a handler backed by shared owner state
+    pub fn new(state: ChainOwnerState) -> Self {
+        Self {
+            address: Address::from_hex("0x00000000000000000000000000000000000000ff").unwrap(),
+            functions: FunctionTable::new(ARBDEBUG_FUNCTIONS),
+            state,
+            logs: LogBuffer::new(),
+        }
+    }
+
+    /// Emit events into the given buffer (e.g. one shared with other handlers)
+    pub fn with_log_buffer(mut self, logs: LogBuffer) -> Self {
+        self.logs = logs;
+        self
+    }
+
+    /// Get the buffer events are emitted into
+    pub fn logs(&self) -> &LogBuffer {
+        &self.logs
+    }
+}
+
+impl PrecompileHandler for ArbDebugHandler {
+    fn address_ref(&self) -> &Address {
+        &self.address
+    }
+
+    fn name(&self) -> &str {
+        "ArbDebug"
+    }
+
+    fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        self.handle_call_with_context(input, &CallContext::default(), config)
+    }
+
+    fn handle_call_with_context(
+        &self,
+        input: &[u8],
+        context: &CallContext,
+        config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
+        let function = self.functions.lookup(input)?;
+        if !config.allow_debug_precompiles {
+            return Err(PrecompileError::DebugPrecompilesDisabled);
+        }
+
+        match function {
+            ArbDebugFunction::BecomeChainOwner => {
+                self.state.add_owner(context.caller);
+                Ok(Vec::new())
+            }
+            ArbDebugFunction::Events => self.handle_events(input, context),
+        }
+    }
+
+    fn gas_cost(&self, _input: &[u8], config: &ArbitrumConfig) -> u64 {
+        config.precompile_gas_cost(self.name()).unwrap_or(10)
+    }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
+    }
+}
+
+impl ArbDebugHandler {
+    /// Handle events(bool,bytes32) call
+    ///
+    /// Emits `Basic(bool flag, bytes32 indexed value)` and `Mixed(bool indexed
+    /// flag, bool not, bytes32 indexed value, address conn, address indexed
+    /// caller)` as Nitro does, and returns the caller and call value.
+    fn handle_events(&self, input: &[u8], context: &CallContext) -> Result<Vec<u8>> {
+        let flag = match decode_u64_arg(input)? {
+            0 => false,
+            1 => true,
+            _ => return Err(PrecompileError::InvalidInput("Boolean argument must be 0 or 1")),
+        };
+        let value: [u8; 32] = input
+            .get(36..68)
+            .ok_or(PrecompileError::InvalidInput("Input too short for bytes32 argument"))?
+            .try_into()
+            .unwrap();
+
+        let bool_word = |b: bool| U256::from_u64(b as u64).to_big_endian();
+        let address_word = |address: &Address| {
+            let mut word = [0u8; 32];
+            word[12..].copy_from_slice(address.as_bytes());
+            word
+        };
+        let topic = |signature: &str| -> [u8; 32] { Keccak256::digest(signature.as_bytes()).into() };
+
+        self.logs.push(PrecompileLog {
+            address: self.address,
+            topics: vec![topic("Basic(bool,bytes32)"), value],
+            data: bool_word(flag),
+        });
+        let mut mixed_data = bool_word(!flag);
+        mixed_data.extend_from_slice(&address_word(&self.address));
+        self.logs.push(PrecompileLog {
+            address: self.address,
+            topics: vec![
+                topic("Mixed(bool,bool,bytes32,address,address)"),
+                bool_word(flag).try_into().unwrap(),
+                value,
+                address_word(&context.caller),
+            ],
+            data: mixed_data,
+        });
+
+        let mut result = address_word(&context.caller).to_vec();
+        result.extend_from_slice(&context.value.to_big_endian());
+        Ok(result)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::precompiles::PrecompileRegistry;
+
+    fn address_call(selector: &str, address: &Address) -> Vec<u8> {
+        let mut input = hex::decode(selector).unwrap();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(address.as_bytes());
+        input
+    }
+
+    fn setup() -> (PrecompileRegistry, Address, Address, Address) {
+        let owner = Address::from_hex("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap();
+        let state = ChainOwnerState::new([owner]);
+        let mut registry = PrecompileRegistry::new();
+        registry.register(Box::new(ArbOwnerHandler::new(state.clone())));
+        registry.register(Box::new(ArbOwnerPublicHandler::new(state)));
+        let arb_owner = Address::from_hex("0x0000000000000000000000000000000000000070").unwrap();
+        let arb_owner_public = Address::from_hex("0x000000000000000000000000000000000000006b").unwrap();
+        (registry, owner, arb_owner, arb_owner_public)
+    }
+
+    #[test]
+    fn test_owner_adds_second_owner() {
+        let (registry, owner, arb_owner, arb_owner_public) = setup();
+        let config = ArbitrumConfig::default();
+        let second = Address::from_hex("0x70997970c51812dc3a010c7d01b50e0d17dc79c8").unwrap();
+        let context = CallContext {
+            caller: owner,
+            ..CallContext::default()
+        };
+
+        let is_owner = address_call("26ef7f68", &second);
+        let result = registry.handle_call(arb_owner_public, &is_owner, &config).unwrap();
+        assert_eq!(result[31], 0);
+
+        registry
+            .handle_call_with_context(arb_owner, &address_call("481f8dbf", &second), &context, &config)
+            .unwrap();
+
+        let result = registry.handle_call(arb_owner_public, &is_owner, &config).unwrap();
+        assert_eq!(result[31], 1);
+
+        let result = registry.handle_call(arb_owner_public, &hex::decode("516b4e0f").unwrap(), &config).unwrap();
+        assert_eq!(U256::from_big_endian(&result[32..64]), U256::from_u64(2));
+        assert_eq!(&result[64 + 12..96], owner.as_bytes());
+        assert_eq!(&result[96 + 12..128], second.as_bytes());
+    }
+
+    #[test]
+    fn test_non_owner_reverts() {
+        let (registry, _, arb_owner, arb_owner_public) = setup();
+        let config = ArbitrumConfig::default();
+        let intruder = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        let context = CallContext {
+            caller: intruder,
+            ..CallContext::default()
+        };
+
+        let err = registry
+            .handle_call_with_context(arb_owner, &address_call("481f8dbf", &intruder), &context, &config)
+            .unwrap_err();
+        assert!(err.to_string().contains("unauthorized"));
+        assert_eq!(err, PrecompileError::Unauthorized { caller: intruder });
+
+        let result = registry
+            .handle_call(arb_owner_public, &address_call("26ef7f68", &intruder), &config)
+            .unwrap();
+        assert_eq!(result[31], 0);
+    }
+
+    #[test]
+    fn test_owner_sets_fee_parameters() {
+        let owner = Address::from_hex("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap();
+        let estimate = L1BaseFeeEstimate::default();
+        let state = ChainOwnerState::new([owner]).with_l1_base_fee_estimate(estimate.clone());
+        let handler = ArbOwnerHandler::new(state.clone());
+        let mut config = ArbitrumConfig::default();
+        let context = CallContext {
+            caller: owner,
+            ..CallContext::default()
+        };
+
+        let mut input = hex::decode("d99bc80e").unwrap();
+        input.extend_from_slice(&U256::from_u64(250_000_000).to_big_endian());
+        handler.handle_call_with_context(&input, &context, &config).unwrap();
+
+        let mut input = hex::decode("718f7805").unwrap();
+        input.extend_from_slice(&U256::from_u64(4).to_big_endian());
+        handler.handle_call_with_context(&input, &context, &config).unwrap();
+
+        // The new inertia weighs the next observation
+        assert_eq!(estimate.inertia(), 4);
+        estimate.observe(0, 20_000_000_000);
+        assert_eq!(estimate.current(20_000_000_000), 15_000_000_000);
+        state.apply_to(&mut config);
+        assert_eq!(config.gas_price_components.l2_base_fee, 250_000_000);
+    }
+
+    #[test]
+    fn test_debug_become_chain_owner() {
+        let state = ChainOwnerState::default();
+        let handler = ArbDebugHandler::new(state.clone());
+        let caller = Address::from_hex("0x70997970c51812dc3a010c7d01b50e0d17dc79c8").unwrap();
+        let context = CallContext {
+            caller,
+            ..CallContext::default()
+        };
+        let input = crate::precompiles::selector("becomeChainOwner()").to_vec();
+
+        let err = handler
+            .handle_call_with_context(&input, &context, &ArbitrumConfig::default())
+            .unwrap_err();
+        assert_eq!(err, PrecompileError::DebugPrecompilesDisabled);
+        assert!(!state.is_owner(&caller));
+
+        let config = ArbitrumConfig {
+            allow_debug_precompiles: true,
+            ..ArbitrumConfig::default()
+        };
+        handler.handle_call_with_context(&input, &context, &config).unwrap();
+        assert!(state.is_owner(&caller));
+
+        // The new owner can now use ArbOwner
+        let second = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        ArbOwnerHandler::new(state.clone())
+            .handle_call_with_context(&address_call("481f8dbf", &second), &context, &config)
+            .unwrap();
+        assert!(state.is_owner(&second));
+    }
+
+    #[test]
+    fn test_debug_events_emits_logs() {
+        let handler = ArbDebugHandler::new(ChainOwnerState::default());
+        let caller = Address::from_hex("0x70997970c51812dc3a010c7d01b50e0d17dc79c8").unwrap();
+        let context = CallContext {
+            caller,
+            value: U256::from_u64(7),
+            ..CallContext::default()
+        };
+        let config = ArbitrumConfig {
+            allow_debug_precompiles: true,
+            ..ArbitrumConfig::default()
+        };
+        let mut input = crate::precompiles::selector("events(bool,bytes32)").to_vec();
+        input.extend_from_slice(&U256::from_u64(1).to_big_endian());
+        input.extend_from_slice(&[0xab; 32]);
+
+        let result = handler.handle_call_with_context(&input, &context, &config).unwrap();
+        assert_eq!(&result[12..32], caller.as_bytes());
+        assert_eq!(U256::from_big_endian(&result[32..64]), U256::from_u64(7));
+
+        let logs = handler.logs().drain();
+        assert_eq!(logs.len(), 2);
+        assert_eq!(logs[0].topics[1], [0xab; 32]);
+        assert_eq!(logs[0].data[31], 1);
+        assert_eq!(logs[1].topics.len(), 4);
+        assert_eq!(&logs[1].topics[3][12..], caller.as_bytes());
+        assert_eq!(logs[1].data[31], 0);
+        assert!(handler.logs().is_empty());
+    }
+
+    #[test]
+    fn test_configured_gas_costs_override_defaults() {
+        let state = ChainOwnerState::default();
+        let handlers: Vec<Box<dyn PrecompileHandler>> = vec![
+            Box::new(ArbOwnerHandler::new(state.clone())),
+            Box::new(ArbOwnerPublicHandler::new(state.clone())),
+            Box::new(ArbDebugHandler::new(state)),
+        ];
+        let defaults = ArbitrumConfig::default();
+        let mut config = ArbitrumConfig::default();
+        for handler in &handlers {
+            config.precompile_gas.insert(handler.name().to_lowercase(), 42);
+        }
+        config.validate().unwrap();
+
+        for handler in &handlers {
+            assert_ne!(handler.gas_cost(&[], &defaults), 42);
+            assert_eq!(handler.gas_cost(&[], &config), 42, "{}", handler.name());
+        }
+        // These names are only known to the gas map, not to --precompiles
+        assert!(config.disable_precompiles(&["arbowner"]).is_err());
+    }
+}
@@ -1,229 +1,650 @@
 //! Arbitrum precompile implementations for Anvil
 
 use crate::arbitrum::ArbitrumConfig;
-use anyhow::{anyhow, Result};
+use crate::gas_pool::{GasPool, L1BaseFeeEstimate, L1FeePool, StorageGas, STORAGE_WRITE_GAS};
+use crate::metrics::Metrics;
+use crate::mock_chain::MockChain;
+use crate::outbox::OutboxAccumulator;
+use crate::owner::ChainOwnerState;
+use crate::selector_registry;
+use crate::state::{InMemoryState, SharedState, StateError};
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt, U256Ext};
+use sha3::{Digest, Keccak256};
+use std::collections::{hash_map::Entry, HashMap};
+use std::sync::{Arc, Mutex};
+
+pub use crate::primitives::{Address, U256};
+
+type Result<T, E = PrecompileError> = std::result::Result<T, E>;
+
+/// Function selectors (lowercase hex, no `0x`) for the ArbSys and ArbGasInfo handlers
+pub mod selectors {
+    // ArbSys
+    /// `arbChainID()`
+    pub const ARB_CHAIN_ID: &str = "d127f54a";
+    /// `arbBlockNumber()`
+    pub const ARB_BLOCK_NUMBER: &str = "a3b1b31d";
+    /// `arbOSVersion()`
+    pub const ARB_OS_VERSION: &str = "051038f2";
+    /// `withdrawEth(address)`
+    pub const WITHDRAW_ETH: &str = "25e16063";
+    /// `sendTxToL1(address,bytes)`
+    pub const SEND_TX_TO_L1: &str = "928c169a";
+    /// `outboxRoot()` (mock extension, not part of Nitro's ArbSys)
+    pub const OUTBOX_ROOT: &str = "880e3a3a";
+    /// `isTopLevelCall()`
+    pub const IS_TOP_LEVEL_CALL: &str = "08bd624c";
+    /// `wasMyCallersAddressAliased()`
+    pub const WAS_MY_CALLERS_ADDRESS_ALIASED: &str = "175a260b";
+    /// `getStorageGasAvailable()`
+    pub const GET_STORAGE_GAS_AVAILABLE: &str = "a94597ff";
+    /// `sendMerkleTreeState()`
+    pub const SEND_MERKLE_TREE_STATE: &str = "7aeecd2a";
+
+    // ArbGasInfo
+    /// `getCurrentTxL1GasFees()`
+    pub const GET_CURRENT_TX_L1_GAS_FEES: &str = "c6f7de0e";
+    /// `getPricesInWei()`
+    pub const GET_PRICES_IN_WEI: &str = "41b247a8";
+    /// `getL1BaseFeeEstimate()`
+    pub const GET_L1_BASE_FEE_ESTIMATE: &str = "f5d6ded7";
+    /// `getPricesInArbGas()`
+    pub const GET_PRICES_IN_ARB_GAS: &str = "02199f34";
+    /// `getL2BaseFeeEstimate()`
+    pub const GET_L2_BASE_FEE_ESTIMATE: &str = "b246b565";
+    /// `getL1GasPriceEstimate()`
+    pub const GET_L1_GAS_PRICE_ESTIMATE: &str = "055f362f";
+    /// `getGasAccountingParams()`
+    pub const GET_GAS_ACCOUNTING_PARAMS: &str = "612af178";
+    /// `getMinimumGasPrice()`
+    pub const GET_MINIMUM_GAS_PRICE: &str = "f918379a";
+    /// `getAmortizedCostCapBips()`
+    pub const GET_AMORTIZED_COST_CAP_BIPS: &str = "7a7d6beb";
+    /// `getL1RewardRate()`
+    pub const GET_L1_REWARD_RATE: &str = "8a5b1d28";
+    /// `getL1BlobBaseFeeEstimate()`
+    pub const GET_L1_BLOB_BASE_FEE_ESTIMATE: &str = "67037bec";
+    /// `getPricesInWeiWithAggregator(address)`
+    pub const GET_PRICES_IN_WEI_WITH_AGGREGATOR: &str = "ba9c916e";
+    /// `getPricesInArbGasWithAggregator(address)`
+    pub const GET_PRICES_IN_ARB_GAS_WITH_AGGREGATOR: &str = "7a1ea732";
+    /// `getL1FeesAvailable()`
+    pub const GET_L1_FEES_AVAILABLE: &str = "5b39d23c";
+
+    /// Every selector constant with the signature it is derived from
+    pub const ALL: &[(&str, &str)] = &[
+        ("arbChainID()", ARB_CHAIN_ID),
+        ("arbBlockNumber()", ARB_BLOCK_NUMBER),
+        ("arbOSVersion()", ARB_OS_VERSION),
+        ("withdrawEth(address)", WITHDRAW_ETH),
+        ("sendTxToL1(address,bytes)", SEND_TX_TO_L1),
+        ("outboxRoot()", OUTBOX_ROOT),
+        ("isTopLevelCall()", IS_TOP_LEVEL_CALL),
+        ("wasMyCallersAddressAliased()", WAS_MY_CALLERS_ADDRESS_ALIASED),
+        ("getStorageGasAvailable()", GET_STORAGE_GAS_AVAILABLE),
+        ("sendMerkleTreeState()", SEND_MERKLE_TREE_STATE),
+        ("getCurrentTxL1GasFees()", GET_CURRENT_TX_L1_GAS_FEES),
+        ("getPricesInWei()", GET_PRICES_IN_WEI),
+        ("getL1BaseFeeEstimate()", GET_L1_BASE_FEE_ESTIMATE),
+        ("getPricesInArbGas()", GET_PRICES_IN_ARB_GAS),
+        ("getL2BaseFeeEstimate()", GET_L2_BASE_FEE_ESTIMATE),
+        ("getL1GasPriceEstimate()", GET_L1_GAS_PRICE_ESTIMATE),
+        ("getGasAccountingParams()", GET_GAS_ACCOUNTING_PARAMS),
+        ("getMinimumGasPrice()", GET_MINIMUM_GAS_PRICE),
+        ("getAmortizedCostCapBips()", GET_AMORTIZED_COST_CAP_BIPS),
+        ("getL1RewardRate()", GET_L1_REWARD_RATE),
+        ("getL1BlobBaseFeeEstimate()", GET_L1_BLOB_BASE_FEE_ESTIMATE),
+        ("getPricesInWeiWithAggregator(address)", GET_PRICES_IN_WEI_WITH_AGGREGATOR),
+        ("getPricesInArbGasWithAggregator(address)", GET_PRICES_IN_ARB_GAS_WITH_AGGREGATOR),
+        ("getL1FeesAvailable()", GET_L1_FEES_AVAILABLE),
+    ];
+}
 
-/// Simple address type (20 bytes)
-#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
-pub struct Address([u8; 20]);
+/// An error returned by a precompile call
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum PrecompileError {
+    #[error("Input too short for function selector")]
+    MissingSelector,
+    /// The selector is not implemented; the message names it if it is a known Arbitrum function
+    #[error("Unknown function selector: 0x{} ({})", hex::encode(.selector), selector_registry::name(*.selector))]
+    UnknownSelector { selector: [u8; 4] },
+    #[error(
+        "Unknown function selector: 0x{} ({}, requires ArbOS {min_version}, configured {configured})",
+        hex::encode(.selector),
+        selector_registry::name(*.selector)
+    )]
+    RequiresArbOsVersion { selector: [u8; 4], min_version: u32, configured: u32 },
+    /// Malformed call arguments
+    #[error("{0}")]
+    InvalidInput(&'static str),
+    #[error("Retryable ticket not found: 0x{}", hex::encode(.id))]
+    TicketNotFound { id: [u8; 32] },
+    #[error("Retryable ticket 0x{} expired at {timeout}", hex::encode(.id))]
+    TicketExpired { id: [u8; 32], timeout: u64 },
+    #[error("Outbox leaf {index} out of range ({leaves} leaves)")]
+    OutboxLeafOutOfRange { index: u64, leaves: usize },
+    #[error("unauthorized caller to access-controlled method: {caller}")]
+    Unauthorized { caller: Address },
+    #[error("tried to remove non-owner {owner}")]
+    NotOwner { owner: Address },
+    #[error("No precompile handler found for address {address}")]
+    NoHandler { address: Address },
+    #[error("debug precompiles are disabled")]
+    DebugPrecompilesDisabled,
+    #[error("Arguments do not match signature '{signature}'")]
+    ArgumentMismatch { signature: String },
+    /// The call's account changes failed, e.g. a withdrawal larger than the balance
+    #[error(transparent)]
+    State(#[from] StateError),
+}
 
-impl Address {
-    pub fn new(bytes: [u8; 20]) -> Self {
-        Self(bytes)
-    }
-    
-    pub fn as_bytes(&self) -> &[u8; 20] {
-        &self.0
-    }
-    
-    pub fn from_hex(hex: &str) -> Result<Self> {
-        let hex = hex.strip_prefix("0x").unwrap_or(hex);
-        if hex.len() != 40 {
-            return Err(anyhow!("Invalid address length"));
-        }
-        
-        let mut bytes = [0u8; 20];
-        for (i, chunk) in hex.as_bytes().chunks(2).enumerate() {
-            if i >= 20 {
-                break;
-            }
-            let byte = u8::from_str_radix(
-                std::str::from_utf8(chunk)?,
-                16
-            )?;
-            bytes[i] = byte;
+/// Compute the 4-byte function selector for a signature such as `"getPricesInWei()"`
+pub fn selector(signature: &str) -> [u8; 4] {
+    let hash = Keccak256::digest(signature.as_bytes());
+    [hash[0], hash[1], hash[2], hash[3]]
+}
+
+/// Selector dispatch table for a handler, built from human-readable signatures
+pub(crate) struct FunctionTable<F> {
+    signatures: Vec<&'static str>,
+    /// Function and minimum calldata length, keyed by selector
+    functions: HashMap<[u8; 4], (F, usize)>,
+}
+
+impl<F: Copy> FunctionTable<F> {
+    /// Build the table from `(signature, function)` pairs
+    pub(crate) fn new(table: &[(&'static str, F)]) -> Self {
+        Self {
+            signatures: table.iter().map(|(signature, _)| *signature).collect(),
+            functions: table
+                .iter()
+                .map(|(signature, function)| (selector(signature), (*function, min_calldata_len(signature))))
+                .collect(),
         }
-        
-        Ok(Self(bytes))
     }
-}
 
-impl std::fmt::Display for Address {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        write!(f, "0x")?;
-        for byte in &self.0 {
-            write!(f, "{:02x}", byte)?;
+    /// Resolve the function named by the selector at the start of `input`
+    ///
+    /// Fails if `input` is too short to hold a head word for every argument.
+    pub(crate) fn lookup(&self, input: &[u8]) -> Result<F> {
+        let selector: [u8; 4] = input
+            .get(0..4)
+            .and_then(|s| s.try_into().ok())
+            .ok_or(PrecompileError::MissingSelector)?;
+        let (function, min_len) = self
+            .functions
+            .get(&selector)
+            .copied()
+            .ok_or(PrecompileError::UnknownSelector { selector })?;
+        if input.len() < min_len {
+            return Err(PrecompileError::InvalidInput("Input too short for function arguments"));
         }
-        Ok(())
+        Ok(function)
     }
-}
 
-impl std::str::FromStr for Address {
-    type Err = anyhow::Error;
-    
-    fn from_str(s: &str) -> Result<Self, Self::Err> {
-        Self::from_hex(s)
+    /// Signatures in table order
+    pub(crate) fn signatures(&self) -> &[&'static str] {
+        &self.signatures
     }
 }
 
-/// Simple U256 type
-#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord)]
-pub struct U256([u8; 32]);
+/// Split a signature such as `"foo(address,uint256)"` into its name and input types
+fn parse_signature(signature: &str) -> (&str, Vec<&str>) {
+    let (name, rest) = signature.split_once('(').unwrap_or((signature, ")"));
+    let params = rest.trim_end_matches(')');
+    let inputs = if params.is_empty() { Vec::new() } else { params.split(',').collect() };
+    (name, inputs)
+}
 
-impl U256 {
-    pub fn new(bytes: [u8; 32]) -> Self {
-        Self(bytes)
-    }
-    
-    pub fn from_u64(value: u64) -> Self {
-        let mut bytes = [0u8; 32];
-        bytes[24..32].copy_from_slice(&value.to_be_bytes());
-        Self(bytes)
-    }
-    
-    pub fn from_big_endian(bytes: &[u8]) -> Self {
-        let mut result = [0u8; 32];
-        let start = 32 - bytes.len().min(32);
-        result[start..].copy_from_slice(&bytes[..bytes.len().min(32)]);
-        Self(result)
-    }
-    
-    pub fn to_big_endian(&self) -> Vec<u8> {
-        self.0.to_vec()
-    }
-    
-    pub fn zero() -> Self {
-        Self([0u8; 32])
-    }
+/// Selector plus one 32-byte head word per argument
+///
+/// Dynamic arguments such as `bytes` also take one head word (their offset), so
+/// this is a lower bound for any valid call.
+fn min_calldata_len(signature: &str) -> usize {
+    4 + 32 * parse_signature(signature).1.len()
+}
 
-    fn max_value() -> Self {
-        Self([0xff; 32])
-    }
+/// Output types of known precompile functions, used for best-effort ABI generation
+const KNOWN_OUTPUTS: &[(&str, &[&str])] = &[
+    ("arbChainID()", &["uint256"]),
+    ("arbBlockNumber()", &["uint256"]),
+    ("arbOSVersion()", &["uint256"]),
+    ("withdrawEth(address)", &["uint256"]),
+    ("sendTxToL1(address,bytes)", &["uint256"]),
+    ("outboxRoot()", &["bytes32"]),
+    ("isTopLevelCall()", &["bool"]),
+    ("wasMyCallersAddressAliased()", &["bool"]),
+    ("sendMerkleTreeState()", &["uint256", "bytes32", "bytes32[]"]),
+    ("getCurrentTxL1GasFees()", &["uint256"]),
+    ("getPricesInWei()", &["uint256", "uint256", "uint256", "uint256", "uint256", "uint256"]),
+    ("getPricesInWeiWithAggregator(address)", &["uint256", "uint256", "uint256", "uint256", "uint256", "uint256"]),
+    ("getL1BaseFeeEstimate()", &["uint256"]),
+    ("getPricesInArbGas()", &["uint256", "uint256", "uint256"]),
+    ("getPricesInArbGasWithAggregator(address)", &["uint256", "uint256", "uint256"]),
+    ("getL2BaseFeeEstimate()", &["uint256"]),
+    ("getL1GasPriceEstimate()", &["uint256"]),
+    ("getGasAccountingParams()", &["uint256", "uint256", "uint256"]),
+    ("getMinimumGasPrice()", &["uint256"]),
+    ("getAmortizedCostCapBips()", &["uint64"]),
+    ("getL1RewardRate()", &["uint64"]),
+    ("getL1BlobBaseFeeEstimate()", &["uint256"]),
+    ("getLifetime()", &["uint256"]),
+    ("getTimeout(bytes32)", &["uint256"]),
+    ("getBeneficiary(bytes32)", &["address"]),
+    ("redeem(bytes32)", &["bytes32"]),
+    ("depositEth(address,uint256)", &["uint256"]),
+    ("isChainOwner(address)", &["bool"]),
+    ("getAllChainOwners()", &["address[]"]),
+];
+
+/// State mutability of functions that are not `view`
+const STATE_CHANGING_FUNCTIONS: &[(&str, &str)] = &[
+    ("withdrawEth(address)", "payable"),
+    ("sendTxToL1(address,bytes)", "payable"),
+    ("redeem(bytes32)", "nonpayable"),
+    ("cancel(bytes32)", "nonpayable"),
+    ("depositEth(address,uint256)", "nonpayable"),
+    ("addChainOwner(address)", "nonpayable"),
+    ("removeChainOwner(address)", "nonpayable"),
+    ("setL1BaseFeeEstimateInertia(uint64)", "nonpayable"),
+    ("setL2BaseFee(uint256)", "nonpayable"),
+    ("becomeChainOwner()", "nonpayable"),
+    ("events(bool,bytes32)", "payable"),
+];
+
+/// Whether `input` calls a function that changes state, e.g. `withdrawEth` or `becomeChainOwner`
+///
+/// Such calls are rejected from read-only entry points like `eth_call`, whose
+/// effects would otherwise persist.
+pub fn is_state_changing(input: &[u8]) -> bool {
+    input.get(..4).is_some_and(|prefix| {
+        STATE_CHANGING_FUNCTIONS
+            .iter()
+            .any(|(signature, _)| selector(signature) == prefix)
+    })
+}
 
-    // --- Math Implementations ---
+/// Build a minimal ABI entry for `signature`
+///
+/// Outputs are filled in for known functions and left empty otherwise.
+fn abi_entry(signature: &str) -> serde_json::Value {
+    let (name, inputs) = parse_signature(signature);
+    let outputs = KNOWN_OUTPUTS
+        .iter()
+        .find(|(known, _)| *known == signature)
+        .map_or(&[][..], |(_, outputs)| *outputs);
+    let state_mutability = STATE_CHANGING_FUNCTIONS
+        .iter()
+        .find(|(known, _)| *known == signature)
+        .map_or("view", |(_, mutability)| *mutability);
+    let params = |types: &[&str]| -> Vec<serde_json::Value> {
+        types
+            .iter()
+            .map(|ty| serde_json::json!({ "name": "", "type": ty }))
+            .collect()
+    };
+
+    serde_json::json!({
+        "type": "function",
+        "name": name,
+        "inputs": params(&inputs),
+        "outputs": params(outputs),
+        "stateMutability": state_mutability,
+    })
+}
 
-    pub fn saturating_add(&self, other: Self) -> Self {
-        let (res, overflow) = self.overflowing_add(other);
-        if overflow {
-            Self::max_value()
-        } else {
-            res
-        }
+/// Decode a 32-byte ABI word that must fit in a u64
+pub(crate) fn decode_u64_word(word: &[u8]) -> Option<u64> {
+    if word.len() != 32 || word[..24].iter().any(|b| *b != 0) {
+        return None;
     }
+    Some(u64::from_be_bytes(word[24..].try_into().unwrap()))
+}
 
-    pub fn saturating_mul(&self, other: Self) -> Self {
-        let a_limbs = self.to_u64_limbs();
-        let b_limbs = other.to_u64_limbs();
+/// ABI-encode a boolean as a 32-byte word
+fn abi_bool(value: bool) -> Vec<u8> {
+    U256::from_u64(value as u64).to_big_endian()
+}
 
-        // 4x4 limb multiplication
-        let mut res_limbs = [0u64; 8];
+/// A call argument for `PrecompileRegistry::call_by_signature`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AbiValue {
+    Address(Address),
+    /// Any `uintN`
+    Uint(U256),
+    Bool(bool),
+    /// `bytes32`
+    FixedBytes([u8; 32]),
+    /// Dynamic `bytes`
+    Bytes(Vec<u8>),
+}
 
-        for i in 0..4 {
-            let mut carry = 0u64;
-            for j in 0..4 {
-                let product = (a_limbs[i] as u128) * (b_limbs[j] as u128);
-                let sum = (res_limbs[i + j] as u128) + product + (carry as u128);
-                
-                res_limbs[i + j] = sum as u64; // Low part
-                carry = (sum >> 64) as u64;    // High part
-            }
-            res_limbs[i + 4] += carry;
+impl AbiValue {
+    /// Check whether this value can be passed as a parameter of type `ty`
+    fn matches(&self, ty: &str) -> bool {
+        match self {
+            AbiValue::Address(_) => ty == "address",
+            AbiValue::Uint(_) => ty.starts_with("uint"),
+            AbiValue::Bool(_) => ty == "bool",
+            AbiValue::FixedBytes(_) => ty == "bytes32",
+            AbiValue::Bytes(_) => ty == "bytes",
         }
+    }
+}
 
-
-        if res_limbs[4..8].iter().any(|&x| x != 0) {
-            return Self::max_value();
+/// ABI-encode `args` as a tuple: one head word each, then the dynamic tails
+pub fn abi_encode(args: &[AbiValue]) -> Vec<u8> {
+    let mut head = Vec::with_capacity(32 * args.len());
+    let mut tail = Vec::new();
+    for arg in args {
+        match arg {
+            AbiValue::Address(address) => {
+                head.extend_from_slice(&[0u8; 12]);
+                head.extend_from_slice(address.as_bytes());
+            }
+            AbiValue::Uint(value) => head.extend_from_slice(&value.to_big_endian()),
+            AbiValue::Bool(value) => head.extend_from_slice(&abi_bool(*value)),
+            AbiValue::FixedBytes(bytes) => head.extend_from_slice(bytes),
+            AbiValue::Bytes(bytes) => {
+                // Head holds the offset of a length-prefixed, zero-padded tail
+                let offset = 32 * args.len() + tail.len();
+                head.extend_from_slice(&U256::from_u64(offset as u64).to_big_endian());
+                tail.extend_from_slice(&U256::from_u64(bytes.len() as u64).to_big_endian());
+                tail.extend_from_slice(bytes);
+                tail.resize(tail.len().next_multiple_of(32), 0);
+            }
         }
-
-        Self::from_u64_limbs(&res_limbs[0..4])
     }
+    head.extend_from_slice(&tail);
+    head
+}
 
-    // --- Internal Helpers ---
-
-    fn overflowing_add(&self, other: Self) -> (Self, bool) {
-        let mut result = [0u8; 32];
-        let mut carry = 0u16;
+/// Calldata of the enclosing transaction
+///
+/// Kept whole rather than as byte counts, since the configured compression
+/// model prices the calldata by its content.
+#[derive(Debug, Clone, Default, PartialEq, Eq)]
+pub struct TxCalldata(Vec<u8>);
 
-        for i in (0..32).rev() {
-            let sum = (self.0[i] as u16) + (other.0[i] as u16) + carry;
-            result[i] = (sum & 0xff) as u8;
-            carry = sum >> 8;
-        }
+impl TxCalldata {
+    pub fn new(data: &[u8]) -> Self {
+        Self(data.to_vec())
+    }
 
-        (Self(result), carry > 0)
+    /// The calldata bytes
+    pub fn as_bytes(&self) -> &[u8] {
+        &self.0
     }
 
-    fn to_u64_limbs(&self) -> [u64; 4] {
-        let mut limbs = [0u64; 4];
-        for i in 0..4 {
-            let start = 24 - (i * 8);
-            let end = 32 - (i * 8);
-            let mut chunk = [0u8; 8];
-            chunk.copy_from_slice(&self.0[start..end]);
-            limbs[i] = u64::from_be_bytes(chunk);
-        }
-        limbs
+    /// Calldata length in bytes
+    pub fn len(&self) -> usize {
+        self.0.len()
     }
 
-    fn from_u64_limbs(limbs: &[u64]) -> Self {
-        let mut bytes = [0u8; 32];
-        for i in 0..4 {
-            let chunk = limbs[i].to_be_bytes();
-            let start = 24 - (i * 8);
-            let end = 32 - (i * 8);
-            bytes[start..end].copy_from_slice(&chunk);
-        }
-        Self(bytes)
+    /// Check if the transaction carried no calldata
+    pub fn is_empty(&self) -> bool {
+        self.0.is_empty()
     }
 }
 
+/// Caller-side information for a precompile call
+///
+/// The default context describes a top-level call from the zero address with
+/// no value attached, no L1 address aliasing and empty transaction calldata.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct CallContext {
+    /// Address that called the precompile
+    pub caller: Address,
+    /// Wei sent along with the call
+    pub value: U256,
+    /// Call depth, where 1 is a call made directly by the transaction
+    pub depth: u32,
+    /// Whether the caller is an aliased L1 contract address
+    pub aliased: bool,
+    /// Calldata of the transaction that made the call
+    pub tx_calldata: TxCalldata,
+}
 
-impl std::ops::Add for U256 {
-    type Output = Self;
-    
-    fn add(self, other: Self) -> Self {
-        let mut result = [0u8; 32];
-        let mut carry = 0u16;
-        
-        for i in (0..32).rev() {
-            let sum = self.0[i] as u16 + other.0[i] as u16 + carry;
-            result[i] = (sum & 0xff) as u8;
-            carry = sum >> 8;
-        }
-        
-        Self(result)
+impl CallContext {
+    /// Describe the calldata of the enclosing transaction
+    pub fn with_tx_calldata(mut self, data: &[u8]) -> Self {
+        self.tx_calldata = TxCalldata::new(data);
+        self
     }
 }
 
-impl std::fmt::Display for U256 {
-    fn fmt(&self, f: &mut std::fmt::Formatter<'_>) -> std::fmt::Result {
-        // Convert to hex string for display
-        write!(f, "0x")?;
-        for byte in &self.0 {
-            write!(f, "{:02x}", byte)?;
+impl Default for CallContext {
+    fn default() -> Self {
+        Self {
+            caller: Address::ZERO,
+            value: U256::zero(),
+            depth: 1,
+            aliased: false,
+            tx_calldata: TxCalldata::default(),
         }
-        Ok(())
     }
 }
 
 /// Precompile handler trait
 pub trait PrecompileHandler: Send + Sync {
+    /// Borrow the precompile address
+    fn address_ref(&self) -> &Address;
     /// Get the precompile address
-    fn address(&self) -> Address;
+    fn address(&self) -> Address {
+        *self.address_ref()
+    }
     /// Get the precompile name
     fn name(&self) -> &str;
     /// Handle a precompile call
     fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>>;
+    /// Handle a precompile call that needs the caller context
+    ///
+    /// Handlers that ignore the caller only implement `handle_call`.
+    fn handle_call_with_context(
+        &self,
+        input: &[u8],
+        _context: &CallContext,
+        config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
+        self.handle_call(input, config)
+    }
     /// Get the gas cost for the call
-    fn gas_cost(&self, input: &[u8]) -> u64;
+    ///
+    /// Handlers named in `config.precompile_gas` charge the configured cost instead of their own.
+    fn gas_cost(&self, input: &[u8], config: &ArbitrumConfig) -> u64;
+    /// Get the human-readable signatures of the functions this handler supports
+    fn signatures(&self) -> &[&'static str] {
+        &[]
+    }
+    /// Get the function signatures and selectors this handler recognizes
+    fn supported_selectors(&self) -> Vec<(String, [u8; 4])> {
+        self.signatures()
+            .iter()
+            .map(|signature| (signature.to_string(), selector(signature)))
+            .collect()
+    }
+}
+
+/// L2-to-L1 message recorded by `ArbSys.withdrawEth` or `ArbSys.sendTxToL1`
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Withdrawal {
+    /// Withdrawal id returned to the caller, also its outbox leaf index
+    pub id: u64,
+    /// L2 address that sent the message
+    pub caller: Address,
+    /// L1 address receiving the funds
+    pub destination: Address,
+    /// Amount withdrawn in wei
+    pub value: U256,
+    /// Calldata for the L1 call, empty for plain ETH withdrawals
+    pub data: Vec<u8>,
+}
+
+impl Withdrawal {
+    /// Outbox leaf: keccak256(caller ‖ destination ‖ uint256 id ‖ uint256 value ‖ data)
+    pub fn leaf_hash(&self) -> [u8; 32] {
+        let mut hasher = Keccak256::new();
+        hasher.update(self.caller.as_bytes());
+        hasher.update(self.destination.as_bytes());
+        hasher.update(U256::from_u64(self.id).to_big_endian());
+        hasher.update(self.value.to_big_endian());
+        hasher.update(&self.data);
+        hasher.finalize().into()
+    }
+}
+
+/// Shared record of L2-to-L1 withdrawals, in id order
+///
+/// Every recorded withdrawal is appended to the store's outbox accumulator.
+/// Cloning the store shares the underlying list and accumulator.
+#[derive(Debug, Clone, Default)]
+pub struct WithdrawalStore {
+    withdrawals: Arc<Mutex<Vec<Withdrawal>>>,
+    outbox: OutboxAccumulator,
+}
+
+impl WithdrawalStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a withdrawal and append its outbox leaf, returning its id
+    pub fn push(&self, caller: Address, destination: Address, value: U256, data: Vec<u8>) -> u64 {
+        let mut withdrawals = self.withdrawals.lock().unwrap();
+        let withdrawal = Withdrawal {
+            id: withdrawals.len() as u64,
+            caller,
+            destination,
+            value,
+            data,
+        };
+        let id = self.outbox.append(withdrawal.leaf_hash());
+        debug_assert_eq!(id, withdrawal.id);
+        withdrawals.push(withdrawal);
+        id
+    }
+
+    /// Get the outbox accumulator fed by this store
+    pub fn outbox(&self) -> &OutboxAccumulator {
+        &self.outbox
+    }
+
+    /// Get a withdrawal by id
+    pub fn get(&self, id: u64) -> Option<Withdrawal> {
+        let index = usize::try_from(id).ok()?;
+        self.withdrawals.lock().unwrap().get(index).cloned()
+    }
+
+    /// Number of recorded withdrawals
+    pub fn len(&self) -> usize {
+        self.withdrawals.lock().unwrap().len()
+    }
+
+    /// Check if no withdrawals have been recorded
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Functions implemented by `ArbSysHandler`
+#[derive(Debug, Clone, Copy)]
+enum ArbSysFunction {
+    ArbChainId,
+    ArbBlockNumber,
+    ArbOsVersion,
+    WithdrawEth,
+    SendTxToL1,
+    OutboxRoot,
+    IsTopLevelCall,
+    WasMyCallersAddressAliased,
+    GetStorageGasAvailable,
+    SendMerkleTreeState,
 }
 
+const ARBSYS_FUNCTIONS: &[(&str, ArbSysFunction)] = &[
+    ("arbChainID()", ArbSysFunction::ArbChainId),
+    ("arbBlockNumber()", ArbSysFunction::ArbBlockNumber),
+    ("arbOSVersion()", ArbSysFunction::ArbOsVersion),
+    ("withdrawEth(address)", ArbSysFunction::WithdrawEth),
+    ("sendTxToL1(address,bytes)", ArbSysFunction::SendTxToL1),
+    ("outboxRoot()", ArbSysFunction::OutboxRoot),
+    ("isTopLevelCall()", ArbSysFunction::IsTopLevelCall),
+    ("wasMyCallersAddressAliased()", ArbSysFunction::WasMyCallersAddressAliased),
+    ("getStorageGasAvailable()", ArbSysFunction::GetStorageGasAvailable),
+    ("sendMerkleTreeState()", ArbSysFunction::SendMerkleTreeState),
+];
+
 /// ArbSys precompile handler (0x64)
 pub struct ArbSysHandler {
     address: Address,
+    functions: FunctionTable<ArbSysFunction>,
+    withdrawals: WithdrawalStore,
+    chain: MockChain,
+    storage_gas: StorageGas,
+    state: SharedState,
 }
 
 impl ArbSysHandler {
     pub fn new() -> Self {
+        Self::with_withdrawals(WithdrawalStore::new())
+    }
+
+    /// Create a handler backed by an existing withdrawal store
+    pub fn with_withdrawals(withdrawals: WithdrawalStore) -> Self {
         Self {
             address: Address::from_hex("0x0000000000000000000000000000000000000064").unwrap(),
+            functions: FunctionTable::new(ARBSYS_FUNCTIONS),
+            withdrawals,
+            chain: MockChain::default(),
+            storage_gas: StorageGas::default(),
+            state: InMemoryState::new().shared(),
         }
     }
+
+    /// Report block numbers from the given mock chain
+    pub fn with_mock_chain(mut self, chain: MockChain) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Get the withdrawal store backing this handler
+    pub fn withdrawals(&self) -> &WithdrawalStore {
+        &self.withdrawals
+    }
+
+    /// Report and drain the given storage gas (e.g. one shared with the processor)
+    pub fn with_storage_gas(mut self, storage_gas: StorageGas) -> Self {
+        self.storage_gas = storage_gas;
+        self
+    }
+
+    /// Get the storage gas L2-to-L1 messages drain
+    pub fn storage_gas(&self) -> &StorageGas {
+        &self.storage_gas
+    }
+
+    /// Debit the value of L2-to-L1 messages from the given state (e.g. one shared with the processor)
+    pub fn with_state_backend(mut self, state: SharedState) -> Self {
+        self.state = state;
+        self
+    }
+
+    /// Get the state L2-to-L1 message values are debited from
+    pub fn state_backend(&self) -> &SharedState {
+        &self.state
+    }
+}
+
+impl Default for ArbSysHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PrecompileHandler for ArbSysHandler {
-    fn address(&self) -> Address {
-        self.address.clone()
+    fn address_ref(&self) -> &Address {
+        &self.address
     }
 
     fn name(&self) -> &str {
@@ -231,28 +652,53 @@ impl PrecompileHandler for ArbSysHandler {
     }
 
     fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        if input.len() < 4 {
-            return Err(anyhow!("Input too short for function selector"));
-        }
-
-        // Extract function selector (first 4 bytes)
-        let selector = &input[0..4];
-        let selector_hex = hex::encode(selector);
+        self.handle_call_with_context(input, &CallContext::default(), config)
+    }
 
-        match selector_hex.as_str() {
-            "d127f54a" => self.handle_arb_chain_id(config),           // arbChainID()
-            "a3b1b31d" => self.handle_arb_block_number(config),        // arbBlockNumber()
-            "051038f2" => self.handle_arb_os_version(config),         // arbOSVersion()
-            _ => Err(anyhow!("Unknown function selector: 0x{}", selector_hex)),
+    fn handle_call_with_context(
+        &self,
+        input: &[u8],
+        context: &CallContext,
+        config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
+        match self.functions.lookup(input)? {
+            ArbSysFunction::ArbChainId => self.handle_arb_chain_id(config),
+            ArbSysFunction::ArbBlockNumber => self.handle_arb_block_number(config),
+            ArbSysFunction::ArbOsVersion => self.handle_arb_os_version(config),
+            ArbSysFunction::WithdrawEth => self.handle_withdraw_eth(input, context),
+            ArbSysFunction::SendTxToL1 => self.handle_send_tx_to_l1(input, context),
+            ArbSysFunction::OutboxRoot => Ok(self.withdrawals.outbox().root().to_vec()),
+            ArbSysFunction::IsTopLevelCall => Ok(abi_bool(context.depth == 1)),
+            ArbSysFunction::WasMyCallersAddressAliased => Ok(abi_bool(context.aliased)),
+            ArbSysFunction::GetStorageGasAvailable => Ok(U256::from_u64(self.storage_gas.available()).to_big_endian()),
+            ArbSysFunction::SendMerkleTreeState => Ok(self.handle_send_merkle_tree_state()),
         }
     }
 
-    fn gas_cost(&self, _input: &[u8]) -> u64 {
-        3 // Minimal gas cost for simple calls
+    fn gas_cost(&self, _input: &[u8], config: &ArbitrumConfig) -> u64 {
+        config.precompile_gas_cost(self.name()).unwrap_or(3) // Minimal gas cost for simple calls
+    }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
     }
 }
 
 impl ArbSysHandler {
+    /// Handle sendMerkleTreeState(), returning `(uint256 size, bytes32 root, bytes32[] partials)`
+    fn handle_send_merkle_tree_state(&self) -> Vec<u8> {
+        let outbox = self.withdrawals.outbox();
+        let partials = outbox.partials();
+        let mut result = U256::from_u64(outbox.len() as u64).to_big_endian();
+        result.extend_from_slice(&outbox.root());
+        result.extend_from_slice(&U256::from_u64(96).to_big_endian());
+        result.extend_from_slice(&U256::from_u64(partials.len() as u64).to_big_endian());
+        for partial in partials {
+            result.extend_from_slice(&partial);
+        }
+        result
+    }
+
     /// Handle arbChainID() call
     fn handle_arb_chain_id(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
         let chain_id = U256::from_u64(config.chain_id);
@@ -261,9 +707,7 @@ impl ArbSysHandler {
 
     /// Handle arbBlockNumber() call
     fn handle_arb_block_number(&self, _config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        // For now, return a mock block number
-        // In a real implementation, this would query the current block
-        let block_number = U256::from_u64(1u64);
+        let block_number = U256::from_u64(self.chain.block_number());
         Ok(block_number.to_big_endian())
     }
 
@@ -272,43 +716,179 @@ impl ArbSysHandler {
         let version = U256::from_u64(config.arb_os_version as u64);
         Ok(version.to_big_endian())
     }
+
+    /// Handle withdrawEth(address) call, debiting and recording the attached value
+    ///
+    /// Reverts without recording anything if the caller cannot cover the value.
+    fn handle_withdraw_eth(&self, input: &[u8], context: &CallContext) -> Result<Vec<u8>> {
+        let arg = input
+            .get(4..36)
+            .ok_or(PrecompileError::InvalidInput("withdrawEth expects a destination address"))?;
+        let destination = Address::new(arg[12..32].try_into().unwrap());
+
+        self.state.debit(&context.caller, context.value)?;
+        let id = self.withdrawals.push(context.caller, destination, context.value, Vec::new());
+        self.storage_gas.consume(STORAGE_WRITE_GAS);
+        Ok(U256::from_u64(id).to_big_endian())
+    }
+
+    /// Handle sendTxToL1(address,bytes) call, debiting the attached value and recording it with the calldata
+    ///
+    /// Reverts without recording anything if the caller cannot cover the value.
+    fn handle_send_tx_to_l1(&self, input: &[u8], context: &CallContext) -> Result<Vec<u8>> {
+        let args = input
+            .get(4..)
+            .filter(|args| args.len() >= 64)
+            .ok_or(PrecompileError::InvalidInput("sendTxToL1 expects a destination address and calldata"))?;
+        let destination = Address::new(args[12..32].try_into().unwrap());
+
+        // Dynamic `bytes`: head word holds the offset of a length-prefixed payload
+        let offset = decode_u64_word(&args[32..64])
+            .and_then(|offset| usize::try_from(offset).ok())
+            .ok_or(PrecompileError::InvalidInput("sendTxToL1 calldata offset out of range"))?;
+        let length = args
+            .get(offset..offset.saturating_add(32))
+            .and_then(decode_u64_word)
+            .and_then(|length| usize::try_from(length).ok())
+            .ok_or(PrecompileError::InvalidInput("sendTxToL1 calldata offset out of range"))?;
+        let data = args
+            .get(offset + 32..(offset + 32).saturating_add(length))
+            .ok_or(PrecompileError::InvalidInput("sendTxToL1 calldata shorter than its declared length"))?;
+
+        self.state.debit(&context.caller, context.value)?;
+        let id = self.withdrawals.push(context.caller, destination, context.value, data.to_vec());
+        self.storage_gas.consume(STORAGE_WRITE_GAS);
+        Ok(U256::from_u64(id).to_big_endian())
+    }
 }
 
 
 //  Struct to hold the accounting parameters internally
 struct GasAccountingParams {
-    speed_limit_per_second: u64,
-    gas_pool_max: u64,
-    max_tx_gas_limit: u64,
     amortized_cost_cap_bips: u64,
+    l1_reward_rate: u64,
 }
 
+/// Functions implemented by `ArbGasInfoHandler`
+#[derive(Debug, Clone, Copy)]
+enum ArbGasInfoFunction {
+    GetCurrentTxL1GasFees,
+    GetPricesInWei,
+    GetL1BaseFeeEstimate,
+    GetPricesInArbGas,
+    GetL2BaseFeeEstimate,
+    GetL1GasPriceEstimate,
+    GetGasAccountingParams,
+    GetMinimumGasPrice,
+    GetAmortizedCostCapBips,
+    GetL1RewardRate,
+    GetL1BlobBaseFeeEstimate,
+    GetPricesInWeiWithAggregator,
+    GetPricesInArbGasWithAggregator,
+    GetL1FeesAvailable,
+}
+
+impl ArbGasInfoFunction {
+    /// ArbOS version that introduced the function, if it is gated
+    ///
+    /// A gated function behaves like an unknown selector on older versions,
+    /// matching how Nitro hides not-yet-activated methods.
+    fn min_arbos_version(self) -> Option<u32> {
+        match self {
+            Self::GetL1RewardRate => Some(11),
+            Self::GetL1BlobBaseFeeEstimate => Some(20),
+            _ => None,
+        }
+    }
+}
+
+const ARBGASINFO_FUNCTIONS: &[(&str, ArbGasInfoFunction)] = &[
+    ("getCurrentTxL1GasFees()", ArbGasInfoFunction::GetCurrentTxL1GasFees),
+    ("getPricesInWei()", ArbGasInfoFunction::GetPricesInWei),
+    ("getL1BaseFeeEstimate()", ArbGasInfoFunction::GetL1BaseFeeEstimate),
+    ("getPricesInArbGas()", ArbGasInfoFunction::GetPricesInArbGas),
+    ("getL2BaseFeeEstimate()", ArbGasInfoFunction::GetL2BaseFeeEstimate),
+    ("getL1GasPriceEstimate()", ArbGasInfoFunction::GetL1GasPriceEstimate),
+    ("getGasAccountingParams()", ArbGasInfoFunction::GetGasAccountingParams),
+    ("getMinimumGasPrice()", ArbGasInfoFunction::GetMinimumGasPrice),
+    ("getAmortizedCostCapBips()", ArbGasInfoFunction::GetAmortizedCostCapBips),
+    ("getL1RewardRate()", ArbGasInfoFunction::GetL1RewardRate),
+    ("getL1BlobBaseFeeEstimate()", ArbGasInfoFunction::GetL1BlobBaseFeeEstimate),
+    ("getPricesInWeiWithAggregator(address)", ArbGasInfoFunction::GetPricesInWeiWithAggregator),
+    ("getPricesInArbGasWithAggregator(address)", ArbGasInfoFunction::GetPricesInArbGasWithAggregator),
+    ("getL1FeesAvailable()", ArbGasInfoFunction::GetL1FeesAvailable),
+];
+
 
 /// ArbGasInfo precompile handler (0x6C)
 pub struct ArbGasInfoHandler {
     address: Address,
+    functions: FunctionTable<ArbGasInfoFunction>,
     accounting_params: GasAccountingParams, 
+    gas_pool: GasPool,
+    l1_fee_pool: L1FeePool,
+    l1_base_fee_estimate: L1BaseFeeEstimate,
 }
 
 
 
 impl ArbGasInfoHandler {
     pub fn new() -> Self {
+        Self::with_gas_pool(GasPool::default())
+    }
+
+    /// Create a handler pricing congestion from an existing gas pool
+    pub fn with_gas_pool(gas_pool: GasPool) -> Self {
         Self {
             address: Address::from_hex("0x000000000000000000000000000000000000006c").unwrap(),
+            functions: FunctionTable::new(ARBGASINFO_FUNCTIONS),
             accounting_params: GasAccountingParams {
-                speed_limit_per_second: 120_000_000,
-                gas_pool_max: 32_000_000,
-                max_tx_gas_limit: 32_000_000,
                 amortized_cost_cap_bips: 10_000,
+                l1_reward_rate: 10,
             },
+            gas_pool,
+            l1_fee_pool: L1FeePool::default(),
+            l1_base_fee_estimate: L1BaseFeeEstimate::default(),
         }
     }
+
+    /// Get the gas pool backing the congestion fee
+    pub fn gas_pool(&self) -> &GasPool {
+        &self.gas_pool
+    }
+
+    /// Report the L1 fees collected in the given pool (e.g. one shared with the processor)
+    pub fn with_l1_fee_pool(mut self, l1_fee_pool: L1FeePool) -> Self {
+        self.l1_fee_pool = l1_fee_pool;
+        self
+    }
+
+    /// Get the pool `getL1FeesAvailable` reports
+    pub fn l1_fee_pool(&self) -> &L1FeePool {
+        &self.l1_fee_pool
+    }
+
+    /// Report the given L1 base fee estimate (e.g. one the processor updates)
+    pub fn with_l1_base_fee_estimate(mut self, l1_base_fee_estimate: L1BaseFeeEstimate) -> Self {
+        self.l1_base_fee_estimate = l1_base_fee_estimate;
+        self
+    }
+
+    /// Get the estimate `getL1BaseFeeEstimate` reports
+    pub fn l1_base_fee_estimate(&self) -> &L1BaseFeeEstimate {
+        &self.l1_base_fee_estimate
+    }
+}
+
+impl Default for ArbGasInfoHandler {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl PrecompileHandler for ArbGasInfoHandler {
-    fn address(&self) -> Address {
-        self.address.clone()
+    fn address_ref(&self) -> &Address {
+        &self.address
     }
 
     fn name(&self) -> &str {
@@ -316,83 +896,80 @@ impl PrecompileHandler for ArbGasInfoHandler {
     }
 
     fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        if input.len() < 4 {
-            return Err(anyhow!("Input too short for function selector"));
-        }
+        self.handle_call_with_context(input, &CallContext::default(), config)
+    }
 
-        let selector = &input[0..4];
-        let selector_hex = hex::encode(selector);
+    fn handle_call_with_context(
+        &self,
+        input: &[u8],
+        context: &CallContext,
+        config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
+        let function = self.functions.lookup(input)?;
+        if let Some(min_version) = function.min_arbos_version() {
+            if config.arb_os_version < min_version {
+                return Err(PrecompileError::RequiresArbOsVersion {
+                    selector: input[0..4].try_into().unwrap(),
+                    min_version,
+                    configured: config.arb_os_version,
+                });
+            }
+        }
 
-        match selector_hex.as_str() {
+        match function {
             // --- Standard Getters ---
-            "c6f7de0e" => self.handle_get_current_tx_l1_gas_fees(input, config),
-            "41b247a8" => self.handle_get_prices_in_wei(config),
-            "f5d6ded7" => self.handle_get_l1_base_fee_estimate(config),
-            "02199f34" => self.handle_get_prices_in_arb_gas(config), 
-            "b246b565" => self.handle_get_l2_base_fee_estimate(config),
-            "055f362f" => self.handle_get_l1_gas_price_estimate(config),
-            "612af178" => self.handle_get_gas_accounting_params(),
-            "f918379a" => self.handle_get_minimum_gas_price(config),
-            "7a7d6beb" => self.handle_get_amortized_cost_cap_bips(),
-            "67037bec" => self.handle_get_l1_blob_base_fee_estimate(config),
-            "43a28b2e" => self.handle_get_prices_in_wei(config),   // getPricesInWeiWithAggregator
-            "12f78baa" => self.handle_get_prices_in_arb_gas(config), // getPricesInArbGasWithAggregator
-
-            _ => Err(anyhow!("Unknown function selector: 0x{}", selector_hex)),
-        }
-    }
-    fn gas_cost(&self, input: &[u8]) -> u64 {
-        if input.len() < 4 { return 0; }
-        
-        let selector = hex::encode(&input[0..4]);
-        
-        match selector.as_str() {
-            "41b247a8" => 10, // getPricesInWei
-            "f5d6ded7" => 5,  // getL1BaseFeeEstimate
-            "02199f34" => 96, // getPricesInArbGas
-            "612af178" => 20, // getGasAccountingParams (returns 3 words)
-            "43a28b2e" => 20, // getPricesInWeiWithAggregator
-            "12f78baa" => 20, // getPricesInArbGasWithAggregator
-            
-            
-            "b246b565" | "055f362f" | "f918379a" | "7a7d6beb" | "67037bec" => 10,
-            
-            _ => 0,
+            ArbGasInfoFunction::GetCurrentTxL1GasFees => {
+                self.handle_get_current_tx_l1_gas_fees(&context.tx_calldata, config)
+            }
+            ArbGasInfoFunction::GetPricesInWei => self.handle_get_prices_in_wei(config),
+            ArbGasInfoFunction::GetL1BaseFeeEstimate => self.handle_get_l1_base_fee_estimate(config),
+            ArbGasInfoFunction::GetPricesInArbGas => self.handle_get_prices_in_arb_gas(config),
+            ArbGasInfoFunction::GetL2BaseFeeEstimate => self.handle_get_l2_base_fee_estimate(config),
+            ArbGasInfoFunction::GetL1GasPriceEstimate => self.handle_get_l1_gas_price_estimate(config),
+            ArbGasInfoFunction::GetGasAccountingParams => self.handle_get_gas_accounting_params(config),
+            ArbGasInfoFunction::GetMinimumGasPrice => self.handle_get_minimum_gas_price(config),
+            ArbGasInfoFunction::GetAmortizedCostCapBips => self.handle_get_amortized_cost_cap_bips(),
+            ArbGasInfoFunction::GetL1RewardRate => self.handle_get_l1_reward_rate(),
+            ArbGasInfoFunction::GetL1BlobBaseFeeEstimate => self.handle_get_l1_blob_base_fee_estimate(config),
+            ArbGasInfoFunction::GetPricesInWeiWithAggregator => self.handle_get_prices_in_wei(config),
+            ArbGasInfoFunction::GetPricesInArbGasWithAggregator => self.handle_get_prices_in_arb_gas(config),
+            ArbGasInfoFunction::GetL1FeesAvailable => Ok(self.encode_u256(self.l1_fee_pool.available()).to_vec()),
+        }
+    }
+    fn gas_cost(&self, input: &[u8], config: &ArbitrumConfig) -> u64 {
+        if let Some(gas) = config.precompile_gas_cost(self.name()) {
+            return gas;
+        }
+        let function = match self.functions.lookup(input) {
+            Ok(function) => function,
+            Err(_) => return 0,
+        };
+
+        match function {
+            ArbGasInfoFunction::GetPricesInWei => 10,
+            ArbGasInfoFunction::GetL1BaseFeeEstimate => 5,
+            ArbGasInfoFunction::GetPricesInArbGas => 96,
+            ArbGasInfoFunction::GetGasAccountingParams => 20, // returns 3 words
+            ArbGasInfoFunction::GetPricesInWeiWithAggregator => 20,
+            ArbGasInfoFunction::GetPricesInArbGasWithAggregator => 20,
+            ArbGasInfoFunction::GetCurrentTxL1GasFees => 0,
+            _ => 10,
         }
     }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
+    }
 }
 
 impl ArbGasInfoHandler {
     /// Handle getCurrentTxL1GasFees() call
-    ///  Uses compression heuristic (0-byte discount)
-    fn handle_get_current_tx_l1_gas_fees(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        //  Analyze input for Zero vs Non-Zero bytes
-        let mut non_zero_bytes = 0u64;
-        let mut zero_bytes = 0u64;
-
-        for &byte in input {
-            if byte == 0 {
-                zero_bytes += 1;
-            } else {
-                non_zero_bytes += 1;
-            }
-        }
-
-        // Apply Heuristic Costing
-        // Cost per non-zero is standard L1 calldata cost (usually 16)
-        let cost_per_non_zero = config.gas_price_components.l1_calldata_cost; 
-        // Cost per zero is standard EVM (4 gas)
-        let cost_per_zero = 4u64;
-
-        let raw_gas = (non_zero_bytes * cost_per_non_zero) + (zero_bytes * cost_per_zero);
-
-        // Apply Compression Discount (0.9x)
-        // using integer math: (raw * 9) / 10
-        let l1_gas_used = (raw_gas * 9) / 10;
-        
-        let l1_gas_fees = l1_gas_used * config.l1_base_fee;
-
-        let fees = U256::from_u64(l1_gas_fees);
+    ///
+    /// Prices the enclosing transaction's calldata, not the precompile input,
+    /// with `ArbitrumConfig::calculate_l1_gas_cost_wei_at` at the L1 base fee estimate.
+    fn handle_get_current_tx_l1_gas_fees(&self, calldata: &TxCalldata, config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        let l1_base_fee = self.l1_base_fee_estimate.current(config.l1_base_fee);
+        let fees = U256::from_u64(config.calculate_l1_gas_cost_wei_at(calldata.as_bytes(), l1_base_fee));
         Ok(fees.to_big_endian().to_vec())
     }
 
@@ -403,23 +980,23 @@ impl ArbGasInfoHandler {
         let mut result = Vec::with_capacity(32 * 6);
 
         // [0] Stub (Legacy L2 Tx)
-        let l2_base_fee = U256::from_u64(config.gas_price_components.l2_base_fee);
+        let l2_base_fee = U256::from_u64(config.gas_price_components.effective_l2_base_fee());
         result.extend_from_slice(&self.encode_u256(l2_base_fee));
 
         // [1] Stub (Legacy L1 Calldata)
         let l1_calldata_cost = U256::from_u64(config.gas_price_components.l1_calldata_cost);
         result.extend_from_slice(&self.encode_u256(l1_calldata_cost));
 
-        // [2] L1 Cost Per Byte (Calculated: L1BaseFee * 16)
-        let l1_base_fee = U256::from_u64(config.l1_base_fee);
+        // [2] L1 Cost Per Byte (Calculated: L1BaseFeeEstimate * 16)
+        let l1_base_fee = U256::from_u64(self.l1_base_fee_estimate.current(config.l1_base_fee));
         let l1_byte_price = l1_base_fee.saturating_mul(l1_calldata_cost);
         result.extend_from_slice(&self.encode_u256(l1_byte_price));
 
         // [3] L2 Base Fee
         result.extend_from_slice(&self.encode_u256(l2_base_fee));
 
-        // [4] Congestion Fee
-        let congestion_fee = U256::from_u64(config.gas_price_components.congestion_fee);
+        // [4] Congestion Fee (grows as the gas pool drains)
+        let congestion_fee = U256::from_u64(self.gas_pool.congestion_fee(&config.gas_price_components));
         result.extend_from_slice(&self.encode_u256(congestion_fee));
 
         // [5] Total L2 Fee (L2Base + Congestion)
@@ -432,20 +1009,20 @@ impl ArbGasInfoHandler {
 
     /// Handle getL1BaseFeeEstimate() call
     fn handle_get_l1_base_fee_estimate(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        let l1_base_fee = U256::from_u64(config.l1_base_fee);
+        let l1_base_fee = U256::from_u64(self.l1_base_fee_estimate.current(config.l1_base_fee));
         Ok(l1_base_fee.to_big_endian())
     }
 
     
     /// Handle getL1GasPriceEstimate()
     fn handle_get_l1_gas_price_estimate(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        let fee = U256::from_u64(config.l1_base_fee);
+        let fee = U256::from_u64(self.l1_base_fee_estimate.current(config.l1_base_fee));
         Ok(self.encode_u256(fee).to_vec())
     }
 
     /// Handle getMinimumGasPrice()
     fn handle_get_minimum_gas_price(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        let fee = U256::from_u64(config.gas_price_components.l2_base_fee);
+        let fee = U256::from_u64(config.gas_price_components.min_l2_gas_price);
         Ok(self.encode_u256(fee).to_vec())
     }
 
@@ -455,13 +1032,19 @@ impl ArbGasInfoHandler {
         Ok(self.encode_u256(bips).to_vec())
     }
 
+    /// Handle getL1RewardRate()
+    fn handle_get_l1_reward_rate(&self) -> Result<Vec<u8>> {
+        let rate = U256::from_u64(self.accounting_params.l1_reward_rate);
+        Ok(self.encode_u256(rate).to_vec())
+    }
+
     /// Handle getGasAccountingParams()
-    fn handle_get_gas_accounting_params(&self) -> Result<Vec<u8>> {
+    fn handle_get_gas_accounting_params(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
         let mut result = Vec::with_capacity(32 * 3);
         
-        let speed = U256::from_u64(self.accounting_params.speed_limit_per_second);
-        let pool = U256::from_u64(self.accounting_params.gas_pool_max);
-        let max_tx = U256::from_u64(self.accounting_params.max_tx_gas_limit);
+        let speed = U256::from_u64(self.gas_pool.speed_limit_per_second());
+        let pool = U256::from_u64(self.gas_pool.max());
+        let max_tx = U256::from_u64(config.l2_block_gas_limit);
 
         result.extend_from_slice(&self.encode_u256(speed));
         result.extend_from_slice(&self.encode_u256(pool));
@@ -475,7 +1058,7 @@ impl ArbGasInfoHandler {
     fn handle_get_prices_in_arb_gas(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
         let mut result = Vec::with_capacity(32 * 3);
         
-        let l2_base_fee = U256::from_u64(config.gas_price_components.l2_base_fee);
+        let l2_base_fee = U256::from_u64(config.gas_price_components.effective_l2_base_fee());
         let l1_calldata_cost = U256::from_u64(config.gas_price_components.l1_calldata_cost);
         let l1_storage_cost = U256::from_u64(config.gas_price_components.l1_storage_cost);
 
@@ -494,9 +1077,9 @@ impl ArbGasInfoHandler {
 
 
     ///  Handle getL2BaseFeeEstimate()
-    /// Returns the current L2 base fee (same as config value)
+    /// Returns the current L2 base fee, clamped to the minimum L2 gas price
     fn handle_get_l2_base_fee_estimate(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
-        let fee = U256::from_u64(config.gas_price_components.l2_base_fee);
+        let fee = U256::from_u64(config.gas_price_components.effective_l2_base_fee());
         Ok(self.encode_u256(fee).to_vec())
     }
 
@@ -512,63 +1095,554 @@ impl ArbGasInfoHandler {
     }
 }
 
+/// A retryable ticket created by a submit-retryable transaction
+#[derive(Debug, Clone, PartialEq)]
+pub struct RetryableTicket {
+    /// Ticket id (the submit-retryable transaction hash)
+    pub id: [u8; 32],
+    /// L1 sender
+    pub from: Address,
+    /// Address that receives the callvalue on cancel or expiry
+    pub beneficiary: Address,
+    /// Destination of the retry call
+    pub retry_to: Address,
+    /// Value sent with the retry call
+    pub retry_value: U256,
+    /// Calldata of the retry call
+    pub retry_data: Vec<u8>,
+    /// Gas limit of the retry call
+    pub gas_limit: u64,
+    /// L2 timestamp at which the ticket was created
+    pub created_at: u64,
+}
+
+impl RetryableTicket {
+    /// Timestamp after which the ticket can no longer be redeemed
+    pub fn timeout(&self, lifetime_secs: u64) -> u64 {
+        self.created_at.saturating_add(lifetime_secs)
+    }
+}
+
+/// Shared map of live retryable tickets, keyed by ticket id
+///
+/// Cloning the store shares the underlying map, so a processor and the
+/// `ArbRetryableTx` precompile can observe the same tickets.
+#[derive(Debug, Clone, Default)]
+pub struct RetryableTicketStore {
+    tickets: Arc<Mutex<HashMap<[u8; 32], RetryableTicket>>>,
+}
+
+impl RetryableTicketStore {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Insert a ticket, replacing any ticket with the same id
+    pub fn insert(&self, ticket: RetryableTicket) {
+        self.tickets.lock().unwrap().insert(ticket.id, ticket);
+    }
+
+    /// Get a ticket by id
+    pub fn get(&self, id: &[u8; 32]) -> Option<RetryableTicket> {
+        self.tickets.lock().unwrap().get(id).cloned()
+    }
+
+    /// Remove a ticket by id, returning it if it existed
+    pub fn remove(&self, id: &[u8; 32]) -> Option<RetryableTicket> {
+        self.tickets.lock().unwrap().remove(id)
+    }
+
+    /// Number of live tickets
+    pub fn len(&self) -> usize {
+        self.tickets.lock().unwrap().len()
+    }
+
+    /// Check if there are no live tickets
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// An event log emitted by a precompile
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct PrecompileLog {
+    /// Address of the emitting precompile
+    pub address: Address,
+    /// Event signature hash followed by the indexed arguments
+    pub topics: Vec<[u8; 32]>,
+    /// ABI-encoded non-indexed arguments
+    pub data: Vec<u8>,
+}
+
+/// Shared buffer of logs emitted by precompiles, in emission order
+///
+/// Cloning the buffer shares the underlying logs.
+#[derive(Debug, Clone, Default)]
+pub struct LogBuffer {
+    logs: Arc<Mutex<Vec<PrecompileLog>>>,
+}
+
+impl LogBuffer {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Append a log
+    pub fn push(&self, log: PrecompileLog) {
+        self.logs.lock().unwrap().push(log);
+    }
+
+    /// Get a copy of the buffered logs
+    pub fn logs(&self) -> Vec<PrecompileLog> {
+        self.logs.lock().unwrap().clone()
+    }
+
+    /// Remove and return the buffered logs
+    pub fn drain(&self) -> Vec<PrecompileLog> {
+        std::mem::take(&mut *self.logs.lock().unwrap())
+    }
+
+    /// Number of buffered logs
+    pub fn len(&self) -> usize {
+        self.logs.lock().unwrap().len()
+    }
+
+    /// Check if no logs are buffered
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Functions implemented by `ArbRetryableTxHandler`
+#[derive(Debug, Clone, Copy)]
+enum ArbRetryableTxFunction {
+    GetLifetime,
+    GetTimeout,
+    GetBeneficiary,
+    Redeem,
+    Cancel,
+}
+
+const ARBRETRYABLETX_FUNCTIONS: &[(&str, ArbRetryableTxFunction)] = &[
+    ("getLifetime()", ArbRetryableTxFunction::GetLifetime),
+    ("getTimeout(bytes32)", ArbRetryableTxFunction::GetTimeout),
+    ("getBeneficiary(bytes32)", ArbRetryableTxFunction::GetBeneficiary),
+    ("redeem(bytes32)", ArbRetryableTxFunction::Redeem),
+    ("cancel(bytes32)", ArbRetryableTxFunction::Cancel),
+];
+
+/// ArbRetryableTx precompile handler (0x6E)
+///
+/// Tickets expire `retryable_lifetime_secs` after creation, measured on the
+/// mock chain's clock.
+pub struct ArbRetryableTxHandler {
+    address: Address,
+    functions: FunctionTable<ArbRetryableTxFunction>,
+    tickets: RetryableTicketStore,
+    chain: MockChain,
+}
+
+impl ArbRetryableTxHandler {
+    pub fn new() -> Self {
+        Self::with_tickets(RetryableTicketStore::new())
+    }
+
+    /// Create a handler backed by an existing ticket store
+    pub fn with_tickets(tickets: RetryableTicketStore) -> Self {
+        Self {
+            address: Address::from_hex("0x000000000000000000000000000000000000006e").unwrap(),
+            functions: FunctionTable::new(ARBRETRYABLETX_FUNCTIONS),
+            tickets,
+            chain: MockChain::default(),
+        }
+    }
+
+    /// Check ticket expiry against the given chain's clock
+    pub fn with_mock_chain(mut self, chain: MockChain) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Get the ticket store backing this handler
+    pub fn tickets(&self) -> &RetryableTicketStore {
+        &self.tickets
+    }
+}
+
+impl Default for ArbRetryableTxHandler {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl PrecompileHandler for ArbRetryableTxHandler {
+    fn address_ref(&self) -> &Address {
+        &self.address
+    }
+
+    fn name(&self) -> &str {
+        "ArbRetryableTx"
+    }
+
+    fn handle_call(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        match self.functions.lookup(input)? {
+            ArbRetryableTxFunction::GetLifetime => self.handle_get_lifetime(config),
+            ArbRetryableTxFunction::GetTimeout => self.handle_get_timeout(input, config),
+            ArbRetryableTxFunction::GetBeneficiary => self.handle_get_beneficiary(input),
+            ArbRetryableTxFunction::Redeem => self.handle_redeem(input, config),
+            ArbRetryableTxFunction::Cancel => self.handle_cancel(input),
+        }
+    }
+
+    fn gas_cost(&self, _input: &[u8], config: &ArbitrumConfig) -> u64 {
+        config.precompile_gas_cost(self.name()).unwrap_or(10)
+    }
+
+    fn signatures(&self) -> &[&'static str] {
+        self.functions.signatures()
+    }
+}
+
+impl ArbRetryableTxHandler {
+    /// Handle getLifetime() call
+    fn handle_get_lifetime(&self, config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        Ok(U256::from_u64(config.retryable_lifetime_secs).to_big_endian())
+    }
+
+    /// Handle getTimeout(bytes32) call
+    fn handle_get_timeout(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        let id = Self::decode_ticket_id(input)?;
+        let ticket = self
+            .tickets
+            .get(&id)
+            .ok_or(PrecompileError::TicketNotFound { id })?;
+        Ok(U256::from_u64(ticket.timeout(config.retryable_lifetime_secs)).to_big_endian())
+    }
+
+    /// Handle getBeneficiary(bytes32) call
+    fn handle_get_beneficiary(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let id = Self::decode_ticket_id(input)?;
+        let ticket = self
+            .tickets
+            .get(&id)
+            .ok_or(PrecompileError::TicketNotFound { id })?;
+
+        let mut result = vec![0u8; 12];
+        result.extend_from_slice(ticket.beneficiary.as_bytes());
+        Ok(result)
+    }
+
+    /// Handle redeem(bytes32) call
+    ///
+    /// Consumes the ticket and returns the retry transaction hash (mocked as the ticket id).
+    /// Reverts once the chain's clock is past the ticket's timeout.
+    fn handle_redeem(&self, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        let id = Self::decode_ticket_id(input)?;
+        let ticket = self
+            .tickets
+            .get(&id)
+            .ok_or(PrecompileError::TicketNotFound { id })?;
+        let timeout = ticket.timeout(config.retryable_lifetime_secs);
+        if self.chain.timestamp() > timeout {
+            return Err(PrecompileError::TicketExpired { id, timeout });
+        }
+        self.tickets
+            .remove(&id)
+            .ok_or(PrecompileError::TicketNotFound { id })?;
+        Ok(id.to_vec())
+    }
+
+    /// Handle cancel(bytes32) call
+    fn handle_cancel(&self, input: &[u8]) -> Result<Vec<u8>> {
+        let id = Self::decode_ticket_id(input)?;
+        self.tickets
+            .remove(&id)
+            .ok_or(PrecompileError::TicketNotFound { id })?;
+        Ok(Vec::new())
+    }
+
+    /// Decode the `bytes32` ticket id argument following the selector
+    fn decode_ticket_id(input: &[u8]) -> Result<[u8; 32]> {
+        input
+            .get(4..36)
+            .and_then(|bytes| bytes.try_into().ok())
+            .ok_or(PrecompileError::InvalidInput("Input too short for ticket id"))
+    }
+}
+
 /// Precompile registry
 pub struct PrecompileRegistry {
-    handlers: Vec<Box<dyn PrecompileHandler>>,
+    handlers: HashMap<Address, Box<dyn PrecompileHandler>>,
+    /// Addresses in registration order, for stable listing
+    order: Vec<Address>,
+    metrics: Metrics,
+    gas_pool: GasPool,
+    chain: MockChain,
+    storage_gas: StorageGas,
+    l1_fee_pool: L1FeePool,
+    l1_base_fee_estimate: L1BaseFeeEstimate,
+    state: SharedState,
+    chain_owners: ChainOwnerState,
 }
 
 impl PrecompileRegistry {
     pub fn new() -> Self {
+        let l1_base_fee_estimate = L1BaseFeeEstimate::default();
         Self {
-            handlers: Vec::new(),
+            handlers: HashMap::new(),
+            order: Vec::new(),
+            metrics: Metrics::new(),
+            gas_pool: GasPool::default(),
+            chain: MockChain::default(),
+            storage_gas: StorageGas::default(),
+            l1_fee_pool: L1FeePool::default(),
+            chain_owners: ChainOwnerState::default().with_l1_base_fee_estimate(l1_base_fee_estimate.clone()),
+            l1_base_fee_estimate,
+            state: InMemoryState::new().shared(),
         }
     }
 
+    /// Count calls in the given metrics (e.g. ones shared with the processor)
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Get the metrics calls are counted in
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Get the gas pool the default `ArbGasInfo` prices congestion from
+    ///
+    /// Share it with `Tx7eProcessor::with_gas_pool` so deposits drain it.
+    pub fn gas_pool(&self) -> &GasPool {
+        &self.gas_pool
+    }
+
+    /// Get the chain the default `ArbSys` and `ArbRetryableTx` read blocks and time from
+    ///
+    /// Share it with `Tx7eProcessor::with_mock_chain` so deposits mine into it.
+    pub fn mock_chain(&self) -> &MockChain {
+        &self.chain
+    }
+
+    /// Get the storage gas the default `ArbSys` reports
+    ///
+    /// Share it with `Tx7eProcessor::with_storage_gas` so deposits drain it too.
+    pub fn storage_gas(&self) -> &StorageGas {
+        &self.storage_gas
+    }
+
+    /// Get the L1 fee pool the default `ArbGasInfo` reports
+    ///
+    /// Share it with `Tx7eProcessor::with_l1_fee_pool` so deposits fill it.
+    pub fn l1_fee_pool(&self) -> &L1FeePool {
+        &self.l1_fee_pool
+    }
+
+    /// Get the L1 base fee estimate the default `ArbGasInfo` reports
+    ///
+    /// Share it with `Tx7eProcessor::with_l1_base_fee_estimate` so deposits update it.
+    pub fn l1_base_fee_estimate(&self) -> &L1BaseFeeEstimate {
+        &self.l1_base_fee_estimate
+    }
+
+    /// Get the account state the default `ArbSys` debits withdrawals from
+    ///
+    /// Share it with `Tx7eProcessor::with_state_backend` so deposits credit it.
+    pub fn state_backend(&self) -> &SharedState {
+        &self.state
+    }
+
+    /// Get the chain owners `ArbOwner` and `ArbOwnerPublic` should share
+    ///
+    /// Owners setting the inertia drive this registry's L1 base fee estimate.
+    /// `RpcState::config` and `Tx7eProcessor::with_chain_owners` apply the other
+    /// parameters they set, such as the L2 base fee.
+    pub fn chain_owners(&self) -> &ChainOwnerState {
+        &self.chain_owners
+    }
+
     /// Register a precompile handler
+    ///
+    /// The first handler registered at an address wins; later registrations
+    /// for the same address are ignored.
     pub fn register(&mut self, handler: Box<dyn PrecompileHandler>) {
-        self.handlers.push(handler);
+        let address = *handler.address_ref();
+        if let Entry::Vacant(entry) = self.handlers.entry(address) {
+            entry.insert(handler);
+            self.order.push(address);
+        }
     }
 
     /// Get a precompile handler by address
     pub fn get_handler(&self, address: &Address) -> Option<&dyn PrecompileHandler> {
-        self.handlers.iter().find(|h| h.address() == *address).map(|h| h.as_ref())
+        self.handlers.get(address).map(|h| h.as_ref())
     }
 
     /// Check if an address has a precompile handler
     pub fn has_handler(&self, address: &Address) -> bool {
-        self.handlers.iter().any(|h| h.address() == *address)
+        self.handlers.contains_key(address)
     }
 
     /// Get all registered precompile addresses
     pub fn get_addresses(&self) -> Vec<Address> {
-        self.handlers.iter().map(|h| h.address()).collect()
+        self.order.clone()
+    }
+
+    /// Describe every registered handler with the selectors it recognizes
+    pub fn selector_table(&self) -> String {
+        let mut table = String::new();
+        for handler in self.order.iter().filter_map(|a| self.get_handler(a)) {
+            let address = hex::encode(handler.address_ref().as_bytes());
+            table.push_str(&format!("{} (0x{})\n", handler.name(), address));
+            for (signature, selector) in handler.supported_selectors() {
+                table.push_str(&format!("  0x{}  {}\n", hex::encode(selector), signature));
+            }
+        }
+        table
+    }
+
+    /// Minimal ABI for every registered handler, keyed by handler name
+    pub fn abi_json(&self) -> serde_json::Value {
+        let mut abis = serde_json::Map::new();
+        for handler in self.order.iter().filter_map(|a| self.get_handler(a)) {
+            let abi = handler.signatures().iter().map(|s| abi_entry(s)).collect();
+            abis.insert(handler.name().to_string(), serde_json::Value::Array(abi));
+        }
+        serde_json::Value::Object(abis)
+    }
+
+    /// Call the function named by `signature`, e.g. `"getTimeout(bytes32)"`, with ABI-encoded `args`
+    ///
+    /// Fails with `ArgumentMismatch` if `args` do not line up with the
+    /// signature's parameter types.
+    pub fn call_by_signature(
+        &self,
+        address: &Address,
+        signature: &str,
+        args: &[AbiValue],
+        config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
+        let (_, types) = parse_signature(signature);
+        if types.len() != args.len() || !args.iter().zip(&types).all(|(arg, ty)| arg.matches(ty)) {
+            return Err(PrecompileError::ArgumentMismatch {
+                signature: signature.to_string(),
+            });
+        }
+        let mut input = selector(signature).to_vec();
+        input.extend_from_slice(&abi_encode(args));
+        self.handle_call(*address, &input, config)
     }
 
     /// Handle a precompile call
     pub fn handle_call(&self, address: Address, input: &[u8], config: &ArbitrumConfig) -> Result<Vec<u8>> {
+        self.handle_call_with_context(address, input, &CallContext::default(), config)
+    }
+
+    /// Handle a precompile call on behalf of the caller described by `context`
+    pub fn handle_call_with_context(
+        &self,
+        address: Address,
+        input: &[u8],
+        context: &CallContext,
+        config: &ArbitrumConfig,
+    ) -> Result<Vec<u8>> {
         if let Some(handler) = self.get_handler(&address) {
-            handler.handle_call(input, config)
+            self.metrics.record_precompile_call(handler.name());
+            handler.handle_call_with_context(input, context, config)
         } else {
-            Err(anyhow!("No precompile handler found for address {}", address))
+            Err(PrecompileError::NoHandler { address })
+        }
+    }
+
+    /// Handle a precompile call, also returning the gas the handler charges for it
+    pub fn handle_call_metered(
+        &self,
+        address: Address,
+        input: &[u8],
+        config: &ArbitrumConfig,
+    ) -> Result<(Vec<u8>, u64)> {
+        let output = self.handle_call(address, input, config)?;
+        Ok((output, self.gas_cost(address, input, config).unwrap_or(0)))
+    }
+
+    /// Gas the precompile at `address` charges for `input`, or `None` if none is registered
+    pub fn gas_cost(&self, address: Address, input: &[u8], config: &ArbitrumConfig) -> Option<u64> {
+        self.get_handler(&address).map(|handler| handler.gas_cost(input, config))
+    }
+}
+
+impl PrecompileRegistry {
+    /// Create a registry with the default precompiles backed by shared state
+    ///
+    /// `ArbGasInfo` prices congestion from `gas_pool` and `ArbSys` reports block
+    /// numbers from `chain`.
+    pub fn with_state(gas_pool: GasPool, chain: MockChain) -> Self {
+        let mut registry = Self {
+            gas_pool,
+            chain,
+            ..Self::new()
+        };
+        for handler in Self::default_handlers(&registry) {
+            registry.register(handler);
+        }
+        registry
+    }
+
+    /// Like `with_state`, but registering only the precompiles `config` enables
+    ///
+    /// The storage gas starts at `config.storage_gas_available` and the L1 base
+    /// fee estimate smooths with `config.l1_base_fee_inertia`.
+    pub fn from_config(config: &ArbitrumConfig, gas_pool: GasPool, chain: MockChain) -> Self {
+        let l1_base_fee_estimate = L1BaseFeeEstimate::new(config.l1_base_fee_inertia);
+        let mut registry = Self {
+            gas_pool,
+            chain,
+            storage_gas: StorageGas::new(config.storage_gas_available),
+            chain_owners: ChainOwnerState::default().with_l1_base_fee_estimate(l1_base_fee_estimate.clone()),
+            l1_base_fee_estimate,
+            ..Self::new()
+        };
+        for handler in Self::default_handlers(&registry) {
+            let address = format!("0x{}", hex::encode(handler.address_ref().as_bytes()));
+            if config.is_precompile_enabled(&address) {
+                registry.register(handler);
+            }
         }
+        registry
+    }
+
+    /// Default handlers sharing the gas pool, chain, storage gas, L1 fee pool, L1 base fee estimate and state of `registry`
+    fn default_handlers(registry: &Self) -> Vec<Box<dyn PrecompileHandler>> {
+        vec![
+            Box::new(
+                ArbSysHandler::new()
+                    .with_mock_chain(registry.chain.clone())
+                    .with_storage_gas(registry.storage_gas.clone())
+                    .with_state_backend(registry.state.clone()),
+            ),
+            Box::new(
+                ArbGasInfoHandler::with_gas_pool(registry.gas_pool.clone())
+                    .with_l1_fee_pool(registry.l1_fee_pool.clone())
+                    .with_l1_base_fee_estimate(registry.l1_base_fee_estimate.clone()),
+            ),
+            Box::new(ArbRetryableTxHandler::new().with_mock_chain(registry.chain.clone())),
+        ]
     }
 }
 
 impl Default for PrecompileRegistry {
     fn default() -> Self {
-        let mut registry = Self::new();
-        
-        // Register default precompiles
-        registry.register(Box::new(ArbSysHandler::new()));
-        registry.register(Box::new(ArbGasInfoHandler::new()));
-        
-        registry
+        Self::with_state(GasPool::default(), MockChain::default())
     }
 }
 
 #[cfg(test)]
 mod tests {
     use super::*;
+    use crate::state::StateBackend;
 
     #[test]
     fn test_address_from_hex() {
@@ -606,33 +1680,659 @@ mod tests {
         assert!(!registry.has_handler(&Address::from_hex("0x0000000000000000000000000000000000000000").unwrap()));
     }
 
+    #[test]
+    fn test_registry_lookup_is_order_independent() {
+        let addresses = [
+            Address::from_hex("0x0000000000000000000000000000000000000064").unwrap(),
+            Address::from_hex("0x000000000000000000000000000000000000006c").unwrap(),
+            Address::from_hex("0x000000000000000000000000000000000000006e").unwrap(),
+        ];
+
+        let mut reversed = PrecompileRegistry::new();
+        reversed.register(Box::new(ArbRetryableTxHandler::new()));
+        reversed.register(Box::new(ArbGasInfoHandler::new()));
+        reversed.register(Box::new(ArbSysHandler::new()));
+
+        let forward = PrecompileRegistry::default();
+        for registry in [&forward, &reversed] {
+            for address in &addresses {
+                let handler = registry.get_handler(address).unwrap();
+                assert_eq!(handler.address(), *address);
+                assert_eq!(handler.address_ref(), address);
+                assert!(registry.has_handler(handler.address_ref()));
+            }
+            assert_eq!(registry.get_addresses().len(), addresses.len());
+        }
+        assert_eq!(forward.get_addresses(), addresses.to_vec());
+        assert_eq!(reversed.get_addresses(), addresses.iter().rev().copied().collect::<Vec<_>>());
+
+        // A second handler at an already registered address is ignored
+        let mut duplicate = PrecompileRegistry::default();
+        duplicate.register(Box::new(ArbSysHandler::new()));
+        assert_eq!(duplicate.get_addresses(), addresses.to_vec());
+    }
+
+    #[test]
+    fn test_arbretryabletx_tickets() {
+        let tickets = RetryableTicketStore::new();
+        let chain = MockChain::default();
+        let handler = ArbRetryableTxHandler::with_tickets(tickets.clone()).with_mock_chain(chain.clone());
+        let config = ArbitrumConfig::default();
+        let beneficiary = Address::from_hex("0xabcdefabcdefabcdefabcdefabcdefabcdefabcd").unwrap();
+
+        tickets.insert(RetryableTicket {
+            id: [7u8; 32],
+            from: Address::from_hex("0x1111111111111111111111111111111111111111").unwrap(),
+            beneficiary,
+            retry_to: Address::from_hex("0x1234567890123456789012345678901234567890").unwrap(),
+            retry_value: U256::zero(),
+            retry_data: Vec::new(),
+            gas_limit: 100000,
+            created_at: chain.timestamp(),
+        });
+
+        // getBeneficiary(bytes32)
+        let mut input = hex::decode("ba20dda4").unwrap();
+        input.extend_from_slice(&[7u8; 32]);
+        let result = handler.handle_call(&input, &config).unwrap();
+        assert_eq!(&result[12..], beneficiary.as_bytes());
+
+        // redeem(bytes32) consumes the ticket
+        let mut input = hex::decode("eda1122c").unwrap();
+        input.extend_from_slice(&[7u8; 32]);
+        assert!(handler.handle_call(&input, &config).is_ok());
+        assert!(tickets.is_empty());
+        assert!(handler.handle_call(&input, &config).is_err());
+    }
+
+    #[test]
+    fn test_retryable_tickets_expire() {
+        let tickets = RetryableTicketStore::new();
+        let chain = MockChain::from_seed(1);
+        let handler = ArbRetryableTxHandler::with_tickets(tickets.clone()).with_mock_chain(chain.clone());
+        let config = ArbitrumConfig {
+            retryable_lifetime_secs: 10,
+            ..ArbitrumConfig::default()
+        };
+        let created_at = chain.timestamp();
+        for id in [[1u8; 32], [2u8; 32]] {
+            tickets.insert(RetryableTicket {
+                id,
+                from: Address::ZERO,
+                beneficiary: Address::ZERO,
+                retry_to: Address::ZERO,
+                retry_value: U256::zero(),
+                retry_data: Vec::new(),
+                gas_limit: 100000,
+                created_at,
+            });
+        }
+        let call = |signature: &str, id: [u8; 32]| {
+            let mut input = selector(signature).to_vec();
+            input.extend_from_slice(&id);
+            handler.handle_call(&input, &config)
+        };
+
+        assert_eq!(
+            U256::from_big_endian(&handler.handle_call(&selector("getLifetime()"), &config).unwrap()),
+            U256::from_u64(10)
+        );
+        let timeout = call("getTimeout(bytes32)", [1u8; 32]).unwrap();
+        assert_eq!(U256::from_big_endian(&timeout), U256::from_u64(created_at + 10));
+
+        // A fresh ticket redeems
+        assert_eq!(call("redeem(bytes32)", [1u8; 32]).unwrap(), [1u8; 32].to_vec());
+
+        // Ten seconds later the ticket is still live, one more and it has expired
+        for _ in 0..10 {
+            chain.advance();
+        }
+        assert_eq!(chain.timestamp(), created_at + 10);
+        chain.advance();
+        assert_eq!(
+            call("redeem(bytes32)", [2u8; 32]),
+            Err(PrecompileError::TicketExpired {
+                id: [2u8; 32],
+                timeout: created_at + 10
+            })
+        );
+        assert_eq!(tickets.len(), 1);
+    }
+
     #[test]
     fn test_arbsys_calls() {
         let handler = ArbSysHandler::new();
         let config = ArbitrumConfig::new(42161, 20, 20_000_000_000);
 
         // Test arbChainID()
-        let input = hex::decode("d127f54a").unwrap();
+        let input = hex::decode(selectors::ARB_CHAIN_ID).unwrap();
         let result = handler.handle_call(&input, &config).unwrap();
         let chain_id = U256::from_big_endian(&result);
         assert_eq!(chain_id, U256::from_u64(42161));
 
         // Test arbOSVersion()
-        let input = hex::decode("051038f2").unwrap();
+        let input = hex::decode(selectors::ARB_OS_VERSION).unwrap();
         let result = handler.handle_call(&input, &config).unwrap();
         let version = U256::from_big_endian(&result);
         assert_eq!(version, U256::from_u64(20));
     }
 
+    #[test]
+    fn test_arbsys_withdraw_eth() {
+        let withdrawals = WithdrawalStore::new();
+        let state = InMemoryState::new();
+        let registry = {
+            let mut registry = PrecompileRegistry::new();
+            registry.register(Box::new(
+                ArbSysHandler::with_withdrawals(withdrawals.clone()).with_state_backend(state.clone().shared()),
+            ));
+            registry
+        };
+        let config = ArbitrumConfig::default();
+        let arbsys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let destination = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+
+        // withdrawEth(address)
+        let mut input = hex::decode(selectors::WITHDRAW_ETH).unwrap();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(destination.as_bytes());
+
+        let context = CallContext {
+            value: U256::from_u64(5_000_000_000_000_000),
+            ..CallContext::default()
+        };
+
+        // Withdrawing more than the caller holds reverts without recording anything
+        assert_eq!(
+            registry.handle_call_with_context(arbsys, &input, &context, &config),
+            Err(PrecompileError::State(StateError::InsufficientBalance {
+                address: context.caller,
+                balance: U256::zero(),
+                amount: context.value,
+            }))
+        );
+        assert!(withdrawals.is_empty());
+
+        state.set_balance(&context.caller, U256::from_u64(12_000_000_000_000_000));
+        registry.handle_call_with_context(arbsys, &input, &context, &config).unwrap();
+        let result = registry.handle_call_with_context(arbsys, &input, &context, &config).unwrap();
+        assert_eq!(result.len(), 32);
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(1));
+
+        let withdrawal = withdrawals.get(1).unwrap();
+        assert_eq!(withdrawal.id, 1);
+        assert_eq!(withdrawal.destination, destination);
+        assert_eq!(withdrawal.value, U256::from_u64(5_000_000_000_000_000));
+        assert_eq!(withdrawals.len(), 2);
+        assert_eq!(state.balance(&context.caller), U256::from_u64(2_000_000_000_000_000));
+
+        // The destination argument is required
+        assert!(registry.handle_call(arbsys, &input[..4], &config).is_err());
+        assert_eq!(withdrawals.len(), 2);
+    }
+
+    #[test]
+    fn test_arbsys_withdrawals_feed_outbox() {
+        let handler = ArbSysHandler::new();
+        let config = ArbitrumConfig::default();
+        let caller = Address::from_hex("0xf39fd6e51aad88f6f4ce6ab8827279cfffb92266").unwrap();
+        let destination = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        handler.state_backend().set_balance(&caller, U256::from_u64(2_000));
+        let context = CallContext {
+            caller,
+            value: U256::from_u64(1_000),
+            ..CallContext::default()
+        };
+
+        // withdrawEth(address)
+        let mut input = hex::decode(selectors::WITHDRAW_ETH).unwrap();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(destination.as_bytes());
+        handler.handle_call_with_context(&input, &context, &config).unwrap();
+
+        // sendTxToL1(address,bytes) with 3 bytes of calldata
+        let mut input = hex::decode(selectors::SEND_TX_TO_L1).unwrap();
+        input.extend_from_slice(&[0u8; 12]);
+        input.extend_from_slice(destination.as_bytes());
+        input.extend_from_slice(&U256::from_u64(64).to_big_endian());
+        input.extend_from_slice(&U256::from_u64(3).to_big_endian());
+        let mut data = [0u8; 32];
+        data[..3].copy_from_slice(&[0xde, 0xad, 0xbe]);
+        input.extend_from_slice(&data);
+        let result = handler.handle_call_with_context(&input, &context, &config).unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(1));
+
+        let message = handler.withdrawals().get(1).unwrap();
+        assert_eq!(message.caller, caller);
+        assert_eq!(message.data, vec![0xde, 0xad, 0xbe]);
+
+        // outboxRoot()
+        let outbox = handler.withdrawals().outbox();
+        let root = handler.handle_call(&hex::decode(selectors::OUTBOX_ROOT).unwrap(), &config).unwrap();
+        assert_eq!(root, outbox.root().to_vec());
+
+        for id in 0..2 {
+            let leaf = handler.withdrawals().get(id).unwrap().leaf_hash();
+            let proof = outbox.proof(id).unwrap();
+            assert!(OutboxAccumulator::verify_proof(&leaf, id, &proof, &outbox.root()));
+        }
+
+        // Calldata shorter than its declared length is rejected
+        assert!(handler.handle_call_with_context(&input[..input.len() - 32], &context, &config).is_err());
+        assert_eq!(handler.withdrawals().len(), 2);
+    }
+
+    #[test]
+    fn test_send_merkle_tree_state_reports_outbox() {
+        let handler = ArbSysHandler::new();
+        let config = ArbitrumConfig::default();
+        let input = hex::decode(selectors::SEND_MERKLE_TREE_STATE).unwrap();
+        let outbox = handler.withdrawals().outbox();
+        for i in 1..=3u8 {
+            outbox.append([i; 32]);
+        }
+
+        let result = handler.handle_call(&input, &config).unwrap();
+        assert_eq!(result.len(), 32 * 6);
+        assert_eq!(U256::from_big_endian(&result[..32]), U256::from_u64(3));
+        assert_eq!(result[32..64], outbox.root());
+        assert_eq!(U256::from_big_endian(&result[64..96]), U256::from_u64(96));
+        assert_eq!(U256::from_big_endian(&result[96..128]), U256::from_u64(2));
+        let partials: Vec<[u8; 32]> = result[128..].chunks(32).map(|word| word.try_into().unwrap()).collect();
+        assert_eq!(partials, outbox.partials());
+        assert_eq!(partials[0], [3u8; 32]);
+    }
+
+    #[test]
+    fn test_arbsys_call_context_predicates() {
+        let handler = ArbSysHandler::new();
+        let config = ArbitrumConfig::default();
+        let is_top_level = hex::decode(selectors::IS_TOP_LEVEL_CALL).unwrap();
+        let was_aliased = hex::decode(selectors::WAS_MY_CALLERS_ADDRESS_ALIASED).unwrap();
+
+        let top_level = CallContext::default();
+        let result = handler.handle_call_with_context(&is_top_level, &top_level, &config).unwrap();
+        assert_eq!(result.len(), 32);
+        assert_eq!(result[31], 1);
+        let result = handler.handle_call_with_context(&was_aliased, &top_level, &config).unwrap();
+        assert_eq!(result, vec![0u8; 32]);
+
+        let nested = CallContext {
+            depth: 2,
+            aliased: true,
+            ..CallContext::default()
+        };
+        let result = handler.handle_call_with_context(&is_top_level, &nested, &config).unwrap();
+        assert_eq!(result, vec![0u8; 32]);
+        let result = handler.handle_call_with_context(&was_aliased, &nested, &config).unwrap();
+        assert_eq!(result[..31], [0u8; 31]);
+        assert_eq!(result[31], 1);
+    }
+
     #[test]
     fn test_arbgasinfo_calls() {
         let handler = ArbGasInfoHandler::new();
         let config = ArbitrumConfig::new(42161, 20, 20_000_000_000);
 
-        // Test getCurrentTxL1GasFees()
-        let input = hex::decode("c6f7de0e").unwrap();
+        // Test getCurrentTxL1GasFees() without transaction calldata
+        let input = hex::decode(selectors::GET_CURRENT_TX_L1_GAS_FEES).unwrap();
+        let result = handler.handle_call(&input, &config).unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::zero());
+    }
+
+    #[test]
+    fn test_current_tx_l1_gas_fees_use_enclosing_calldata() {
+        let handler = ArbGasInfoHandler::new();
+        let config = ArbitrumConfig::new(42161, 20, 20_000_000_000);
+        let input = hex::decode(selectors::GET_CURRENT_TX_L1_GAS_FEES).unwrap();
+
+        // 1 KiB priced the way the processor prices a deposit's calldata
+        let mut calldata = vec![0xab; 768];
+        calldata.extend_from_slice(&[0u8; 256]);
+        let context = CallContext::default().with_tx_calldata(&calldata);
+        assert_eq!(context.tx_calldata.len(), 1024);
+
+        let result = handler.handle_call_with_context(&input, &context, &config).unwrap();
+        assert_eq!(
+            U256::from_big_endian(&result),
+            U256::from_u64(config.calculate_l1_gas_cost_wei(&calldata))
+        );
+
+        // The fee scales with the calldata, not with the precompile input
+        let half = CallContext::default().with_tx_calldata(&calldata[..512]);
+        let result = handler.handle_call_with_context(&input, &half, &config).unwrap();
+        let l1_gas = 512 * config.gas_price_components.l1_calldata_cost;
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(l1_gas * 20_000_000_000));
+
+        // A 1.5x L1 fee scalar raises the fee by half
+        let mut scaled = config.clone();
+        scaled.gas_price_components.l1_fee_scalar = 1_500_000;
+        let result = handler.handle_call_with_context(&input, &half, &scaled).unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(l1_gas * 30_000_000_000));
+    }
+
+    #[test]
+    fn test_arbgasinfo_arbos_version_gating() {
+        let handler = ArbGasInfoHandler::new();
+        let input = hex::decode(selectors::GET_L1_REWARD_RATE).unwrap(); // ArbOS 11+
+
+        let old_config = ArbitrumConfig::new(42161, 6, 20_000_000_000);
+        let err = handler.handle_call(&input, &old_config).unwrap_err();
+        assert!(err.to_string().contains("Unknown function selector"));
+        assert!(matches!(
+            err,
+            PrecompileError::RequiresArbOsVersion { min_version: 11, configured: 6, .. }
+        ));
+
+        let config = ArbitrumConfig::new(42161, 20, 20_000_000_000);
+        let result = handler.handle_call(&input, &config).unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(10));
+
+        // Ungated functions answer at any version
+        let input = hex::decode(selectors::GET_L1_BASE_FEE_ESTIMATE).unwrap();
+        assert!(handler.handle_call(&input, &old_config).is_ok());
+    }
+
+    #[test]
+    fn test_l2_pricing_respects_minimum_gas_price() {
+        let handler = ArbGasInfoHandler::new();
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.l2_base_fee = 1_000;
+        let word = |selector: &str, index: usize| {
+            let output = handler.handle_call(&hex::decode(selector).unwrap(), &config).unwrap();
+            U256::from_big_endian(&output[index * 32..(index + 1) * 32])
+        };
+        let floor = U256::from_u64(100_000_000);
+
+        assert_eq!(word(selectors::GET_MINIMUM_GAS_PRICE, 0), floor);
+        assert_eq!(word(selectors::GET_L2_BASE_FEE_ESTIMATE, 0), floor);
+        assert_eq!(word(selectors::GET_PRICES_IN_ARB_GAS, 0), floor);
+        assert_eq!(word(selectors::GET_PRICES_IN_WEI, 0), floor);
+        assert_eq!(word(selectors::GET_PRICES_IN_WEI, 3), floor);
+        assert_eq!(word(selectors::GET_PRICES_IN_WEI, 5), floor);
+    }
+
+    #[test]
+    fn test_prices_in_wei_use_l1_base_fee_estimate() {
+        let handler = ArbGasInfoHandler::new();
+        let config = ArbitrumConfig::default();
+        let word = |selector: &str, index: usize| {
+            let output = handler.handle_call(&hex::decode(selector).unwrap(), &config).unwrap();
+            U256::from_big_endian(&output[index * 32..(index + 1) * 32])
+        };
+        let l1_calldata_cost = U256::from_u64(config.gas_price_components.l1_calldata_cost);
+
+        // A deposit at 30 gwei moves the 20 gwei estimate a tenth of the way there
+        handler.l1_base_fee_estimate().observe(30_000_000_000, config.l1_base_fee);
+        let estimate = word(selectors::GET_L1_BASE_FEE_ESTIMATE, 0);
+        assert_eq!(estimate, U256::from_u64(21_000_000_000));
+        assert_eq!(word(selectors::GET_PRICES_IN_WEI, 2), estimate.saturating_mul(l1_calldata_cost));
+    }
+
+    #[test]
+    fn test_gas_accounting_params_use_block_gas_limit() {
+        let handler = ArbGasInfoHandler::new();
+        let config = ArbitrumConfig {
+            l2_block_gas_limit: 12_345_678,
+            ..Default::default()
+        };
+
+        let input = hex::decode(selectors::GET_GAS_ACCOUNTING_PARAMS).unwrap();
         let result = handler.handle_call(&input, &config).unwrap();
-        let base_fee = U256::from_big_endian(&result);
-        assert_eq!(base_fee, U256::from_u64(1_280_000_000_000));
+        assert_eq!(result.len(), 96);
+        assert_eq!(U256::from_big_endian(&result[64..96]), U256::from_u64(12_345_678));
+    }
+
+    #[test]
+    fn test_selector_table() {
+        let table = PrecompileRegistry::default().selector_table();
+        assert!(table.contains("ArbSys (0x0000000000000000000000000000000000000064)"));
+        assert!(table.contains("0xd127f54a  arbChainID()"));
+        assert!(table.contains("ArbGasInfo (0x000000000000000000000000000000000000006c)"));
+        assert!(table.contains("0x41b247a8  getPricesInWei()"));
+    }
+
+    #[test]
+    fn test_abi_json() {
+        let abi = PrecompileRegistry::default().abi_json();
+        let prices = abi["ArbGasInfo"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["name"] == "getPricesInWei")
+            .unwrap();
+        assert_eq!(prices["inputs"].as_array().unwrap().len(), 0);
+        assert_eq!(prices["outputs"].as_array().unwrap().len(), 6);
+        assert_eq!(prices["stateMutability"], "view");
+
+        let withdraw = abi["ArbSys"]
+            .as_array()
+            .unwrap()
+            .iter()
+            .find(|entry| entry["name"] == "withdrawEth")
+            .unwrap();
+        assert_eq!(withdraw["inputs"][0]["type"], "address");
+        assert_eq!(withdraw["stateMutability"], "payable");
+        assert_eq!(abi["ArbRetryableTx"].as_array().unwrap().len(), 5);
+    }
+
+    #[test]
+    fn test_selector_helper() {
+        assert_eq!(hex::encode(selector("getL1BaseFeeEstimate()")), "f5d6ded7");
+        assert_eq!(hex::encode(selector("arbChainID()")), selectors::ARB_CHAIN_ID);
+    }
+
+    #[test]
+    fn test_selector_constants_match_signatures() {
+        for (signature, selector) in selectors::ALL {
+            let hash = Keccak256::digest(signature.as_bytes());
+            assert_eq!(hex::encode(&hash[..4]), *selector, "wrong selector for {}", signature);
+        }
+    }
+
+    #[test]
+    fn test_supported_selectors_are_dispatched() {
+        let config = ArbitrumConfig::default();
+        let handlers: Vec<Box<dyn PrecompileHandler>> = vec![
+            Box::new(ArbSysHandler::new()),
+            Box::new(ArbGasInfoHandler::new()),
+        ];
+        for handler in handlers {
+            for (signature, selector) in handler.supported_selectors() {
+                // Enough zeroed words for the widest argument list (address, bytes)
+                let mut input = selector.to_vec();
+                input.extend_from_slice(&[0u8; 64]);
+                assert!(
+                    handler.handle_call(&input, &config).is_ok(),
+                    "{} {} is listed but not handled",
+                    handler.name(),
+                    signature
+                );
+            }
+        }
+    }
+
+    #[test]
+    fn test_configured_gas_costs_override_defaults() {
+        let registry = PrecompileRegistry::default();
+        let arb_sys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let arb_gas_info = Address::from_hex("0x000000000000000000000000000000000000006c").unwrap();
+        let chain_id = hex::decode(selectors::ARB_CHAIN_ID).unwrap();
+        let prices = hex::decode(selectors::GET_PRICES_IN_ARB_GAS).unwrap();
+
+        let mut config = ArbitrumConfig::default();
+        assert_eq!(registry.handle_call_metered(arb_sys, &chain_id, &config).unwrap().1, 3);
+        assert_eq!(registry.handle_call_metered(arb_gas_info, &prices, &config).unwrap().1, 96);
+
+        config.precompile_gas.insert("arbsys".to_string(), 700);
+        config.validate().unwrap();
+        let (output, gas) = registry.handle_call_metered(arb_sys, &chain_id, &config).unwrap();
+        assert_eq!(U256::from_big_endian(&output), U256::from_u64(config.chain_id));
+        assert_eq!(gas, 700);
+        // Other precompiles keep their built-in costs
+        assert_eq!(registry.handle_call_metered(arb_gas_info, &prices, &config).unwrap().1, 96);
+
+        config.precompile_gas.insert("arbfoo".to_string(), 1);
+        assert!(config.validate().unwrap_err().to_string().contains("Unknown precompile 'arbfoo'"));
+    }
+
+    #[test]
+    fn test_from_config_skips_disabled_precompiles() {
+        let mut config = ArbitrumConfig::default();
+        config.disable_precompiles(&["arbgasinfo"]).unwrap();
+        let registry = PrecompileRegistry::from_config(&config, GasPool::default(), MockChain::default());
+
+        let arb_gas_info = Address::from_hex("0x000000000000000000000000000000000000006c").unwrap();
+        let arb_sys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        assert!(!registry.has_handler(&arb_gas_info));
+        assert_eq!(
+            registry.handle_call(arb_gas_info, &selector("getPricesInWei()"), &config),
+            Err(PrecompileError::NoHandler { address: arb_gas_info })
+        );
+        let result = registry.handle_call(arb_sys, &selector("arbChainID()"), &config).unwrap();
+        assert_eq!(U256::from_big_endian(&result), U256::from_u64(42161));
+        assert_eq!(registry.get_addresses().len(), 2);
+
+        config.enable_only_precompiles(&["ArbRetryableTx"]).unwrap();
+        let registry = PrecompileRegistry::from_config(&config, GasPool::default(), MockChain::default());
+        assert_eq!(registry.get_addresses().len(), 1);
+        assert!(!registry.has_handler(&arb_sys));
+
+        let err = config.disable_precompiles(&["arbaddresstable"]).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown precompile 'arbaddresstable' (known: arbgasinfo, arbretryabletx, arbsys)");
+    }
+
+    #[test]
+    fn test_storage_gas_available_decreases_with_storage_writes() {
+        use crate::tx7e::{mock_deposit, Tx7eProcessor};
+
+        let config = ArbitrumConfig {
+            storage_gas_available: 1_000_000,
+            ..ArbitrumConfig::default()
+        };
+        let registry = PrecompileRegistry::from_config(&config, GasPool::default(), MockChain::default());
+        let processor = Tx7eProcessor::new().with_storage_gas(registry.storage_gas().clone());
+        let arbsys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let storage_gas_available = || {
+            let input = hex::decode(selectors::GET_STORAGE_GAS_AVAILABLE).unwrap();
+            let result = registry.handle_call(arbsys, &input, &config).unwrap();
+            assert_eq!(result.len(), 32);
+            U256::from_big_endian(&result)
+        };
+        assert_eq!(storage_gas_available(), U256::from_u64(1_000_000));
+
+        // withdrawEth(address) records an outbox leaf
+        let mut input = hex::decode(selectors::WITHDRAW_ETH).unwrap();
+        input.extend_from_slice(&[0u8; 32]);
+        registry.handle_call(arbsys, &input, &config).unwrap();
+        assert_eq!(storage_gas_available(), U256::from_u64(1_000_000 - STORAGE_WRITE_GAS));
+
+        // A deposit through the processor drains the same counter
+        let tx = mock_deposit([4u8; 32]).build().unwrap();
+        let raw_tx = tx.rlp_encode_typed();
+        assert!(futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+        assert_eq!(storage_gas_available(), U256::from_u64(1_000_000 - 2 * STORAGE_WRITE_GAS));
+    }
+
+    #[test]
+    fn test_l1_fees_available_sums_processed_deposits() {
+        use crate::tx7e::{mock_deposit, Tx7eProcessor};
+
+        let config = ArbitrumConfig::default();
+        let registry = PrecompileRegistry::with_state(GasPool::default(), MockChain::default());
+        let processor = Tx7eProcessor::new().with_l1_fee_pool(registry.l1_fee_pool().clone());
+        let arb_gas_info = Address::from_hex("0x000000000000000000000000000000000000006c").unwrap();
+        let l1_fees_available = || {
+            let input = hex::decode(selectors::GET_L1_FEES_AVAILABLE).unwrap();
+            U256::from_big_endian(&registry.handle_call(arb_gas_info, &input, &config).unwrap())
+        };
+        assert_eq!(l1_fees_available(), U256::zero());
+
+        for (source_hash, l1_fee) in [([5u8; 32], 21_000), ([6u8; 32], 4_000)] {
+            let tx = mock_deposit(source_hash).with_l1_fee(U256::from_u64(l1_fee)).build().unwrap();
+            let raw_tx = tx.rlp_encode_typed();
+            assert!(futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+        }
+        assert_eq!(l1_fees_available(), U256::from_u64(25_000));
+    }
+
+    #[test]
+    fn test_call_by_signature() {
+        let config = ArbitrumConfig::default();
+        let registry = PrecompileRegistry::default();
+        let arb_gas_info = Address::from_hex("0x000000000000000000000000000000000000006c").unwrap();
+
+        let output = registry
+            .call_by_signature(&arb_gas_info, "getPricesInWei()", &[], &config)
+            .unwrap();
+        let prices: Vec<U256> = output.chunks(32).map(U256::from_big_endian).collect();
+        let l2_base_fee = U256::from_u64(config.gas_price_components.effective_l2_base_fee());
+        assert_eq!(prices.len(), 6);
+        assert_eq!(prices[0], l2_base_fee);
+        assert_eq!(prices[2], U256::from_u64(config.l1_base_fee * config.gas_price_components.l1_calldata_cost));
+        assert_eq!(prices[5], l2_base_fee);
+
+        // Static and dynamic arguments are encoded for the handler
+        let aggregator = AbiValue::Address(Address::from_hex("0x1234567890123456789012345678901234567890").unwrap());
+        let with_aggregator = registry
+            .call_by_signature(&arb_gas_info, "getPricesInWeiWithAggregator(address)", &[aggregator], &config)
+            .unwrap();
+        assert_eq!(with_aggregator, output);
+
+        let withdrawals = WithdrawalStore::new();
+        let mut registry = PrecompileRegistry::new();
+        registry.register(Box::new(ArbSysHandler::with_withdrawals(withdrawals.clone())));
+        let arb_sys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let destination = Address::from_hex("0x00000000000000000000000000000000000000aa").unwrap();
+        let args = [AbiValue::Address(destination), AbiValue::Bytes(vec![0xab; 33])];
+        registry
+            .call_by_signature(&arb_sys, "sendTxToL1(address,bytes)", &args, &config)
+            .unwrap();
+        assert_eq!(withdrawals.get(0).unwrap().data, vec![0xab; 33]);
+
+        assert_eq!(
+            registry.call_by_signature(&arb_sys, "sendTxToL1(address,bytes)", &args[..1], &config),
+            Err(PrecompileError::ArgumentMismatch {
+                signature: "sendTxToL1(address,bytes)".to_string()
+            })
+        );
+        assert!(registry
+            .call_by_signature(&arb_sys, "withdrawEth(address)", &[AbiValue::Bool(true)], &config)
+            .is_err());
+    }
+
+    #[test]
+    fn test_truncated_arguments_are_rejected() {
+        let config = ArbitrumConfig::default();
+        let registry = PrecompileRegistry::default();
+        let short = PrecompileError::InvalidInput("Input too short for function arguments");
+        for address in registry.get_addresses() {
+            let handler = registry.get_handler(&address).unwrap();
+            for (signature, selector) in handler.supported_selectors() {
+                let args = parse_signature(&signature).1.len();
+                if args == 0 {
+                    continue;
+                }
+                // No argument bytes, then every argument but the last byte
+                for len in [0, 32 * args - 1] {
+                    let mut input = selector.to_vec();
+                    input.resize(4 + len, 0);
+                    assert_eq!(
+                        handler.handle_call(&input, &config),
+                        Err(short.clone()),
+                        "{} {} with {} argument bytes",
+                        handler.name(),
+                        signature,
+                        len
+                    );
+                }
+            }
+        }
+
+        // sendTxToL1(address,bytes) needs a head word for both arguments
+        let arb_sys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let mut input = selector("sendTxToL1(address,bytes)").to_vec();
+        input.extend_from_slice(&[0u8; 32]);
+        assert_eq!(registry.handle_call(arb_sys, &input, &config), Err(short));
     }
 }
@@ -0,0 +1,818 @@
+//! Address and U256 primitives
+//!
+//! By default these are small self-contained types. With the `alloy` feature they
+//! are aliases for the `alloy-primitives` types instead, and the `AddressExt` and
+//! `U256Ext` traits provide the helpers the rest of the crate relies on.
+//!
+//! With `std`, both types implement serde: `Address` as a `0x`-prefixed hex string,
+//! and `U256` as a hex quantity, deserialized from a number, a decimal string or
+//! a `0x`-prefixed hex string.
+
+#[cfg(not(feature = "alloy"))]
+pub use native::{Address, U256};
+
+#[cfg(feature = "alloy")]
+pub use alloy::{Address, AddressExt, U256Ext, U256};
+
+/// Whether the last address byte falls in one of Arbitrum's precompile slots
+///
+/// Covers the ArbOS precompiles (`0x64`..=`0x73`), `NodeInterface` (`0xc8`),
+/// `NodeInterfaceDebug` (`0xc9`) and `ArbDebug` (`0xff`).
+fn is_arbitrum_precompile(bytes: &[u8; 20]) -> bool {
+    bytes[..19].iter().all(|&b| b == 0) && matches!(bytes[19], 0x64..=0x73 | 0xc8 | 0xc9 | 0xff)
+}
+
+/// Wrapper that formats bytes as `0x`-prefixed lowercase hex
+///
+/// Both `Display` and `Debug` use the hex form, so hashes stay readable in logs
+/// and in derived `Debug` output.
+#[derive(Clone, Copy, PartialEq, Eq, Hash)]
+pub struct HexBytes<T>(pub T);
+
+impl<T: AsRef<[u8]>> core::fmt::Display for HexBytes<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        write!(f, "0x{}", hex::encode(self.0.as_ref()))
+    }
+}
+
+impl<T: AsRef<[u8]>> core::fmt::Debug for HexBytes<T> {
+    fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+        core::fmt::Display::fmt(self, f)
+    }
+}
+
+#[cfg(not(feature = "alloy"))]
+mod native {
+    use alloc::string::String;
+    use alloc::vec::Vec;
+    use anyhow::{anyhow, Result};
+
+    /// Simple address type (20 bytes)
+    #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+    pub struct Address([u8; 20]);
+
+    impl Address {
+        /// The zero address
+        pub const ZERO: Self = Self([0u8; 20]);
+
+        pub fn new(bytes: [u8; 20]) -> Self {
+            Self(bytes)
+        }
+
+        pub fn is_zero(&self) -> bool {
+            *self == Self::ZERO
+        }
+
+        /// Check if this is one of the canonical Arbitrum precompile addresses
+        pub fn is_precompile(&self) -> bool {
+            super::is_arbitrum_precompile(&self.0)
+        }
+
+        pub fn as_bytes(&self) -> &[u8; 20] {
+            &self.0
+        }
+
+        pub fn from_hex(hex: &str) -> Result<Self> {
+            let hex = hex.strip_prefix("0x").unwrap_or(hex);
+            if hex.len() != 40 {
+                return Err(anyhow!("Invalid address length"));
+            }
+
+            let mut bytes = [0u8; 20];
+            for (i, chunk) in hex.as_bytes().chunks(2).enumerate() {
+                if i >= 20 {
+                    break;
+                }
+                let byte = u8::from_str_radix(
+                    core::str::from_utf8(chunk)?,
+                    16
+                )?;
+                bytes[i] = byte;
+            }
+
+            Ok(Self(bytes))
+        }
+    }
+
+    impl core::fmt::Display for Address {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            write!(f, "0x")?;
+            for byte in &self.0 {
+                write!(f, "{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+
+    impl core::str::FromStr for Address {
+        type Err = anyhow::Error;
+
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            Self::from_hex(s)
+        }
+    }
+
+    impl From<[u8; 20]> for Address {
+        fn from(bytes: [u8; 20]) -> Self {
+            Self(bytes)
+        }
+    }
+
+    impl TryFrom<&[u8]> for Address {
+        type Error = anyhow::Error;
+
+        fn try_from(bytes: &[u8]) -> Result<Self, Self::Error> {
+            let bytes: [u8; 20] = bytes
+                .try_into()
+                .map_err(|_| anyhow!("Invalid address length: expected 20 bytes, got {}", bytes.len()))?;
+            Ok(Self(bytes))
+        }
+    }
+
+    impl TryFrom<&str> for Address {
+        type Error = anyhow::Error;
+
+        fn try_from(s: &str) -> Result<Self, Self::Error> {
+            Self::from_hex(s)
+        }
+    }
+
+    impl AsRef<[u8]> for Address {
+        fn as_ref(&self) -> &[u8] {
+            &self.0
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl serde::Serialize for Address {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.collect_str(self)
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<'de> serde::Deserialize<'de> for Address {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            let hex = String::deserialize(deserializer)?;
+            Self::from_hex(&hex).map_err(serde::de::Error::custom)
+        }
+    }
+
+    /// Simple U256 type
+    #[derive(Debug, Clone, Copy, Default, PartialEq, Eq, PartialOrd, Ord)]
+    pub struct U256([u8; 32]);
+
+    impl U256 {
+        pub fn new(bytes: [u8; 32]) -> Self {
+            Self(bytes)
+        }
+
+        pub fn from_u64(value: u64) -> Self {
+            let mut bytes = [0u8; 32];
+            bytes[24..32].copy_from_slice(&value.to_be_bytes());
+            Self(bytes)
+        }
+
+        pub fn from_big_endian(bytes: &[u8]) -> Self {
+            // Fast path for values that fit in a u64 (fees, gas, block numbers)
+            if bytes.len() <= 8 {
+                let mut word = [0u8; 8];
+                word[8 - bytes.len()..].copy_from_slice(bytes);
+                return Self::from_u64(u64::from_be_bytes(word));
+            }
+
+            let mut result = [0u8; 32];
+            let start = 32 - bytes.len().min(32);
+            result[start..].copy_from_slice(&bytes[..bytes.len().min(32)]);
+            Self(result)
+        }
+
+        /// All 32 bytes, leading zeros included; the inverse of `new`
+        pub fn to_big_endian(&self) -> Vec<u8> {
+            self.0.to_vec()
+        }
+
+        /// Big-endian bytes with leading zeros trimmed, empty for zero
+        ///
+        /// This is the RLP integer encoding, and what `from_big_endian` reads back.
+        pub fn to_big_endian_minimal(&self) -> Vec<u8> {
+            let start = self.0.iter().position(|&byte| byte != 0).unwrap_or(32);
+            self.0[start..].to_vec()
+        }
+
+        /// Encode as a JSON-RPC quantity: `0x` and minimal hex digits, with zero as `0x0`
+        pub fn to_hex_quantity(&self) -> String {
+            let hex = hex::encode(self.0);
+            let digits = hex.trim_start_matches('0');
+            let mut quantity = String::from("0x");
+            quantity.push_str(if digits.is_empty() { "0" } else { digits });
+            quantity
+        }
+
+        pub fn zero() -> Self {
+            Self([0u8; 32])
+        }
+
+        fn max_value() -> Self {
+            Self([0xff; 32])
+        }
+
+        // --- Math Implementations ---
+
+        /// Add, or `None` on overflow
+        pub fn checked_add(&self, other: Self) -> Option<Self> {
+            let (res, overflow) = self.overflowing_add(other);
+            (!overflow).then_some(res)
+        }
+
+        pub fn saturating_add(&self, other: Self) -> Self {
+            let (res, overflow) = self.overflowing_add(other);
+            if overflow {
+                Self::max_value()
+            } else {
+                res
+            }
+        }
+
+        /// Subtract, or `None` on underflow
+        pub fn checked_sub(&self, other: Self) -> Option<Self> {
+            let (res, underflow) = self.overflowing_sub(other);
+            (!underflow).then_some(res)
+        }
+
+        /// Subtract, stopping at zero
+        pub fn saturating_sub(&self, other: Self) -> Self {
+            let (res, underflow) = self.overflowing_sub(other);
+            if underflow {
+                Self::zero()
+            } else {
+                res
+            }
+        }
+
+        pub fn saturating_mul(&self, other: Self) -> Self {
+            let a_limbs = self.to_u64_limbs();
+            let b_limbs = other.to_u64_limbs();
+
+            // 4x4 limb multiplication
+            let mut res_limbs = [0u64; 8];
+
+            for i in 0..4 {
+                let mut carry = 0u64;
+                for j in 0..4 {
+                    let product = (a_limbs[i] as u128) * (b_limbs[j] as u128);
+                    let sum = (res_limbs[i + j] as u128) + product + (carry as u128);
+
+                    res_limbs[i + j] = sum as u64; // Low part
+                    carry = (sum >> 64) as u64;    // High part
+                }
+                res_limbs[i + 4] += carry;
+            }
+
+
+            if res_limbs[4..8].iter().any(|&x| x != 0) {
+                return Self::max_value();
+            }
+
+            Self::from_u64_limbs(&res_limbs[0..4])
+        }
+
+        /// Raise to the power `exp`, saturating at `2^256 - 1` on overflow
+        ///
+        /// `0^0` is 1. Takes a `U256` exponent, like alloy's method of the same
+        /// name, so one call saturates under either backend.
+        pub fn saturating_pow(self, exp: Self) -> Self {
+            let mut result = Self::from_u64(1);
+            if exp == Self::zero() {
+                return result;
+            }
+            for bit in (0..=exp.log2_floor() as usize).rev() {
+                result = result.saturating_mul(result);
+                if exp.0[31 - bit / 8] & (1 << (bit % 8)) != 0 {
+                    result = result.saturating_mul(self);
+                }
+            }
+            result
+        }
+
+        /// Index of the highest set bit, i.e. `floor(log2(self))`
+        ///
+        /// Zero has no logarithm and also returns 0.
+        pub fn log2_floor(&self) -> u32 {
+            match self.0.iter().position(|&byte| byte != 0) {
+                Some(i) => 8 * (31 - i as u32) + 7 - self.0[i].leading_zeros(),
+                None => 0,
+            }
+        }
+
+        // --- Internal Helpers ---
+
+        fn overflowing_add(&self, other: Self) -> (Self, bool) {
+            let mut result = [0u8; 32];
+            let mut carry = 0u16;
+
+            for i in (0..32).rev() {
+                let sum = (self.0[i] as u16) + (other.0[i] as u16) + carry;
+                result[i] = (sum & 0xff) as u8;
+                carry = sum >> 8;
+            }
+
+            (Self(result), carry > 0)
+        }
+
+        fn overflowing_sub(&self, other: Self) -> (Self, bool) {
+            let mut result = [0u8; 32];
+            let mut borrow = 0i16;
+
+            for i in (0..32).rev() {
+                let mut diff = (self.0[i] as i16) - (other.0[i] as i16) - borrow;
+                borrow = (diff < 0) as i16;
+                if diff < 0 {
+                    diff += 256;
+                }
+                result[i] = diff as u8;
+            }
+
+            (Self(result), borrow > 0)
+        }
+
+        fn to_u64_limbs(self) -> [u64; 4] {
+            let mut limbs = [0u64; 4];
+            for (i, limb) in limbs.iter_mut().enumerate() {
+                let start = 24 - (i * 8);
+                let end = 32 - (i * 8);
+                let mut chunk = [0u8; 8];
+                chunk.copy_from_slice(&self.0[start..end]);
+                *limb = u64::from_be_bytes(chunk);
+            }
+            limbs
+        }
+
+        fn from_u64_limbs(limbs: &[u64]) -> Self {
+            let mut bytes = [0u8; 32];
+            for (i, limb) in limbs.iter().take(4).enumerate() {
+                let chunk = limb.to_be_bytes();
+                let start = 24 - (i * 8);
+                let end = 32 - (i * 8);
+                bytes[start..end].copy_from_slice(&chunk);
+            }
+            Self(bytes)
+        }
+    }
+
+
+    impl core::ops::Add for U256 {
+        type Output = Self;
+
+        fn add(self, other: Self) -> Self {
+            let mut result = [0u8; 32];
+            let mut carry = 0u16;
+
+            for i in (0..32).rev() {
+                let sum = self.0[i] as u16 + other.0[i] as u16 + carry;
+                result[i] = (sum & 0xff) as u8;
+                carry = sum >> 8;
+            }
+
+            Self(result)
+        }
+    }
+
+    macro_rules! impl_bitwise {
+        ($trait:ident, $method:ident, $op:tt) => {
+            impl core::ops::$trait for U256 {
+                type Output = Self;
+
+                fn $method(self, other: Self) -> Self {
+                    let mut result = [0u8; 32];
+                    for (i, byte) in result.iter_mut().enumerate() {
+                        *byte = self.0[i] $op other.0[i];
+                    }
+                    Self(result)
+                }
+            }
+        };
+    }
+
+    impl_bitwise!(BitAnd, bitand, &);
+    impl_bitwise!(BitOr, bitor, |);
+    impl_bitwise!(BitXor, bitxor, ^);
+
+    impl core::ops::Shl<usize> for U256 {
+        type Output = Self;
+
+        /// Shift towards the most significant bit; shifts of 256 or more give zero
+        fn shl(self, shift: usize) -> Self {
+            let mut result = [0u8; 32];
+            if shift >= 256 {
+                return Self(result);
+            }
+            let (bytes, bits) = (shift / 8, (shift % 8) as u32);
+            for (i, byte) in result.iter_mut().enumerate().take(32 - bytes) {
+                *byte = self.0[i + bytes] << bits;
+                if bits > 0 && i + bytes + 1 < 32 {
+                    *byte |= self.0[i + bytes + 1] >> (8 - bits);
+                }
+            }
+            Self(result)
+        }
+    }
+
+    impl core::ops::Shr<usize> for U256 {
+        type Output = Self;
+
+        /// Shift towards the least significant bit; shifts of 256 or more give zero
+        fn shr(self, shift: usize) -> Self {
+            let mut result = [0u8; 32];
+            if shift >= 256 {
+                return Self(result);
+            }
+            let (bytes, bits) = (shift / 8, (shift % 8) as u32);
+            for (i, byte) in result.iter_mut().enumerate().skip(bytes) {
+                *byte = self.0[i - bytes] >> bits;
+                if bits > 0 && i > bytes {
+                    *byte |= self.0[i - bytes - 1] << (8 - bits);
+                }
+            }
+            Self(result)
+        }
+    }
+
+    impl core::str::FromStr for U256 {
+        type Err = anyhow::Error;
+
+        /// Parse a decimal string, or hex with a `0x` prefix
+        fn from_str(s: &str) -> Result<Self, Self::Err> {
+            if let Some(hex) = s.strip_prefix("0x") {
+                if hex.is_empty() || hex.len() > 64 {
+                    return Err(anyhow!("Invalid U256 hex length: {}", hex.len()));
+                }
+                let mut bytes = [0u8; 32];
+                hex::decode_to_slice(alloc::format!("{:0>64}", hex), &mut bytes)
+                    .map_err(|e| anyhow!("Invalid U256 hex: {}", e))?;
+                return Ok(Self(bytes));
+            }
+
+            if s.is_empty() || !s.bytes().all(|b| b.is_ascii_digit()) {
+                return Err(anyhow!("Invalid U256 decimal: '{}'", s));
+            }
+            let mut bytes = [0u8; 32];
+            for digit in s.bytes() {
+                let mut carry = (digit - b'0') as u16;
+                for byte in bytes.iter_mut().rev() {
+                    let value = (*byte as u16) * 10 + carry;
+                    *byte = value as u8;
+                    carry = value >> 8;
+                }
+                if carry != 0 {
+                    return Err(anyhow!("U256 decimal overflows 256 bits: '{}'", s));
+                }
+            }
+            Ok(Self(bytes))
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl serde::Serialize for U256 {
+        fn serialize<S: serde::Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+            serializer.serialize_str(&self.to_hex_quantity())
+        }
+    }
+
+    #[cfg(feature = "std")]
+    impl<'de> serde::Deserialize<'de> for U256 {
+        fn deserialize<D: serde::Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+            struct U256Visitor;
+
+            impl serde::de::Visitor<'_> for U256Visitor {
+                type Value = U256;
+
+                fn expecting(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+                    f.write_str("a number, a decimal string or a 0x-prefixed hex string")
+                }
+
+                fn visit_u64<E: serde::de::Error>(self, value: u64) -> Result<U256, E> {
+                    Ok(U256::from_u64(value))
+                }
+
+                fn visit_str<E: serde::de::Error>(self, value: &str) -> Result<U256, E> {
+                    value.parse().map_err(E::custom)
+                }
+            }
+
+            deserializer.deserialize_any(U256Visitor)
+        }
+    }
+
+    impl core::fmt::Display for U256 {
+        fn fmt(&self, f: &mut core::fmt::Formatter<'_>) -> core::fmt::Result {
+            // Convert to hex string for display
+            write!(f, "0x")?;
+            for byte in &self.0 {
+                write!(f, "{:02x}", byte)?;
+            }
+            Ok(())
+        }
+    }
+}
+
+#[cfg(feature = "alloy")]
+mod alloy {
+    use anyhow::{anyhow, Result};
+
+    pub use alloy_primitives::{Address, U256};
+
+    /// Helpers the crate uses on `Address`, provided for the alloy type
+    pub trait AddressExt: Sized {
+        fn as_bytes(&self) -> &[u8; 20];
+        fn from_hex(hex: &str) -> Result<Self>;
+        fn is_precompile(&self) -> bool;
+    }
+
+    impl AddressExt for Address {
+        fn as_bytes(&self) -> &[u8; 20] {
+            &self.0 .0
+        }
+
+        fn is_precompile(&self) -> bool {
+            super::is_arbitrum_precompile(self.as_bytes())
+        }
+
+        fn from_hex(hex: &str) -> Result<Self> {
+            let hex = hex.strip_prefix("0x").unwrap_or(hex);
+            if hex.len() != 40 {
+                return Err(anyhow!("Invalid address length"));
+            }
+            let mut bytes = [0u8; 20];
+            hex::decode_to_slice(hex, &mut bytes)?;
+            Ok(Address::new(bytes))
+        }
+    }
+
+    /// Helpers the crate uses on `U256`, provided for the alloy type
+    pub trait U256Ext: Sized {
+        fn new(bytes: [u8; 32]) -> Self;
+        fn from_u64(value: u64) -> Self;
+        fn from_big_endian(bytes: &[u8]) -> Self;
+        fn to_big_endian(&self) -> Vec<u8>;
+        fn to_big_endian_minimal(&self) -> Vec<u8>;
+        fn zero() -> Self;
+        fn log2_floor(&self) -> u32;
+        fn to_hex_quantity(&self) -> String;
+    }
+
+    impl U256Ext for U256 {
+        fn new(bytes: [u8; 32]) -> Self {
+            U256::from_be_bytes(bytes)
+        }
+
+        fn from_u64(value: u64) -> Self {
+            U256::from(value)
+        }
+
+        /// Matches the native type: inputs longer than 32 bytes keep their first 32 bytes
+        fn from_big_endian(bytes: &[u8]) -> Self {
+            U256::from_be_slice(&bytes[..bytes.len().min(32)])
+        }
+
+        fn to_big_endian(&self) -> Vec<u8> {
+            self.to_be_bytes::<32>().to_vec()
+        }
+
+        fn to_big_endian_minimal(&self) -> Vec<u8> {
+            self.to_be_bytes_trimmed_vec()
+        }
+
+        fn zero() -> Self {
+            U256::ZERO
+        }
+
+        /// Matches the native type: zero returns 0
+        fn log2_floor(&self) -> u32 {
+            self.bit_len().saturating_sub(1) as u32
+        }
+
+        fn to_hex_quantity(&self) -> String {
+            format!("{:#x}", self)
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use alloc::string::ToString;
+    use alloc::{format, vec};
+
+    #[test]
+    fn test_hex_bytes_formatting() {
+        let hash = HexBytes([0xab; 32]);
+        let expected = format!("0x{}", "ab".repeat(32));
+        assert_eq!(hash.to_string(), expected);
+        assert_eq!(format!("{:?}", hash), expected);
+        assert_eq!(expected.len(), 66);
+        assert_eq!(HexBytes(&[] as &[u8]).to_string(), "0x");
+    }
+
+    #[test]
+    fn test_address_conversions() {
+        let mut bytes = [0u8; 20];
+        bytes[19] = 0x64;
+
+        let from_array = Address::from(bytes);
+        let from_slice = Address::try_from(&bytes[..]).unwrap();
+        assert_eq!(from_array, from_slice);
+
+        let as_ref: &[u8] = from_array.as_ref();
+        assert_eq!(as_ref, &bytes[..]);
+    }
+
+    #[test]
+    fn test_address_zero_and_precompile() {
+        assert!(Address::ZERO.is_zero());
+        assert!(!Address::ZERO.is_precompile());
+
+        let arbsys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        assert!(!arbsys.is_zero());
+        assert!(arbsys.is_precompile());
+        assert!(Address::from_hex("0x00000000000000000000000000000000000000ff").unwrap().is_precompile());
+        assert!(!Address::from_hex("0x0000000000000000000000000000000000000063").unwrap().is_precompile());
+        assert!(!Address::from_hex("0x0100000000000000000000000000000000000064").unwrap().is_precompile());
+    }
+
+    #[test]
+    fn test_u256_from_big_endian_lengths() {
+        assert_eq!(U256::from_big_endian(&[]), U256::zero());
+        assert_eq!(U256::from_big_endian(&[0x01, 0x00]), U256::from_u64(256));
+        assert_eq!(U256::from_big_endian(&u64::MAX.to_be_bytes()), U256::from_u64(u64::MAX));
+
+        let mut nine = [0u8; 9];
+        nine[0] = 1;
+        let value = U256::from_big_endian(&nine);
+        assert_eq!(value.to_big_endian()[23], 1);
+        assert_eq!(U256::from_big_endian(&value.to_big_endian()), value);
+
+        // Inputs longer than 32 bytes keep their first 32 bytes
+        let mut long = [0u8; 33];
+        long[0] = 0xaa;
+        long[32] = 0xbb;
+        let truncated = U256::from_big_endian(&long);
+        assert_eq!(truncated.to_big_endian()[0], 0xaa);
+        assert_eq!(truncated.to_big_endian()[31], 0);
+    }
+
+    #[test]
+    fn test_u256_shifts_cross_byte_boundaries() {
+        let one = U256::from_u64(1);
+        assert_eq!(one << 9usize, U256::from_u64(512));
+        assert_eq!(U256::from_u64(0x81) << 9usize, U256::from_u64(0x10200));
+        assert_eq!(U256::from_u64(0x10200) >> 9usize, U256::from_u64(0x81));
+        assert_eq!(U256::from_u64(0x1ff) >> 9usize, U256::zero());
+
+        // Bits carried across the u64 limb boundary and off either end
+        let high = one << 255usize;
+        assert_eq!(high.to_big_endian()[0], 0x80);
+        assert_eq!(high >> 255usize, one);
+        assert_eq!((U256::from_u64(u64::MAX) << 4usize).to_big_endian()[23], 0x0f);
+        assert_eq!(high << 1usize, U256::zero());
+        assert_eq!(one >> 1usize, U256::zero());
+        assert_eq!(one << 256usize, U256::zero());
+        assert_eq!(high >> 256usize, U256::zero());
+        assert_eq!(one << 0usize, one);
+    }
+
+    #[test]
+    fn test_u256_bitwise_ops() {
+        let a = U256::from_u64(0b1100);
+        let b = U256::from_u64(0b1010);
+        assert_eq!(a & b, U256::from_u64(0b1000));
+        assert_eq!(a | b, U256::from_u64(0b1110));
+        assert_eq!(a ^ b, U256::from_u64(0b0110));
+        assert_eq!(a ^ a, U256::zero());
+
+        // Mask the low 160 bits of a packed word, as when decoding an address
+        let word = U256::new([0xab; 32]);
+        let address_mask = U256::new([0xff; 32]) >> 96usize;
+        let address = (word & address_mask).to_big_endian();
+        assert_eq!(address[..12], [0u8; 12]);
+        assert_eq!(address[12..], [0xab; 20]);
+        assert_eq!((word | address_mask).to_big_endian()[31], 0xff);
+    }
+
+    #[test]
+    fn test_u256_to_hex_quantity() {
+        assert_eq!(U256::zero().to_hex_quantity(), "0x0");
+        assert_eq!(U256::from_u64(0x2a).to_hex_quantity(), "0x2a");
+        assert_eq!(U256::from_u64(0x100).to_hex_quantity(), "0x100");
+        assert_eq!(U256::from_u64(1_000_000_000_000_000_000).to_hex_quantity(), "0xde0b6b3a7640000");
+        assert_eq!(U256::new([0xff; 32]).to_hex_quantity(), format!("0x{}", "f".repeat(64)));
+
+        let mut top_bit = [0u8; 32];
+        top_bit[0] = 0x80;
+        assert_eq!(U256::new(top_bit).to_hex_quantity(), format!("0x8{}", "0".repeat(63)));
+    }
+
+    #[test]
+    fn test_u256_saturating_sub() {
+        assert_eq!(U256::from_u64(300).saturating_sub(U256::from_u64(45)), U256::from_u64(255));
+        assert_eq!(U256::from_u64(1 << 40).saturating_sub(U256::from_u64(1)), U256::from_u64((1 << 40) - 1));
+        assert_eq!(U256::from_u64(1).saturating_sub(U256::from_u64(2)), U256::zero());
+        assert_eq!(U256::new([0xff; 32]).saturating_sub(U256::new([0xff; 32])), U256::zero());
+
+        assert_eq!(U256::from_u64(300).checked_sub(U256::from_u64(45)), Some(U256::from_u64(255)));
+        assert_eq!(U256::from_u64(1).checked_sub(U256::from_u64(2)), None);
+    }
+
+    #[test]
+    fn test_u256_to_big_endian_minimal() {
+        assert!(U256::zero().to_big_endian_minimal().is_empty());
+        assert_eq!(U256::from_u64(0x7f).to_big_endian_minimal(), vec![0x7f]);
+        assert_eq!(U256::from_u64(0x400).to_big_endian_minimal(), vec![0x04, 0x00]);
+        assert_eq!(U256::new([0xff; 32]).to_big_endian_minimal(), vec![0xff; 32]);
+
+        // Matches the RLP integer encoding of the same value, and reads back
+        for value in [0u64, 1, 0x7f, 0x80, 0x400, 20_000_000_000, u64::MAX] {
+            let minimal = U256::from_u64(value).to_big_endian_minimal();
+            assert_eq!(rlp::encode(&minimal), rlp::encode(&value));
+            assert_eq!(U256::from_big_endian(&minimal), U256::from_u64(value));
+        }
+    }
+
+    #[test]
+    fn test_u256_log2_floor() {
+        assert_eq!(U256::zero().log2_floor(), 0);
+        assert_eq!(U256::from_u64(1).log2_floor(), 0);
+        assert_eq!(U256::from_u64(2).log2_floor(), 1);
+        assert_eq!(U256::from_u64(255).log2_floor(), 7);
+        assert_eq!(U256::from_u64(256).log2_floor(), 8);
+        assert_eq!(U256::from_u64(u64::MAX).log2_floor(), 63);
+        assert_eq!(U256::new([0xff; 32]).log2_floor(), 255);
+    }
+
+    #[test]
+    fn test_u256_saturating_pow() {
+        let pow = |base: u64, exp: u64| U256::from_u64(base).saturating_pow(U256::from_u64(exp));
+        let mut top_bit = [0u8; 32];
+        top_bit[0] = 0x80;
+        assert_eq!(pow(2, 255), U256::new(top_bit));
+        assert_eq!(pow(2, 255).log2_floor(), 255);
+        assert_eq!(pow(2, 256), U256::new([0xff; 32]));
+        assert_eq!(pow(3, u64::MAX), U256::new([0xff; 32]));
+        assert_eq!(U256::from_u64(2).saturating_pow(U256::new([0xff; 32])), U256::new([0xff; 32]));
+        assert_eq!(pow(10, 18), U256::from_u64(1_000_000_000_000_000_000));
+        assert_eq!(pow(7, 5), U256::from_u64(16_807));
+        assert_eq!(pow(3, 0), U256::from_u64(1));
+        assert_eq!(pow(0, 0), U256::from_u64(1));
+        assert_eq!(pow(0, 5), U256::zero());
+        assert_eq!(pow(1, u64::MAX), U256::from_u64(1));
+    }
+
+    #[test]
+    fn test_address_conversion_length_mismatch() {
+        assert!(Address::try_from(&[0u8; 19][..]).is_err());
+        assert!(Address::try_from(&[0u8; 21][..]).is_err());
+    }
+
+    // alloy's `Address` has no `TryFrom<&str>`; it is parsed with `FromStr` instead
+    #[cfg(not(feature = "alloy"))]
+    #[test]
+    fn test_address_try_from_str() {
+        let address = Address::try_from("0x0000000000000000000000000000000000000064").unwrap();
+        assert_eq!(address.as_bytes()[19], 0x64);
+        assert!(Address::try_from("0x64").is_err());
+        assert!(Address::try_from("0x00000000000000000000000000000000000000zz").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_address_serde_round_trip() {
+        let address = Address::from_hex("0x000000000000000000000000000000000000006e").unwrap();
+        let json = serde_json::to_string(&address).unwrap();
+        assert_eq!(json.to_lowercase(), "\"0x000000000000000000000000000000000000006e\"");
+        assert_eq!(serde_json::from_str::<Address>(&json).unwrap(), address);
+        assert!(serde_json::from_str::<Address>("\"0x6e\"").is_err());
+        assert!(serde_json::from_str::<Address>("110").is_err());
+    }
+
+    #[cfg(feature = "std")]
+    #[test]
+    fn test_u256_serde_accepts_numbers_decimal_and_hex() {
+        let wei = U256::from_u64(1_000_000_000_000_000_000);
+        let json = serde_json::to_string(&wei).unwrap();
+        assert_eq!(json, "\"0xde0b6b3a7640000\"");
+        assert_eq!(serde_json::from_str::<U256>(&json).unwrap(), wei);
+        assert_eq!(serde_json::from_str::<U256>("\"1000000000000000000\"").unwrap(), wei);
+        assert_eq!(serde_json::from_str::<U256>("1000000000000000000").unwrap(), wei);
+        assert_eq!(serde_json::from_str::<U256>("\"0x0\"").unwrap(), U256::zero());
+
+        let max = U256::new([0xff; 32]);
+        let max_decimal = "115792089237316195423570985008687907853269984665640564039457584007913129639935";
+        assert_eq!(serde_json::from_str::<U256>(&format!("\"{}\"", max_decimal)).unwrap(), max);
+        assert_eq!(serde_json::from_str::<U256>(&serde_json::to_string(&max).unwrap()).unwrap(), max);
+
+        // One past the maximum, and strings that are neither decimal nor hex
+        assert!(serde_json::from_str::<U256>("\"115792089237316195423570985008687907853269984665640564039457584007913129639936\"").is_err());
+        assert!(serde_json::from_str::<U256>("\"12ab\"").is_err());
+        assert!(serde_json::from_str::<U256>(&format!("\"0x1{}\"", "0".repeat(64))).is_err());
+    }
+}
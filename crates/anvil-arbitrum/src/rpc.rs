@@ -0,0 +1,1139 @@
+//! JSON-RPC server that handles 0x7e transactions and forwards everything else to Anvil
+
+use crate::arbitrum::ArbitrumConfig;
+use crate::gas_pool::GasPool;
+use crate::metrics::{Metrics, MetricsSnapshot};
+use crate::mock_chain::MockChain;
+use crate::precompiles::{is_state_changing, Address, CallContext, PrecompileRegistry, U256};
+use crate::selector_registry;
+use crate::tx7e::{
+    calldata_gas, Tx7eParser, Tx7eProcessor, Tx7eTransaction, TxType, TX_BASE_GAS, TX_SUBTYPE_SUBMIT_RETRYABLE,
+};
+use crate::primitives::HexBytes;
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt, U256Ext};
+use anyhow::{anyhow, Result};
+use axum::extract::State;
+use axum::routing::{get, post};
+use axum::{Json, Router};
+use serde_json::{json, Value};
+use std::future::Future;
+use std::io::ErrorKind;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+use std::time::Duration;
+use tokio::net::TcpListener;
+use tracing::{debug, info};
+
+/// JSON-RPC error code for an unknown method
+pub const METHOD_NOT_FOUND: i64 = -32601;
+/// JSON-RPC error code for malformed parameters
+pub const INVALID_PARAMS: i64 = -32602;
+/// JSON-RPC error code for a request that could not be processed
+pub const SERVER_ERROR: i64 = -32000;
+/// JSON-RPC error code for a reverted call, as used by Ethereum clients
+pub const EXECUTION_REVERTED: i64 = 3;
+
+/// Time allowed to connect to the upstream Anvil or a fork endpoint
+pub const UPSTREAM_CONNECT_TIMEOUT: Duration = Duration::from_secs(5);
+/// Time allowed for a whole request to the upstream Anvil or a fork endpoint
+pub const UPSTREAM_REQUEST_TIMEOUT: Duration = Duration::from_secs(30);
+
+/// Selector of the standard `Error(string)` revert payload
+const ERROR_STRING_SELECTOR: [u8; 4] = [0x08, 0xc3, 0x79, 0xa0];
+
+/// A JSON-RPC error returned to the caller
+#[derive(Debug, Clone, PartialEq)]
+pub struct RpcError {
+    pub code: i64,
+    pub message: String,
+    pub data: Option<Value>,
+}
+
+impl RpcError {
+    pub fn new(code: i64, message: impl Into<String>) -> Self {
+        Self {
+            code,
+            message: message.into(),
+            data: None,
+        }
+    }
+
+    /// A reverted call carrying `Error(string)` revert data for `reason`
+    pub fn reverted(reason: &str) -> Self {
+        Self {
+            code: EXECUTION_REVERTED,
+            message: format!("execution reverted: {}", reason),
+            data: Some(json!(format!("0x{}", hex::encode(encode_revert_reason(reason))))),
+        }
+    }
+
+    fn to_json(&self) -> Value {
+        match &self.data {
+            Some(data) => json!({ "code": self.code, "message": self.message, "data": data }),
+            None => json!({ "code": self.code, "message": self.message }),
+        }
+    }
+}
+
+/// ABI-encode `reason` as `Error(string)` revert data
+pub fn encode_revert_reason(reason: &str) -> Vec<u8> {
+    let bytes = reason.as_bytes();
+    let mut data = ERROR_STRING_SELECTOR.to_vec();
+    data.extend_from_slice(&U256::from_u64(32).to_big_endian());
+    data.extend_from_slice(&U256::from_u64(bytes.len() as u64).to_big_endian());
+    data.extend_from_slice(bytes);
+    data.resize(data.len() + (32 - bytes.len() % 32) % 32, 0);
+    data
+}
+
+/// Shared state behind the RPC server
+pub struct RpcState {
+    config: Mutex<ArbitrumConfig>,
+    processor: Tx7eProcessor,
+    registry: PrecompileRegistry,
+    upstream: Option<String>,
+    client: reqwest::Client,
+    serve_metrics: bool,
+}
+
+impl RpcState {
+    /// Create server state for the given configuration
+    ///
+    /// The registry holds the precompiles `config` enables, and the processor
+    /// shares its gas pool, chain, pools, L1 base fee estimate, state and metrics.
+    pub fn new(config: ArbitrumConfig) -> Self {
+        let metrics = Metrics::new();
+        let registry = PrecompileRegistry::from_config(&config, GasPool::default(), MockChain::default())
+            .with_metrics(metrics.clone());
+        let processor = Tx7eProcessor::new()
+            .with_parser(Tx7eParser::from_config(&config))
+            .with_config(config.clone())
+            .with_registry_state(&registry)
+            .with_metrics(metrics);
+        Self {
+            config: Mutex::new(config),
+            processor,
+            registry,
+            upstream: None,
+            client: http_client(UPSTREAM_REQUEST_TIMEOUT),
+            serve_metrics: false,
+        }
+    }
+
+    /// Fail forwarded requests that take longer than `timeout` (default `UPSTREAM_REQUEST_TIMEOUT`)
+    pub fn with_upstream_timeout(mut self, timeout: Duration) -> Self {
+        self.client = http_client(timeout);
+        self
+    }
+
+    /// Serve a snapshot of the metrics in Prometheus text format at `GET /metrics`
+    pub fn with_metrics_endpoint(mut self) -> Self {
+        self.serve_metrics = true;
+        self
+    }
+
+    /// Forward requests the server does not handle itself to the given Anvil URL
+    pub fn with_upstream(mut self, url: impl Into<String>) -> Self {
+        self.upstream = Some(url.into());
+        self
+    }
+
+    /// Process 0x7e transactions with the given processor, used as is
+    pub fn with_processor(mut self, processor: Tx7eProcessor) -> Self {
+        self.processor = processor;
+        self
+    }
+
+    /// Serve precompile calls from the given registry
+    ///
+    /// The processor is rewired to the registry's shared state, as in `new`.
+    pub fn with_registry(mut self, registry: PrecompileRegistry) -> Self {
+        self.processor = self.processor.with_registry_state(&registry);
+        self.registry = registry;
+        self
+    }
+
+    /// Get a snapshot of the active Arbitrum configuration
+    ///
+    /// Parameters set through the registry's `ArbOwner`, such as the L2 base fee,
+    /// are applied on top, so precompile calls see them.
+    pub fn config(&self) -> ArbitrumConfig {
+        let mut config = self.config.lock().unwrap().clone();
+        self.registry.chain_owners().apply_to(&mut config);
+        config
+    }
+
+    /// Replace the active Arbitrum configuration
+    ///
+    /// The transaction processor prices later deposits with it too. The L1 base
+    /// fee estimate keeps its value and only takes a changed inertia.
+    pub fn set_config(&self, config: ArbitrumConfig) {
+        let mut current = self.config.lock().unwrap();
+        if config.l1_base_fee_inertia != current.l1_base_fee_inertia {
+            self.registry.l1_base_fee_estimate().set_inertia(config.l1_base_fee_inertia);
+        }
+        self.processor.set_config(&config);
+        *current = config;
+    }
+
+    /// Get the precompile registry
+    pub fn registry(&self) -> &PrecompileRegistry {
+        &self.registry
+    }
+
+    /// Get the 0x7e transaction processor
+    pub fn processor(&self) -> &Tx7eProcessor {
+        &self.processor
+    }
+
+    /// Get a snapshot of the processor's transaction counts and the registry's call counts
+    pub fn metrics(&self) -> MetricsSnapshot {
+        MetricsSnapshot {
+            precompile_calls: self.registry.metrics().snapshot().precompile_calls,
+            ..self.processor.metrics().snapshot()
+        }
+    }
+
+    /// Handle a single JSON-RPC request object
+    pub async fn handle_request(&self, request: Value) -> Value {
+        let id = request.get("id").cloned().unwrap_or(Value::Null);
+        let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+        let params = request.get("params").cloned().unwrap_or(Value::Null);
+        debug!("RPC request: {} {}", method, params);
+
+        let result = match method {
+            "eth_sendRawTransaction" => match self.send_raw_transaction(&params).await {
+                Some(result) => result,
+                None => self.forward(&request).await,
+            },
+            "eth_call" => match self.precompile_call(&params) {
+                Some(result) => result,
+                None => self.forward(&request).await,
+            },
+            "eth_estimateGas" => match self.precompile_estimate_gas(&params) {
+                Some(result) => result,
+                None => self.forward(&request).await,
+            },
+            "arb_getPrecompileResult" => self.get_precompile_result(&params),
+            "arb_setL1BaseFee" => self.set_l1_base_fee(&params),
+            "arb_simulateDeposit" => self.simulate_deposit(&params),
+            "eth_chainId" | "net_version" | "eth_blockNumber" if self.upstream.is_some() => {
+                self.forward(&request).await
+            }
+            "eth_chainId" => Ok(json!(format!("{:#x}", self.config().chain_id))),
+            "net_version" => Ok(json!(self.config().chain_id.to_string())),
+            "eth_blockNumber" => Ok(json!(format!("{:#x}", self.processor.mock_chain().block_number()))),
+            _ => self.forward(&request).await,
+        };
+
+        match result {
+            Ok(result) => json!({ "jsonrpc": "2.0", "id": id, "result": result }),
+            Err(error) => json!({ "jsonrpc": "2.0", "id": id, "error": error.to_json() }),
+        }
+    }
+
+    /// Handle `eth_sendRawTransaction`, or return `None` if it is not a 0x7e transaction
+    async fn send_raw_transaction(&self, params: &Value) -> Option<Result<Value, RpcError>> {
+        let raw = match params.get(0).and_then(Value::as_str) {
+            Some(raw) => raw,
+            None => return Some(Err(RpcError::new(INVALID_PARAMS, "Expected a raw transaction hex string"))),
+        };
+        let bytes = match hex::decode(raw.trim_start_matches("0x")) {
+            Ok(bytes) => bytes,
+            Err(e) => return Some(Err(RpcError::new(INVALID_PARAMS, format!("Invalid hex: {}", e)))),
+        };
+        match TxType::of(&bytes) {
+            Ok(TxType::Arbitrum) => {}
+            Ok(_) => return None,
+            Err(e) => return Some(Err(RpcError::new(INVALID_PARAMS, e.to_string()))),
+        }
+
+        if bytes.get(1) == Some(&TX_SUBTYPE_SUBMIT_RETRYABLE) {
+            return Some(
+                self.processor
+                    .process_retryable(&bytes)
+                    .map(|id| json!(format!("0x{}", hex::encode(id))))
+                    .map_err(|e| RpcError::new(SERVER_ERROR, e.to_string())),
+            );
+        }
+
+        let result = self.processor.process_transaction(&bytes).await;
+        Some(match result.transaction {
+            Some(tx) if result.success => Ok(json!(HexBytes(tx.hash()).to_string())),
+            _ => Err(RpcError::new(SERVER_ERROR, result.error)),
+        })
+    }
+
+    /// Handle `arb_getPrecompileResult(address, dataHex)` by calling the handler directly
+    fn get_precompile_result(&self, params: &Value) -> Result<Value, RpcError> {
+        let address = params
+            .get(0)
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected a precompile address"))?;
+        let address = Address::from_hex(address)
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid address: {}", e)))?;
+        let data = params
+            .get(1)
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected calldata hex"))?;
+        let data = hex::decode(data.trim_start_matches("0x"))
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid hex: {}", e)))?;
+
+        self.call_precompile(address, &data, &CallContext::default())
+    }
+
+    /// Handle `arb_setL1BaseFee(valueHex)`, changing the L1 base fee for later calls
+    ///
+    /// The smoothed estimate restarts from the new fee.
+    fn set_l1_base_fee(&self, params: &Value) -> Result<Value, RpcError> {
+        let value = params
+            .get(0)
+            .and_then(Value::as_str)
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected an L1 base fee hex quantity"))?;
+        let l1_base_fee = parse_u64_quantity(value, "L1 base fee")?;
+
+        let mut config = self.config.lock().unwrap();
+        let updated = ArbitrumConfig {
+            l1_base_fee,
+            ..config.clone()
+        };
+        updated
+            .validate()
+            .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid L1 base fee: {}", e)))?;
+        self.processor.set_config(&updated);
+        *config = updated;
+        self.registry.l1_base_fee_estimate().restart(l1_base_fee);
+        info!("L1 base fee set to {}", l1_base_fee);
+        Ok(json!(true))
+    }
+
+    /// Handle `arb_simulateDeposit({...})`, building the 0x7e deposit for an L1 message
+    ///
+    /// `l1TxHash`, `l1BlockNumber`, `l1LogIndex` and `target` are required; `value`
+    /// defaults to zero, `data` to empty, `refundAddress` to `target`, `l1Timestamp`
+    /// to now and `gas` to the deposit's intrinsic gas. L1 pricing comes from the
+    /// active configuration. Nothing is submitted.
+    fn simulate_deposit(&self, params: &Value) -> Result<Value, RpcError> {
+        let request = params
+            .get(0)
+            .filter(|request| request.is_object())
+            .ok_or_else(|| RpcError::new(INVALID_PARAMS, "Expected a deposit parameters object"))?;
+        let field = |name: &str| request.get(name).and_then(Value::as_str);
+        let required = |name: &str| {
+            field(name).ok_or_else(|| RpcError::new(INVALID_PARAMS, format!("Missing field: {}", name)))
+        };
+        let address = |name: &str, value: &str| {
+            Address::from_hex(value)
+                .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid {}: {}", name, e)))
+        };
+
+        let l1_tx_hash: [u8; 32] = parse_hex_data(required("l1TxHash")?, "l1TxHash")?
+            .try_into()
+            .map_err(|_| RpcError::new(INVALID_PARAMS, "Invalid l1TxHash: expected 32 bytes"))?;
+        let l1_block_number = parse_u64_quantity(required("l1BlockNumber")?, "l1BlockNumber")?;
+        let l1_log_index = parse_u64_quantity(required("l1LogIndex")?, "l1LogIndex")?;
+        let target = address("target", required("target")?)?;
+        let value = field("value").map(|v| parse_u256_quantity(v, "value")).transpose()?.unwrap_or_else(U256::zero);
+        let data = field("data").map(|d| parse_hex_data(d, "data")).transpose()?.unwrap_or_default();
+        let refund_address = field("refundAddress")
+            .map(|a| address("refundAddress", a))
+            .transpose()?
+            .unwrap_or(target);
+        let l1_timestamp = match field("l1Timestamp") {
+            Some(timestamp) => parse_u64_quantity(timestamp, "l1Timestamp")?,
+            None => std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .map(|elapsed| elapsed.as_secs())
+                .unwrap_or_default(),
+        };
+
+        let config = self.config();
+        let source_hash = Tx7eParser::new().generate_source_hash(&l1_tx_hash, l1_block_number, l1_log_index);
+        let l1_gas_used = config.calculate_l1_gas_cost(&data);
+        let l1_fee = U256::from_u64(config.calculate_l1_gas_cost_wei(&data));
+        let mut tx = Tx7eTransaction::new(
+            config.chain_id,
+            target,
+            value,
+            data,
+            0,
+            l1_block_number,
+            l1_timestamp,
+            U256::from_u64(config.l1_base_fee),
+            U256::from_u64(config.l1_base_fee),
+            l1_gas_used,
+            l1_fee,
+            refund_address,
+            source_hash,
+        );
+        tx.gas_limit = match field("gas") {
+            Some(gas) => parse_u64_quantity(gas, "gas")?,
+            None => tx.intrinsic_gas(),
+        };
+
+        let raw = tx.rlp_encode_typed();
+        Ok(json!({
+            "raw": HexBytes(&raw).to_string(),
+            "hash": HexBytes(tx.hash()).to_string(),
+            "intrinsicGas": format!("{:#x}", tx.intrinsic_gas()),
+            "transaction": tx.to_rpc_json(),
+        }))
+    }
+
+    /// Handle `eth_call` to a registered precompile, or return `None` to forward it
+    fn precompile_call(&self, params: &Value) -> Option<Result<Value, RpcError>> {
+        let call = params.get(0)?;
+        let address = Address::from_hex(call.get("to")?.as_str()?).ok()?;
+        if !self.registry.has_handler(&address) {
+            return None;
+        }
+
+        Some(call_context(call).and_then(|(data, context)| {
+            // eth_call is read-only, but the precompile stores are not snapshotted
+            if is_state_changing(&data) {
+                let name = selector_registry::name(data[..4].try_into().unwrap());
+                return Err(RpcError::reverted(&format!("{} changes state and cannot be run by eth_call", name)));
+            }
+            self.call_precompile(address, &data, &context)
+        }))
+    }
+
+    /// Handle `eth_estimateGas` for a registered precompile, or return `None` to forward it
+    ///
+    /// The estimate is the intrinsic gas of the call plus the precompile's gas
+    /// cost, including any `precompile_gas` override. The call is priced, not run.
+    fn precompile_estimate_gas(&self, params: &Value) -> Option<Result<Value, RpcError>> {
+        let call = params.get(0)?;
+        let address = Address::from_hex(call.get("to")?.as_str()?).ok()?;
+        if !self.registry.has_handler(&address) {
+            return None;
+        }
+
+        Some(call_context(call).map(|(data, _)| {
+            let gas = self.registry.gas_cost(address, &data, &self.config()).unwrap_or(0);
+            json!(format!("{:#x}", TX_BASE_GAS + calldata_gas(&data) + gas))
+        }))
+    }
+
+    /// Call the precompile at `address`, returning its output as hex
+    fn call_precompile(&self, address: Address, data: &[u8], context: &CallContext) -> Result<Value, RpcError> {
+        self.registry
+            .handle_call_with_context(address, data, context, &self.config.lock().unwrap())
+            .map(|output| json!(format!("0x{}", hex::encode(output))))
+            .map_err(|e| RpcError::reverted(&e.to_string()))
+    }
+
+    /// Forward a request to the upstream Anvil and return its result
+    async fn forward(&self, request: &Value) -> Result<Value, RpcError> {
+        let method = request.get("method").and_then(Value::as_str).unwrap_or_default();
+        let upstream = self.upstream.as_ref().ok_or_else(|| {
+            RpcError::new(METHOD_NOT_FOUND, format!("Method not found: {}", method))
+        })?;
+
+        let response: Value = self
+            .client
+            .post(upstream)
+            .json(request)
+            .send()
+            .await
+            .map_err(|e| RpcError::new(SERVER_ERROR, format!("Upstream request failed: {}", e)))?
+            .json()
+            .await
+            .map_err(|e| RpcError::new(SERVER_ERROR, format!("Invalid upstream response: {}", e)))?;
+
+        if let Some(error) = response.get("error") {
+            return Err(RpcError::new(
+                error.get("code").and_then(Value::as_i64).unwrap_or(SERVER_ERROR),
+                error.get("message").and_then(Value::as_str).unwrap_or_default(),
+            ));
+        }
+        Ok(response.get("result").cloned().unwrap_or(Value::Null))
+    }
+}
+
+/// Calldata and caller context of an `eth_call` call object
+///
+/// The call is treated as a top-level transaction from `from` (the zero address
+/// if absent) carrying `value`, whose calldata is the precompile input.
+fn call_context(call: &Value) -> Result<(Vec<u8>, CallContext), RpcError> {
+    let field = |name: &str| call.get(name).and_then(Value::as_str);
+    // Clients send calldata as either `input` or the older `data` field
+    let data = parse_hex_data(field("input").or_else(|| field("data")).unwrap_or("0x"), "input")?;
+    let caller = field("from")
+        .map(|from| {
+            Address::from_hex(from).map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid from: {}", e)))
+        })
+        .transpose()?
+        .unwrap_or(Address::ZERO);
+    let value = field("value").map(|v| parse_u256_quantity(v, "value")).transpose()?.unwrap_or_else(U256::zero);
+    let context = CallContext {
+        caller,
+        value,
+        ..CallContext::default()
+    }
+    .with_tx_calldata(&data);
+    Ok((data, context))
+}
+
+/// Parse a `0x`-prefixed hex quantity parameter into a `u64`
+fn parse_u64_quantity(value: &str, name: &str) -> Result<u64, RpcError> {
+    u64::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid {} '{}': {}", name, value, e)))
+}
+
+/// Parse a `0x`-prefixed hex quantity parameter into a `U256`
+fn parse_u256_quantity(value: &str, name: &str) -> Result<U256, RpcError> {
+    let digits = value.trim_start_matches("0x");
+    let padded = if digits.len() % 2 == 1 { format!("0{}", digits) } else { digits.to_string() };
+    let bytes = parse_hex_data(&padded, name)?;
+    if bytes.len() > 32 {
+        return Err(RpcError::new(INVALID_PARAMS, format!("Invalid {} '{}': exceeds 256 bits", name, value)));
+    }
+    Ok(U256::from_big_endian(&bytes))
+}
+
+/// Parse a `0x`-prefixed hex data parameter
+fn parse_hex_data(value: &str, name: &str) -> Result<Vec<u8>, RpcError> {
+    hex::decode(value.trim_start_matches("0x"))
+        .map_err(|e| RpcError::new(INVALID_PARAMS, format!("Invalid {}: {}", name, e)))
+}
+
+/// Fetch the base fee of a block from a JSON-RPC endpoint via `eth_getBlockByNumber`
+///
+/// `block` of `None` queries the latest block.
+pub async fn fetch_block_base_fee(url: &str, block: Option<u64>) -> Result<u64> {
+    let tag = block.map_or_else(|| "latest".to_string(), |number| format!("{:#x}", number));
+    let request = json!({
+        "jsonrpc": "2.0",
+        "id": 1,
+        "method": "eth_getBlockByNumber",
+        "params": [tag, false],
+    });
+    let response: Value = http_client(UPSTREAM_REQUEST_TIMEOUT)
+        .post(url)
+        .json(&request)
+        .send()
+        .await?
+        .json()
+        .await?;
+
+    if let Some(error) = response.get("error") {
+        return Err(anyhow!("eth_getBlockByNumber failed: {}", error));
+    }
+    let base_fee = response["result"]["baseFeePerGas"]
+        .as_str()
+        .ok_or_else(|| anyhow!("Block {} has no baseFeePerGas", tag))?;
+    u64::from_str_radix(base_fee.trim_start_matches("0x"), 16)
+        .map_err(|e| anyhow!("Invalid baseFeePerGas '{}': {}", base_fee, e))
+}
+
+/// HTTP client with `UPSTREAM_CONNECT_TIMEOUT` and the given request timeout
+fn http_client(request_timeout: Duration) -> reqwest::Client {
+    reqwest::Client::builder()
+        .connect_timeout(UPSTREAM_CONNECT_TIMEOUT)
+        .timeout(request_timeout)
+        .build()
+        .expect("HTTP client with timeouts")
+}
+
+/// Build the HTTP router for the RPC server
+pub fn router(state: Arc<RpcState>) -> Router {
+    let mut router = Router::new().route("/", post(handle_http));
+    if state.serve_metrics {
+        router = router.route("/metrics", get(handle_metrics));
+    }
+    router.with_state(state)
+}
+
+async fn handle_metrics(State(state): State<Arc<RpcState>>) -> String {
+    state.metrics().to_prometheus()
+}
+
+async fn handle_http(State(state): State<Arc<RpcState>>, Json(body): Json<Value>) -> Json<Value> {
+    match body {
+        Value::Array(requests) => {
+            let mut responses = Vec::with_capacity(requests.len());
+            for request in requests {
+                responses.push(state.handle_request(request).await);
+            }
+            Json(Value::Array(responses))
+        }
+        request => Json(state.handle_request(request).await),
+    }
+}
+
+/// Serve JSON-RPC on an already bound listener until the server stops
+pub async fn serve(listener: TcpListener, state: Arc<RpcState>) -> Result<()> {
+    Server::from_listener(listener, state).run(std::future::pending()).await
+}
+
+/// An error binding the RPC server
+#[derive(Debug, thiserror::Error)]
+pub enum ServerError {
+    #[error("Address {address} is already in use; stop the process listening there or choose another --port")]
+    AddrInUse { address: String },
+    #[error("Failed to bind {address}: {source}")]
+    Bind { address: String, source: std::io::Error },
+}
+
+/// JSON-RPC server bound to a listening socket
+pub struct Server {
+    listener: TcpListener,
+    state: Arc<RpcState>,
+}
+
+impl Server {
+    /// Bind `host:port`, reporting a port that is already taken as `ServerError::AddrInUse`
+    pub async fn bind(host: &str, port: u16, state: Arc<RpcState>) -> Result<Self, ServerError> {
+        let address = format!("{}:{}", host, port);
+        match TcpListener::bind((host, port)).await {
+            Ok(listener) => Ok(Self::from_listener(listener, state)),
+            Err(e) if e.kind() == ErrorKind::AddrInUse => Err(ServerError::AddrInUse { address }),
+            Err(source) => Err(ServerError::Bind { address, source }),
+        }
+    }
+
+    /// Serve on an already bound listener
+    pub fn from_listener(listener: TcpListener, state: Arc<RpcState>) -> Self {
+        Self { listener, state }
+    }
+
+    /// Address the server is listening on
+    pub fn local_addr(&self) -> std::io::Result<SocketAddr> {
+        self.listener.local_addr()
+    }
+
+    /// Serve requests until `shutdown` completes, then let in-flight requests finish
+    pub async fn run(self, shutdown: impl Future<Output = ()> + Send + 'static) -> Result<()> {
+        info!("JSON-RPC server listening on {}", self.listener.local_addr()?);
+        axum::serve(self.listener, router(self.state))
+            .with_graceful_shutdown(shutdown)
+            .await?;
+        info!("JSON-RPC server stopped");
+        Ok(())
+    }
+}
+
+/// Wait for Ctrl-C or, on Unix, SIGTERM
+pub async fn shutdown_signal() {
+    let ctrl_c = async {
+        if tokio::signal::ctrl_c().await.is_err() {
+            std::future::pending::<()>().await;
+        }
+    };
+
+    #[cfg(unix)]
+    let terminate = async {
+        match tokio::signal::unix::signal(tokio::signal::unix::SignalKind::terminate()) {
+            Ok(mut signal) => {
+                signal.recv().await;
+            }
+            Err(_) => std::future::pending::<()>().await,
+        }
+    };
+    #[cfg(not(unix))]
+    let terminate = std::future::pending::<()>();
+
+    tokio::select! {
+        _ = ctrl_c => info!("Received Ctrl-C, shutting down"),
+        _ = terminate => info!("Received SIGTERM, shutting down"),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::owner::ArbOwnerHandler;
+    use crate::precompiles::{selector, selectors, ArbSysHandler, WithdrawalStore};
+    use crate::tx7e::Tx7eTransaction;
+
+    async fn spawn_server(state: RpcState) -> String {
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, Arc::new(state)));
+        url
+    }
+
+    async fn rpc_call(url: &str, method: &str, params: Value) -> Value {
+        reqwest::Client::new()
+            .post(url)
+            .json(&json!({ "jsonrpc": "2.0", "id": 1, "method": method, "params": params }))
+            .send()
+            .await
+            .unwrap()
+            .json()
+            .await
+            .unwrap()
+    }
+
+    fn raw_deposit() -> Vec<u8> {
+        let tx = Tx7eTransaction::new(
+            42161,
+            Address::from_hex("0x1234567890123456789012345678901234567890").unwrap(),
+            U256::from_u64(1_000_000_000_000_000_000),
+            vec![0x60, 0x2b, 0x57, 0xfd],
+            100000,
+            12345,
+            1640995200,
+            U256::from_u64(20_000_000_000),
+            U256::from_u64(25_000_000_000),
+            50000,
+            U256::from_u64(1_000_000_000_000_000),
+            Address::from_hex("0xabcdefabcdefabcdefabcdefabcdefabcdefabcd").unwrap(),
+            [1u8; 32],
+        );
+        tx.rlp_encode_typed()
+    }
+
+    #[tokio::test]
+    async fn test_send_raw_tx7e_returns_hash() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+        let raw = format!("0x{}", hex::encode(raw_deposit()));
+
+        let response = rpc_call(&url, "eth_sendRawTransaction", json!([raw])).await;
+        let hash = response["result"].as_str().unwrap();
+        let bytes = hex::decode(hash.trim_start_matches("0x")).unwrap();
+        assert_eq!(bytes.len(), 32);
+        assert_eq!(response["id"], 1);
+    }
+
+    #[tokio::test]
+    async fn test_metrics_endpoint() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default()).with_metrics_endpoint()).await;
+        let raw = format!("0x{}", hex::encode(raw_deposit()));
+        rpc_call(&url, "eth_sendRawTransaction", json!([raw])).await;
+
+        let text = reqwest::get(format!("{}/metrics", url)).await.unwrap().text().await.unwrap();
+        assert!(text.contains("anvil_arbitrum_tx7e_processed_total{result=\"success\"} 1\n"));
+
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+        let status = reqwest::get(format!("{}/metrics", url)).await.unwrap().status();
+        assert_eq!(status, reqwest::StatusCode::NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_bind_port_in_use() {
+        let state = Arc::new(RpcState::new(ArbitrumConfig::default()));
+        let first = Server::bind("127.0.0.1", 0, state.clone()).await.unwrap();
+        let port = first.local_addr().unwrap().port();
+
+        match Server::bind("127.0.0.1", port, state).await {
+            Err(ServerError::AddrInUse { address }) => assert_eq!(address, format!("127.0.0.1:{}", port)),
+            Err(e) => panic!("expected AddrInUse, got {}", e),
+            Ok(_) => panic!("expected AddrInUse, got a second listener"),
+        }
+    }
+
+    #[tokio::test]
+    async fn test_run_stops_on_shutdown() {
+        let state = Arc::new(RpcState::new(ArbitrumConfig::default()));
+        let server = Server::bind("127.0.0.1", 0, state).await.unwrap();
+        let url = format!("http://{}", server.local_addr().unwrap());
+        let (stop, stopped) = tokio::sync::oneshot::channel::<()>();
+        let handle = tokio::spawn(server.run(async {
+            let _ = stopped.await;
+        }));
+
+        let response = rpc_call(&url, "arb_setL1BaseFee", json!(["0x1"])).await;
+        assert!(response.get("result").is_some());
+
+        stop.send(()).unwrap();
+        handle.await.unwrap().unwrap();
+        assert!(reqwest::get(&url).await.is_err());
+    }
+
+    #[tokio::test]
+    async fn test_invalid_tx7e_returns_error() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+
+        let response = rpc_call(&url, "eth_sendRawTransaction", json!(["0x7e01"])).await;
+        assert_eq!(response["error"]["code"], SERVER_ERROR);
+    }
+
+    #[tokio::test]
+    async fn test_get_precompile_result_arb_chain_id() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+
+        let response = rpc_call(
+            &url,
+            "arb_getPrecompileResult",
+            json!(["0x0000000000000000000000000000000000000064", "0xd127f54a"]),
+        )
+        .await;
+        let output = hex::decode(response["result"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        assert_eq!(U256::from_big_endian(&output), U256::from_u64(42161));
+    }
+
+    #[tokio::test]
+    async fn test_get_precompile_result_revert() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+
+        let response = rpc_call(
+            &url,
+            "arb_getPrecompileResult",
+            json!(["0x0000000000000000000000000000000000000064", "0xdeadbeef"]),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], EXECUTION_REVERTED);
+        let data = response["error"]["data"].as_str().unwrap();
+        assert!(data.starts_with("0x08c379a0"));
+    }
+
+    #[tokio::test]
+    async fn test_eth_call_routed_to_precompile() {
+        let config = ArbitrumConfig::default();
+        let url = spawn_server(RpcState::new(config.clone())).await;
+
+        let response = rpc_call(
+            &url,
+            "eth_call",
+            json!([
+                { "to": "0x000000000000000000000000000000000000006c", "data": "0x41b247a8" },
+                "latest"
+            ]),
+        )
+        .await;
+        let output = hex::decode(response["result"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        assert_eq!(output.len(), 6 * 32);
+
+        let words: Vec<U256> = output.chunks(32).map(U256::from_big_endian).collect();
+        let l1_byte_price = config.l1_base_fee * config.gas_price_components.l1_calldata_cost;
+        assert_eq!(words[2], U256::from_u64(l1_byte_price));
+        assert_eq!(words[3], U256::from_u64(config.gas_price_components.l2_base_fee));
+    }
+
+    #[tokio::test]
+    async fn test_eth_call_passes_call_object_as_context() {
+        let config = ArbitrumConfig::default();
+        let url = spawn_server(RpcState::new(config.clone())).await;
+
+        // getCurrentTxL1GasFees prices the eth_call input as the transaction calldata
+        let input = hex::decode(selectors::GET_CURRENT_TX_L1_GAS_FEES).unwrap();
+        let response = rpc_call(
+            &url,
+            "eth_call",
+            json!([{
+                "from": "0x1234567890123456789012345678901234567890",
+                "to": "0x000000000000000000000000000000000000006c",
+                "input": HexBytes(&input).to_string(),
+                "value": "0x0",
+            }, "latest"]),
+        )
+        .await;
+        let output = hex::decode(response["result"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        let fee = U256::from_u64(config.calculate_l1_gas_cost_wei(&input));
+        assert_ne!(fee, U256::zero());
+        assert_eq!(U256::from_big_endian(&output), fee);
+
+        let response = rpc_call(
+            &url,
+            "eth_call",
+            json!([{ "from": "0x12", "to": "0x000000000000000000000000000000000000006c", "input": "0x" }, "latest"]),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_eth_call_rejects_state_changing_functions() {
+        let withdrawals = WithdrawalStore::new();
+        let mut registry = PrecompileRegistry::new();
+        registry.register(Box::new(ArbSysHandler::with_withdrawals(withdrawals.clone())));
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default()).with_registry(registry)).await;
+
+        let mut input = hex::decode(selectors::WITHDRAW_ETH).unwrap();
+        input.extend_from_slice(&[0u8; 32]);
+        let response = rpc_call(
+            &url,
+            "eth_call",
+            json!([{ "to": "0x0000000000000000000000000000000000000064", "input": HexBytes(&input).to_string() }, "latest"]),
+        )
+        .await;
+        assert_eq!(
+            response["error"]["message"],
+            "execution reverted: withdrawEth(address) changes state and cannot be run by eth_call"
+        );
+        assert!(withdrawals.is_empty());
+        assert!(is_state_changing(&selector("becomeChainOwner()")));
+        assert!(!is_state_changing(&hex::decode(selectors::ARB_BLOCK_NUMBER).unwrap()));
+    }
+
+    #[tokio::test]
+    async fn test_eth_estimate_gas_includes_precompile_cost() {
+        let mut config = ArbitrumConfig::default();
+        config.precompile_gas.insert("arbsys".to_string(), 700);
+        let url = spawn_server(RpcState::new(config)).await;
+
+        let input = hex::decode(selectors::ARB_CHAIN_ID).unwrap();
+        let response = rpc_call(
+            &url,
+            "eth_estimateGas",
+            json!([{ "to": "0x0000000000000000000000000000000000000064", "input": HexBytes(&input).to_string() }]),
+        )
+        .await;
+        assert_eq!(response["result"], format!("{:#x}", TX_BASE_GAS + calldata_gas(&input) + 700));
+
+        // Other addresses go upstream, which is absent here
+        let response = rpc_call(
+            &url,
+            "eth_estimateGas",
+            json!([{ "to": "0x1234567890123456789012345678901234567890" }]),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_eth_call_to_other_address_forwarded() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+
+        let response = rpc_call(
+            &url,
+            "eth_call",
+            json!([{ "to": "0x1234567890123456789012345678901234567890", "data": "0x" }, "latest"]),
+        )
+        .await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_set_l1_base_fee_updates_arb_gas_info() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+        let l1_base_fee_estimate = |response: Value| {
+            let output = hex::decode(response["result"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+            U256::from_big_endian(&output)
+        };
+        let params = json!(["0x000000000000000000000000000000000000006c", "0xf5d6ded7"]);
+
+        let response = rpc_call(&url, "arb_getPrecompileResult", params.clone()).await;
+        assert_eq!(l1_base_fee_estimate(response), U256::from_u64(20_000_000_000));
+
+        let response = rpc_call(&url, "arb_setL1BaseFee", json!(["0x77359400"])).await;
+        assert_eq!(response["result"], true);
+        let response = rpc_call(&url, "arb_getPrecompileResult", params).await;
+        assert_eq!(l1_base_fee_estimate(response), U256::from_u64(2_000_000_000));
+
+        // Invalid values are rejected and leave the fee unchanged
+        let response = rpc_call(&url, "arb_setL1BaseFee", json!(["0x0"])).await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+        let response = rpc_call(&url, "arb_setL1BaseFee", json!(["0xzz"])).await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[tokio::test]
+    async fn test_reloaded_config_reaches_processor() {
+        let state = RpcState::new(ArbitrumConfig::default());
+        let mut config = state.config();
+        config.gas_price_components.l2_base_fee = 250_000_000;
+        config.l2_block_gas_limit = 50_000;
+        state.set_config(config.clone());
+
+        assert_eq!(state.processor().config(), config);
+        assert_eq!(state.processor().parser().l2_base_fee(), 250_000_000);
+
+        // The deposit asks for 100_000 gas, over the reloaded block gas limit
+        let result = state.processor().process_transaction(&raw_deposit()).await;
+        assert!(!result.success);
+        assert!(result.error.contains("gas limit"), "{}", result.error);
+    }
+
+    #[tokio::test]
+    async fn test_new_state_shares_registry_stores() {
+        let mut config = ArbitrumConfig {
+            storage_gas_available: 1_000_000,
+            ..ArbitrumConfig::default()
+        };
+        config.enable_only_precompiles(&["arbsys", "arbgasinfo"]).unwrap();
+        let state = RpcState::new(config);
+        let arb_sys = Address::from_hex("0x0000000000000000000000000000000000000064").unwrap();
+        let arb_gas_info = Address::from_hex("0x000000000000000000000000000000000000006c").unwrap();
+        let call = |address: Address, function: &str| {
+            let input = hex::decode(function).unwrap();
+            U256::from_big_endian(&state.registry().handle_call(address, &input, &state.config()).unwrap())
+        };
+        let arb_retryable_tx = Address::from_hex("0x000000000000000000000000000000000000006e").unwrap();
+        assert!(!state.registry().has_handler(&arb_retryable_tx));
+        assert_eq!(call(arb_sys, selectors::GET_STORAGE_GAS_AVAILABLE), U256::from_u64(1_000_000));
+
+        let result = state.processor().process_transaction(&raw_deposit()).await;
+        assert!(result.success, "{}", result.error);
+
+        // The deposit is visible through the precompiles without any extra wiring
+        let block_number = state.processor().mock_chain().block_number();
+        assert_eq!(call(arb_sys, selectors::ARB_BLOCK_NUMBER), U256::from_u64(block_number));
+        assert_ne!(call(arb_gas_info, selectors::GET_L1_FEES_AVAILABLE), U256::zero());
+        assert!(call(arb_sys, selectors::GET_STORAGE_GAS_AVAILABLE) < U256::from_u64(1_000_000));
+        let target = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        assert_eq!(
+            state.registry().state_backend().balance(&target),
+            U256::from_u64(1_000_000_000_000_000_000)
+        );
+    }
+
+    #[tokio::test]
+    async fn test_owner_set_l2_base_fee_reaches_arb_gas_info() {
+        let owner = Address::from_hex("0x70997970c51812dc3a010c7d01b50e0d17dc79c8").unwrap();
+        let mut registry =
+            PrecompileRegistry::from_config(&ArbitrumConfig::default(), GasPool::default(), MockChain::default());
+        registry.chain_owners().add_owner(owner);
+        registry.register(Box::new(ArbOwnerHandler::new(registry.chain_owners().clone())));
+        let state = RpcState::new(ArbitrumConfig::default()).with_registry(registry);
+
+        let arb_owner = Address::from_hex("0x0000000000000000000000000000000000000070").unwrap();
+        let mut input = selector("setL2BaseFee(uint256)").to_vec();
+        input.extend_from_slice(&U256::from_u64(250_000_000).to_big_endian());
+        let context = CallContext {
+            caller: owner,
+            ..CallContext::default()
+        };
+        state
+            .registry()
+            .handle_call_with_context(arb_owner, &input, &context, &state.config())
+            .unwrap();
+
+        let arb_gas_info = Address::from_hex("0x000000000000000000000000000000000000006c").unwrap();
+        let input = hex::decode(selectors::GET_PRICES_IN_WEI).unwrap();
+        let output = state.registry().handle_call(arb_gas_info, &input, &state.config()).unwrap();
+        let words: Vec<U256> = output.chunks(32).map(U256::from_big_endian).collect();
+        assert_eq!(words[0], U256::from_u64(250_000_000));
+        assert_eq!(words[3], U256::from_u64(250_000_000));
+
+        // Deposits are priced with it too, and a reload keeps it
+        assert_eq!(state.processor().parser().l2_base_fee(), 250_000_000);
+        state.set_config(ArbitrumConfig::default());
+        assert_eq!(state.config().gas_price_components.l2_base_fee, 250_000_000);
+        assert_eq!(state.processor().config().gas_price_components.l2_base_fee, 250_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_deposits_smooth_l1_base_fee_estimate() {
+        let state = Arc::new(RpcState::new(ArbitrumConfig::default()));
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let url = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(serve(listener, state.clone()));
+
+        rpc_call(&url, "arb_setL1BaseFee", json!(["0x77359400"])).await;
+        let estimate = state.registry().l1_base_fee_estimate();
+        assert_eq!(estimate.current(state.config().l1_base_fee), 2_000_000_000);
+
+        // The deposit was submitted at 20 gwei, moving the estimate a tenth of the way there
+        let raw = format!("0x{}", hex::encode(raw_deposit()));
+        rpc_call(&url, "eth_sendRawTransaction", json!([raw])).await;
+        let params = json!(["0x000000000000000000000000000000000000006c", "0xf5d6ded7"]);
+        let response = rpc_call(&url, "arb_getPrecompileResult", params).await;
+        let output = hex::decode(response["result"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        assert_eq!(U256::from_big_endian(&output), U256::from_u64(3_800_000_000));
+        assert_eq!(state.config().l1_base_fee, 2_000_000_000);
+
+        // Reloading the configuration keeps the estimate
+        state.set_config(state.config());
+        assert_eq!(estimate.current(state.config().l1_base_fee), 3_800_000_000);
+
+        // Setting the fee restarts the estimate
+        rpc_call(&url, "arb_setL1BaseFee", json!(["0x77359400"])).await;
+        assert_eq!(estimate.current(state.config().l1_base_fee), 2_000_000_000);
+    }
+
+    #[tokio::test]
+    async fn test_simulate_deposit_round_trips() {
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+        let request = json!({
+            "l1TxHash": format!("0x{}", "ab".repeat(32)),
+            "l1BlockNumber": "0x3039",
+            "l1LogIndex": "0x2",
+            "target": "0x1234567890123456789012345678901234567890",
+            "value": "0xde0b6b3a7640000",
+            "data": "0x602b57fd",
+            "refundAddress": "0xabcdefabcdefabcdefabcdefabcdefabcdefabcd",
+            "l1Timestamp": "0x61cf9980",
+        });
+
+        let response = rpc_call(&url, "arb_simulateDeposit", json!([request])).await;
+        let result = &response["result"];
+        let raw = hex::decode(result["raw"].as_str().unwrap().trim_start_matches("0x")).unwrap();
+        let tx = Tx7eParser::new().parse(&raw).unwrap();
+
+        assert_eq!(tx.target, Address::from_hex("0x1234567890123456789012345678901234567890").unwrap());
+        assert_eq!(tx.value, U256::from_u64(1_000_000_000_000_000_000));
+        assert_eq!(tx.data, vec![0x60, 0x2b, 0x57, 0xfd]);
+        assert_eq!(tx.l1_timestamp, 1640995200);
+        assert_eq!(tx.source_hash, Tx7eParser::new().generate_source_hash(&[0xab; 32], 12345, 2));
+        assert_eq!(tx.gas_limit, tx.intrinsic_gas());
+        assert!(tx.validate().is_empty());
+        assert_eq!(result["hash"], HexBytes(tx.hash()).to_string());
+        assert_eq!(result["intrinsicGas"], format!("{:#x}", tx.intrinsic_gas()));
+        assert_eq!(result["transaction"], tx.to_rpc_json());
+
+        let response = rpc_call(&url, "arb_simulateDeposit", json!([{ "target": "0x00" }])).await;
+        assert_eq!(response["error"]["code"], INVALID_PARAMS);
+    }
+
+    #[test]
+    fn test_encode_revert_reason() {
+        let data = encode_revert_reason("nope");
+        assert_eq!(data.len(), 4 + 32 * 3);
+        assert_eq!(&data[..4], &ERROR_STRING_SELECTOR);
+        assert_eq!(U256::from_big_endian(&data[36..68]), U256::from_u64(4));
+        assert_eq!(&data[68..72], b"nope");
+    }
+
+    #[tokio::test]
+    async fn test_chain_probes_answered_without_upstream() {
+        let nova = spawn_server(RpcState::new(ArbitrumConfig::arbitrum_nova())).await;
+        let response = rpc_call(&nova, "eth_chainId", json!([])).await;
+        assert_eq!(response["result"], "0xa4ba");
+        let response = rpc_call(&nova, "net_version", json!([])).await;
+        assert_eq!(response["result"], "42170");
+
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+        let block_number = |response: Value| {
+            u64::from_str_radix(response["result"].as_str().unwrap().trim_start_matches("0x"), 16).unwrap()
+        };
+        let before = block_number(rpc_call(&url, "eth_blockNumber", json!([])).await);
+        assert_eq!(block_number(rpc_call(&url, "eth_blockNumber", json!([])).await), before);
+
+        // Each processed deposit mines a block on the mock chain
+        let raw = format!("0x{}", hex::encode(raw_deposit()));
+        let response = rpc_call(&url, "eth_sendRawTransaction", json!([raw])).await;
+        assert!(response["result"].is_string(), "{}", response);
+        assert_eq!(block_number(rpc_call(&url, "eth_blockNumber", json!([])).await), before + 1);
+    }
+
+    #[tokio::test]
+    async fn test_other_methods_forwarded_to_upstream() {
+        let upstream = spawn_server(RpcState::new(ArbitrumConfig::default())).await;
+        let url = spawn_server(RpcState::new(ArbitrumConfig::default()).with_upstream(upstream)).await;
+
+        // The upstream doesn't know this method either, so its error is passed through
+        let response = rpc_call(&url, "eth_accounts", json!([])).await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+
+        // Non-0x7e raw transactions go upstream rather than to the processor
+        let response = rpc_call(&url, "eth_sendRawTransaction", json!(["0x02"])).await;
+        assert_eq!(response["error"]["code"], METHOD_NOT_FOUND);
+    }
+
+    #[tokio::test]
+    async fn test_hung_upstream_times_out() {
+        // An upstream that accepts connections but never answers
+        let listener = TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let upstream = format!("http://{}", listener.local_addr().unwrap());
+        tokio::spawn(async move {
+            let mut connections = Vec::new();
+            while let Ok((stream, _)) = listener.accept().await {
+                connections.push(stream);
+            }
+        });
+        let state = RpcState::new(ArbitrumConfig::default())
+            .with_upstream(upstream)
+            .with_upstream_timeout(Duration::from_millis(200));
+        let url = spawn_server(state).await;
+
+        let started = std::time::Instant::now();
+        let response = rpc_call(&url, "eth_accounts", json!([])).await;
+        assert_eq!(response["error"]["code"], SERVER_ERROR);
+        assert!(response["error"]["message"].as_str().unwrap().starts_with("Upstream request failed"));
+        assert!(started.elapsed() < Duration::from_secs(5));
+    }
+}
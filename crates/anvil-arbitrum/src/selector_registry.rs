@@ -0,0 +1,143 @@
+//! Names of well-known Arbitrum precompile selectors, for error messages and logs
+
+use crate::precompiles::selector;
+use std::collections::HashMap;
+use std::sync::OnceLock;
+
+/// Name reported for selectors that are not in `KNOWN_SIGNATURES`
+pub const UNKNOWN: &str = "unknown";
+
+/// Signatures of Nitro's precompile functions, whether or not a handler implements them
+pub const KNOWN_SIGNATURES: &[&str] = &[
+    // ArbSys (0x64)
+    "arbBlockNumber()",
+    "arbBlockHash(uint256)",
+    "arbChainID()",
+    "arbOSVersion()",
+    "getStorageGasAvailable()",
+    "isTopLevelCall()",
+    "mapL1SenderContractAddressToL2Alias(address,address)",
+    "wasMyCallersAddressAliased()",
+    "myCallersAddressWithoutAliasing()",
+    "sendTxToL1(address,bytes)",
+    "sendMerkleTreeState()",
+    "withdrawEth(address)",
+    // ArbGasInfo (0x6C)
+    "getPricesInWeiWithAggregator(address)",
+    "getPricesInWei()",
+    "getPricesInArbGasWithAggregator(address)",
+    "getPricesInArbGas()",
+    "getGasAccountingParams()",
+    "getMinimumGasPrice()",
+    "getL1BaseFeeEstimate()",
+    "getL1BaseFeeEstimateInertia()",
+    "getL1RewardRate()",
+    "getL1RewardRecipient()",
+    "getL1GasPriceEstimate()",
+    "getCurrentTxL1GasFees()",
+    "getGasBacklog()",
+    "getPricingInertia()",
+    "getGasBacklogTolerance()",
+    "getL1PricingSurplus()",
+    "getPerBatchGasCharge()",
+    "getAmortizedCostCapBips()",
+    "getL1FeesAvailable()",
+    "getL1PricingEquilibrationUnits()",
+    "getLastL1PricingUpdateTime()",
+    "getL1PricingFundsDueForRewards()",
+    "getL1PricingUnitsSinceUpdate()",
+    "getLastL1PricingSurplus()",
+    "getL2BaseFeeEstimate()",
+    "getL1BlobBaseFeeEstimate()",
+    // ArbRetryableTx (0x6E)
+    "redeem(bytes32)",
+    "getLifetime()",
+    "getTimeout(bytes32)",
+    "keepalive(bytes32)",
+    "getBeneficiary(bytes32)",
+    "cancel(bytes32)",
+    "getCurrentRedeemer()",
+    "submitRetryable(bytes32,uint256,uint256,uint256,uint256,uint64,uint256,address,address,address,bytes)",
+    // ArbOwnerPublic (0x6B)
+    "isChainOwner(address)",
+    "getAllChainOwners()",
+    "getNetworkFeeAccount()",
+    "getInfraFeeAccount()",
+    "getBrotliCompressionLevel()",
+    "getScheduledUpgrade()",
+    // ArbOwner (0x70)
+    "addChainOwner(address)",
+    "removeChainOwner(address)",
+    "setL1BaseFeeEstimateInertia(uint64)",
+    "setL2BaseFee(uint256)",
+    "setMinimumL2BaseFee(uint256)",
+    "setSpeedLimit(uint64)",
+    "setMaxTxGasLimit(uint64)",
+    "setL2GasPricingInertia(uint64)",
+    "setL2GasBacklogTolerance(uint64)",
+    "setNetworkFeeAccount(address)",
+    "setInfraFeeAccount(address)",
+    "setL1PricePerUnit(uint256)",
+    "setPerBatchGasCharge(int64)",
+    "setAmortizedCostCapBips(uint64)",
+    "scheduleArbOSUpgrade(uint64,uint64)",
+    // ArbDebug (0xFF)
+    "becomeChainOwner()",
+    "events(bool,bytes32)",
+    // ArbAddressTable (0x66)
+    "addressExists(address)",
+    "compress(address)",
+    "decompress(bytes,uint256)",
+    "lookup(address)",
+    "lookupIndex(uint256)",
+    "register(address)",
+    "size()",
+    // NodeInterface (0xC8)
+    "estimateRetryableTicket(address,uint256,address,uint256,address,address,bytes)",
+    "gasEstimateComponents(address,bool,bytes)",
+    "gasEstimateL1Component(address,bool,bytes)",
+    "nitroGenesisBlock()",
+];
+
+/// Signature of the known function with this selector
+pub fn lookup(selector_bytes: [u8; 4]) -> Option<&'static str> {
+    static TABLE: OnceLock<HashMap<[u8; 4], &'static str>> = OnceLock::new();
+    TABLE
+        .get_or_init(|| KNOWN_SIGNATURES.iter().map(|signature| (selector(signature), *signature)).collect())
+        .get(&selector_bytes)
+        .copied()
+}
+
+/// Signature of the known function with this selector, or `UNKNOWN`
+pub fn name(selector_bytes: [u8; 4]) -> &'static str {
+    lookup(selector_bytes).unwrap_or(UNKNOWN)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::arbitrum::ArbitrumConfig;
+    use crate::precompiles::{ArbSysHandler, PrecompileHandler};
+
+    #[test]
+    fn test_unknown_selector_error_names_known_function() {
+        let handler = ArbSysHandler::new();
+        let config = ArbitrumConfig::default();
+
+        // arbBlockHash(uint256) is a Nitro function this handler does not implement
+        let mut input = selector("arbBlockHash(uint256)").to_vec();
+        input.extend_from_slice(&[0u8; 32]);
+        let err = handler.handle_call(&input, &config).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            format!(
+                "Unknown function selector: 0x{} (arbBlockHash(uint256))",
+                hex::encode(selector("arbBlockHash(uint256)"))
+            )
+        );
+
+        let err = handler.handle_call(&[0xde, 0xad, 0xbe, 0xef], &config).unwrap_err();
+        assert_eq!(err.to_string(), "Unknown function selector: 0xdeadbeef (unknown)");
+        assert_eq!(lookup(selector("getL1FeesAvailable()")), Some("getL1FeesAvailable()"));
+    }
+}
@@ -0,0 +1,335 @@
+//! Account state shared by the processor and precompiles: balances, nonces and code
+
+use crate::precompiles::{Address, U256};
+#[cfg(feature = "alloy")]
+use crate::primitives::U256Ext;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+
+/// Backend for the account state the mock L2 tracks
+///
+/// Deposits credit balances and consume nonces through it, and `ArbSys`
+/// withdrawals debit it. Implement it to back the state with something other
+/// than `InMemoryState`, e.g. a forked node.
+pub trait StateBackend: Send + Sync {
+    /// Balance of the account in wei
+    fn balance(&self, address: &Address) -> U256;
+
+    /// Replace the balance of the account
+    fn set_balance(&self, address: &Address, balance: U256);
+
+    /// Next nonce of the account
+    fn nonce(&self, address: &Address) -> u64;
+
+    /// Advance the nonce, returning the value it had before
+    fn increment_nonce(&self, address: &Address) -> u64;
+
+    /// Deployed code of the account, empty for externally owned accounts
+    fn code(&self, address: &Address) -> Vec<u8>;
+
+    /// Add `amount` to the balance, saturating at `U256::MAX`
+    ///
+    /// The default reads and then writes the balance. Backends shared between
+    /// threads should override it with an atomic update.
+    fn credit(&self, address: &Address, amount: U256) {
+        self.set_balance(address, self.balance(address).saturating_add(amount));
+    }
+
+    /// Subtract `amount` from the balance, failing if the balance is smaller
+    ///
+    /// Like `credit`, the default is not atomic.
+    fn debit(&self, address: &Address, amount: U256) -> Result<(), StateError> {
+        let balance = self.balance(address);
+        let remaining = balance.checked_sub(amount).ok_or(StateError::InsufficientBalance {
+            address: *address,
+            balance,
+            amount,
+        })?;
+        self.set_balance(address, remaining);
+        Ok(())
+    }
+}
+
+/// A state backend shared between the processor and precompile handlers
+pub type SharedState = Arc<dyn StateBackend>;
+
+/// Errors applying a change through `StateJournal`
+#[derive(Debug, Clone, thiserror::Error, PartialEq, Eq)]
+pub enum StateError {
+    #[error("Balance of {address} would overflow")]
+    BalanceOverflow { address: Address },
+    #[error("Insufficient balance for {address}: have {balance}, need {amount}")]
+    InsufficientBalance { address: Address, balance: U256, amount: U256 },
+    #[error("Nonce of {address} is exhausted")]
+    NonceOverflow { address: Address },
+}
+
+#[derive(Debug, Clone, Copy)]
+enum Change {
+    Credit(Address, U256),
+    IncrementNonce(Address),
+}
+
+/// Changes to a state backend that are kept or undone as a unit
+///
+/// Each change is checked when it is made but only staged. `commit` applies
+/// them all as credits and nonce increments, so changes made to the same
+/// accounts in the meantime (e.g. an `ArbSys` withdrawal) are kept. Dropping
+/// the journal or calling `rollback` discards them.
+pub struct StateJournal<'a> {
+    state: &'a dyn StateBackend,
+    changes: Vec<Change>,
+}
+
+impl<'a> StateJournal<'a> {
+    pub fn new(state: &'a dyn StateBackend) -> Self {
+        Self {
+            state,
+            changes: Vec::new(),
+        }
+    }
+
+    /// Stage adding `amount` to the balance, failing instead of saturating
+    pub fn credit(&mut self, address: &Address, amount: U256) -> Result<(), StateError> {
+        let overflow = StateError::BalanceOverflow { address: *address };
+        let mut balance = self.state.balance(address);
+        for change in &self.changes {
+            if let Change::Credit(credited, staged) = change {
+                if credited == address {
+                    balance = balance.checked_add(*staged).ok_or(overflow.clone())?;
+                }
+            }
+        }
+        balance.checked_add(amount).ok_or(overflow)?;
+        self.changes.push(Change::Credit(*address, amount));
+        Ok(())
+    }
+
+    /// Stage advancing the nonce, returning the value it will have before
+    pub fn increment_nonce(&mut self, address: &Address) -> Result<u64, StateError> {
+        let staged = self
+            .changes
+            .iter()
+            .filter(|change| matches!(change, Change::IncrementNonce(incremented) if incremented == address))
+            .count() as u64;
+        let nonce = self
+            .state
+            .nonce(address)
+            .checked_add(staged)
+            .filter(|nonce| *nonce < u64::MAX)
+            .ok_or(StateError::NonceOverflow { address: *address })?;
+        self.changes.push(Change::IncrementNonce(*address));
+        Ok(nonce)
+    }
+
+    /// Apply every staged change
+    pub fn commit(self) {
+        for change in self.changes {
+            match change {
+                Change::Credit(address, amount) => self.state.credit(&address, amount),
+                Change::IncrementNonce(address) => {
+                    self.state.increment_nonce(&address);
+                }
+            }
+        }
+    }
+
+    /// Discard every staged change
+    pub fn rollback(self) {}
+}
+
+#[derive(Debug, Clone, Default)]
+struct Account {
+    balance: U256,
+    nonce: u64,
+    code: Vec<u8>,
+}
+
+/// State backend kept in memory; accounts start empty
+///
+/// Cloning the state shares the underlying accounts.
+#[derive(Debug, Clone, Default)]
+pub struct InMemoryState {
+    accounts: Arc<Mutex<HashMap<Address, Account>>>,
+}
+
+impl InMemoryState {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Wrap the state for sharing through `SharedState`
+    pub fn shared(self) -> SharedState {
+        Arc::new(self)
+    }
+
+    /// Replace the nonce of the account
+    pub fn set_nonce(&self, address: &Address, nonce: u64) {
+        self.update(address, |account| account.nonce = nonce);
+    }
+
+    /// Deploy `code` at the account
+    pub fn set_code(&self, address: &Address, code: Vec<u8>) {
+        self.accounts.lock().unwrap().entry(*address).or_default().code = code;
+    }
+
+    /// Number of accounts that have been touched
+    pub fn len(&self) -> usize {
+        self.accounts.lock().unwrap().len()
+    }
+
+    /// Check if no account has been touched
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn read<T>(&self, address: &Address, field: impl FnOnce(&Account) -> T) -> Option<T> {
+        self.accounts.lock().unwrap().get(address).map(field)
+    }
+
+    /// Update the account under a single lock acquisition
+    fn update<T>(&self, address: &Address, change: impl FnOnce(&mut Account) -> T) -> T {
+        change(self.accounts.lock().unwrap().entry(*address).or_default())
+    }
+}
+
+impl StateBackend for InMemoryState {
+    fn balance(&self, address: &Address) -> U256 {
+        self.read(address, |account| account.balance).unwrap_or_else(U256::zero)
+    }
+
+    fn set_balance(&self, address: &Address, balance: U256) {
+        self.update(address, |account| account.balance = balance);
+    }
+
+    fn credit(&self, address: &Address, amount: U256) {
+        self.update(address, |account| account.balance = account.balance.saturating_add(amount));
+    }
+
+    fn debit(&self, address: &Address, amount: U256) -> Result<(), StateError> {
+        self.update(address, |account| {
+            account.balance = account.balance.checked_sub(amount).ok_or(StateError::InsufficientBalance {
+                address: *address,
+                balance: account.balance,
+                amount,
+            })?;
+            Ok(())
+        })
+    }
+
+    fn nonce(&self, address: &Address) -> u64 {
+        self.read(address, |account| account.nonce).unwrap_or(0)
+    }
+
+    fn increment_nonce(&self, address: &Address) -> u64 {
+        let mut accounts = self.accounts.lock().unwrap();
+        let account = accounts.entry(*address).or_default();
+        let nonce = account.nonce;
+        account.nonce += 1;
+        nonce
+    }
+
+    fn code(&self, address: &Address) -> Vec<u8> {
+        self.read(address, |account| account.code.clone()).unwrap_or_default()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    #[cfg(feature = "alloy")]
+    use crate::primitives::AddressExt;
+
+    #[test]
+    fn test_in_memory_state_tracks_accounts() {
+        let state = InMemoryState::new();
+        let alice = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        assert_eq!(state.balance(&alice), U256::zero());
+        assert_eq!(state.nonce(&alice), 0);
+        assert!(state.code(&alice).is_empty());
+        assert!(state.is_empty());
+
+        state.set_balance(&alice, U256::from_u64(100));
+        state.credit(&alice, U256::from_u64(50));
+        assert_eq!(state.balance(&alice), U256::from_u64(150));
+        assert_eq!(
+            state.debit(&alice, U256::from_u64(200)),
+            Err(StateError::InsufficientBalance {
+                address: alice,
+                balance: U256::from_u64(150),
+                amount: U256::from_u64(200),
+            })
+        );
+        assert_eq!(state.balance(&alice), U256::from_u64(150));
+        state.debit(&alice, U256::from_u64(150)).unwrap();
+        assert_eq!(state.balance(&alice), U256::zero());
+
+        assert_eq!(state.increment_nonce(&alice), 0);
+        assert_eq!(state.increment_nonce(&alice), 1);
+        assert_eq!(state.nonce(&alice), 2);
+
+        // Clones share the accounts
+        let shared = state.clone().shared();
+        state.set_code(&alice, vec![0xfe]);
+        assert_eq!(shared.code(&alice), vec![0xfe]);
+        assert_eq!(state.len(), 1);
+    }
+
+    #[test]
+    fn test_journal_commits_deltas() {
+        let state = InMemoryState::new();
+        let alice = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        state.set_balance(&alice, U256::from_u64(100));
+
+        let mut journal = StateJournal::new(&state);
+        journal.credit(&alice, U256::from_u64(50)).unwrap();
+        assert_eq!(journal.increment_nonce(&alice), Ok(0));
+        assert_eq!(journal.increment_nonce(&alice), Ok(1));
+        // Nothing is applied before the commit
+        assert_eq!(state.balance(&alice), U256::from_u64(100));
+        assert_eq!(state.nonce(&alice), 0);
+
+        // A withdrawal made in the meantime survives the commit
+        state.debit(&alice, U256::from_u64(30)).unwrap();
+        journal.commit();
+        assert_eq!(state.balance(&alice), U256::from_u64(120));
+        assert_eq!(state.nonce(&alice), 2);
+
+        // Rolling back leaves the account as it is
+        let mut journal = StateJournal::new(&state);
+        journal.credit(&alice, U256::from_u64(1)).unwrap();
+        journal.rollback();
+        assert_eq!(state.balance(&alice), U256::from_u64(120));
+
+        // Staged credits count towards overflow
+        let mut journal = StateJournal::new(&state);
+        journal.credit(&alice, U256::new([0xff; 32]).saturating_sub(U256::from_u64(200))).unwrap();
+        assert_eq!(
+            journal.credit(&alice, U256::from_u64(100)),
+            Err(StateError::BalanceOverflow { address: alice })
+        );
+    }
+
+    #[test]
+    fn test_in_memory_credit_and_debit_are_atomic() {
+        let state = InMemoryState::new();
+        let alice = Address::from_hex("0x1234567890123456789012345678901234567890").unwrap();
+        state.set_balance(&alice, U256::from_u64(4_000));
+
+        let threads: Vec<_> = (0..4)
+            .map(|_| {
+                let state = state.clone();
+                std::thread::spawn(move || {
+                    for _ in 0..1_000 {
+                        state.credit(&alice, U256::from_u64(2));
+                        state.debit(&alice, U256::from_u64(1)).unwrap();
+                    }
+                })
+            })
+            .collect();
+        for thread in threads {
+            thread.join().unwrap();
+        }
+        assert_eq!(state.balance(&alice), U256::from_u64(8_000));
+    }
+}
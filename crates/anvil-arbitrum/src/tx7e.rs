@@ -1,270 +1,805 @@
 //! Arbitrum 0x7e transaction type implementation for Anvil
 
-use crate::precompiles::{Address, U256};
-use anyhow::{anyhow, Result};
-use rlp::{Decodable, DecoderError, Encodable, Rlp, RlpStream};
+pub use crate::core::{
+    calldata_gas, Tx7eRetryableTransaction, Tx7eTransaction, DEPOSIT_FIELDS_SINGLE_FEE, DEPOSIT_FIELDS_SPLIT_FEE,
+    TX_BASE_GAS, TX_DATA_NON_ZERO_GAS, TX_DATA_ZERO_GAS, TX_SUBTYPE_SUBMIT_RETRYABLE, TX_TYPE_0X7E,
+};
+use crate::arbitrum::{ArbitrumConfig, GasPriceComponents, DEFAULT_L2_BLOCK_GAS_LIMIT};
+use crate::gas_pool::{GasPool, L1BaseFeeEstimate, L1FeePool, StorageGas, STORAGE_WRITE_GAS};
+use crate::metrics::Metrics;
+use crate::mock_chain::MockChain;
+use crate::owner::ChainOwnerState;
+use crate::precompiles::{
+    decode_u64_word, Address, PrecompileLog, PrecompileRegistry, RetryableTicket, RetryableTicketStore, U256,
+};
+use crate::state::{InMemoryState, SharedState, StateError, StateJournal};
+use crate::primitives::HexBytes;
+#[cfg(feature = "alloy")]
+use crate::primitives::{AddressExt, U256Ext};
+use rlp::{Decodable, DecoderError, Encodable, PayloadInfo, Rlp, RlpStream};
+use serde_json::{json, Value};
 use sha3::{Digest, Keccak256};
+use std::collections::{HashMap, HashSet};
+use std::sync::{Arc, Mutex};
+use tracing::info;
 
-/// Transaction type for Arbitrum deposit transactions
-pub const TX_TYPE_0X7E: u8 = 0x7e;
+type Result<T, E = TxError> = std::result::Result<T, E>;
 
-/// Arbitrum deposit transaction (0x7e)
-#[derive(Debug, Clone, PartialEq)]
-pub struct Tx7eTransaction {
-    /// Chain ID
-    pub chain_id: u64,
-    /// Target address
-    pub target: Address,
-    /// Value in wei
-    pub value: U256,
-    /// Calldata
-    pub data: Vec<u8>,
-    /// Gas limit
-    pub gas_limit: u64,
-    /// L1 block number
-    pub l1_block_number: u64,
-    /// L1 timestamp
-    pub l1_timestamp: u64,
-    /// L1 base fee
-    pub l1_base_fee: U256,
-    /// L1 gas price
-    pub l1_gas_price: U256,
-    /// L1 gas used
-    pub l1_gas_used: u64,
-    /// L1 fee
-    pub l1_fee: U256,
-    /// Refund address
-    pub refund_address: Address,
-    /// Source hash
-    pub source_hash: [u8; 32],
-}
+/// Default maximum gas limit accepted by the validator
+pub const DEFAULT_MAX_GAS_LIMIT: u64 = DEFAULT_L2_BLOCK_GAS_LIMIT;
+
+/// Default maximum encoded transaction size (geth's `txMaxSize`)
+pub const DEFAULT_MAX_TX_SIZE: usize = 128 * 1024;
+
+/// Default maximum calldata length (Nitro's sequencer `max-tx-data-size`)
+pub const DEFAULT_MAX_CALLDATA_LEN: usize = 95_000;
+
+/// Intrinsic gas per access-list address (EIP-2930)
+pub const TX_ACCESS_LIST_ADDRESS_GAS: u64 = 2400;
+
+/// Intrinsic gas per access-list storage key (EIP-2930)
+pub const TX_ACCESS_LIST_STORAGE_KEY_GAS: u64 = 1900;
+
+/// RLP field index of the calldata in a deposit transaction
+const DEPOSIT_DATA_INDEX: usize = 3;
+
+/// RLP field index of the retry calldata in a submit-retryable transaction
+const RETRYABLE_DATA_INDEX: usize = 12;
 
 impl Tx7eTransaction {
-    /// Create a new deposit transaction
-    pub fn new(
-        chain_id: u64,
-        target: Address,
-        value: U256,
-        data: Vec<u8>,
-        gas_limit: u64,
-        l1_block_number: u64,
-        l1_timestamp: u64,
-        l1_base_fee: U256,
-        l1_gas_price: U256,
-        l1_gas_used: u64,
-        l1_fee: U256,
-        refund_address: Address,
-        source_hash: [u8; 32],
-    ) -> Self {
-        Self {
-            chain_id,
-            target,
-            value,
-            data,
-            gas_limit,
-            l1_block_number,
-            l1_timestamp,
-            l1_base_fee,
-            l1_gas_price,
-            l1_gas_used,
+    /// Start building a deposit with named fields
+    pub fn builder() -> Tx7eTransactionBuilder {
+        Tx7eTransactionBuilder::new()
+    }
+
+    /// Check the transaction's fields, independent of any block or chain limits
+    pub fn validate(&self) -> Vec<TxValidationError> {
+        let mut errors = Vec::new();
+
+        // Check chain ID
+        if self.chain_id == 0 {
+            errors.push(TxValidationError::ZeroChainId);
+        }
+
+        // Check target address
+        if self.target.is_zero() {
+            errors.push(TxValidationError::ZeroTarget);
+        }
+
+        // Check gas limit
+        if self.gas_limit == 0 {
+            errors.push(TxValidationError::ZeroGasLimit);
+        }
+
+        // Check L1 block number
+        if self.l1_block_number == 0 {
+            errors.push(TxValidationError::ZeroL1BlockNumber);
+        }
+
+        // Check L1 timestamp
+        if self.l1_timestamp == 0 {
+            errors.push(TxValidationError::ZeroL1Timestamp);
+        }
+
+        // Check L1 base fee
+        if self.l1_base_fee == U256::zero() {
+            errors.push(TxValidationError::ZeroL1BaseFee);
+        }
+
+        // Check source hash
+        if self.source_hash == [0u8; 32] {
+            errors.push(TxValidationError::ZeroSourceHash);
+        }
+
+        // Check the L1 fee split: both shares or neither, adding up to the L1 fee
+        match (self.l1_poster_fee, self.l1_network_fee) {
+            (None, None) => {}
+            (Some(poster_fee), Some(network_fee)) => {
+                if poster_fee.checked_add(network_fee) != Some(self.l1_fee) {
+                    errors.push(TxValidationError::L1FeeSplitMismatch);
+                }
+            }
+            _ => errors.push(TxValidationError::IncompleteL1FeeSplit),
+        }
+
+        errors
+    }
+
+    /// Convert to the JSON object shape returned by `eth_getTransactionByHash`
+    ///
+    /// Numeric fields are hex quantities and byte fields are `0x`-prefixed hex data,
+    /// following go-ethereum conventions. `l1PosterFee` and `l1NetworkFee` are
+    /// only present when the L1 fee is split.
+    pub fn to_rpc_json(&self) -> Value {
+        let mut json = json!({
+            "hash": hex_data(&self.hash()),
+            "type": format!("{:#04x}", TX_TYPE_0X7E),
+            "chainId": u64_quantity(self.chain_id),
+            "to": self.target.to_string(),
+            "value": self.value.to_hex_quantity(),
+            "input": hex_data(&self.data),
+            "gas": u64_quantity(self.gas_limit),
+            "requestId": hex_data(&self.request_id()),
+            "sourceHash": hex_data(&self.source_hash),
+            "l1BlockNumber": u64_quantity(self.l1_block_number),
+            "l1Timestamp": u64_quantity(self.l1_timestamp),
+            "l1BaseFee": self.l1_base_fee.to_hex_quantity(),
+            "l1GasPrice": self.l1_gas_price.to_hex_quantity(),
+            "l1GasUsed": u64_quantity(self.l1_gas_used),
+            "l1Fee": self.l1_fee.to_hex_quantity(),
+            "refundTo": self.refund_address.to_string(),
+        });
+        if let Some((poster_fee, network_fee)) = self.l1_fee_split() {
+            json["l1PosterFee"] = json!(poster_fee.to_hex_quantity());
+            json["l1NetworkFee"] = json!(network_fee.to_hex_quantity());
+        }
+        json
+    }
+
+    /// Rebuild a transaction from the JSON object shape produced by `to_rpc_json`
+    ///
+    /// Derived fields (`hash`, `requestId`) are ignored. `l1PosterFee` and
+    /// `l1NetworkFee` are optional but must appear together and add up to
+    /// `l1Fee`; every other field is required, and `type` must be `0x7e`.
+    pub fn from_rpc_json(value: &Value) -> Result<Self> {
+        let field = |name: &'static str| {
+            value
+                .get(name)
+                .and_then(Value::as_str)
+                .ok_or(TxError::MissingJsonField { field: name })
+        };
+        let invalid = |name: &'static str, reason: String| TxError::InvalidJsonField { field: name, reason };
+        let u64_field = |name| {
+            let text = field(name)?;
+            u64::from_str_radix(hex_digits(text), 16).map_err(|e| invalid(name, format!("'{}': {}", text, e)))
+        };
+        let bytes_field = |name| {
+            let text = field(name)?;
+            hex::decode(hex_digits(text)).map_err(|e| invalid(name, format!("'{}': {}", text, e)))
+        };
+        let u256_field = |name| {
+            let text = field(name)?;
+            let digits = hex_digits(text);
+            let padded = if digits.len() % 2 == 1 { format!("0{}", digits) } else { digits.to_string() };
+            let bytes = hex::decode(padded).map_err(|e| invalid(name, format!("'{}': {}", text, e)))?;
+            if bytes.len() > 32 {
+                return Err(invalid(name, format!("'{}' exceeds 256 bits", text)));
+            }
+            Ok(U256::from_big_endian(&bytes))
+        };
+        let address_field = |name| {
+            let text = field(name)?;
+            Address::from_hex(text).map_err(|e| invalid(name, format!("'{}': {}", text, e)))
+        };
+
+        let tx_type = u64_field("type")?;
+        if tx_type != TX_TYPE_0X7E as u64 {
+            return Err(TxError::InvalidType { got: tx_type as u8 });
+        }
+        let source_hash: [u8; 32] = bytes_field("sourceHash")?
+            .try_into()
+            .map_err(|bytes: Vec<u8>| invalid("sourceHash", format!("expected 32 bytes, got {}", bytes.len())))?;
+
+        let (l1_poster_fee, l1_network_fee) = match (value.get("l1PosterFee"), value.get("l1NetworkFee")) {
+            (None, None) => (None, None),
+            _ => (Some(u256_field("l1PosterFee")?), Some(u256_field("l1NetworkFee")?)),
+        };
+
+        let l1_fee = u256_field("l1Fee")?;
+        if let (Some(poster_fee), Some(network_fee)) = (l1_poster_fee, l1_network_fee) {
+            if poster_fee.checked_add(network_fee) != Some(l1_fee) {
+                return Err(invalid("l1Fee", "is not the sum of l1PosterFee and l1NetworkFee".to_string()));
+            }
+        }
+
+        Ok(Self {
+            chain_id: u64_field("chainId")?,
+            target: address_field("to")?,
+            value: u256_field("value")?,
+            data: bytes_field("input")?,
+            gas_limit: u64_field("gas")?,
+            l1_block_number: u64_field("l1BlockNumber")?,
+            l1_timestamp: u64_field("l1Timestamp")?,
+            l1_base_fee: u256_field("l1BaseFee")?,
+            l1_gas_price: u256_field("l1GasPrice")?,
+            l1_gas_used: u64_field("l1GasUsed")?,
             l1_fee,
-            refund_address,
+            refund_address: address_field("refundTo")?,
             source_hash,
+            l1_poster_fee,
+            l1_network_fee,
+        })
+    }
+}
+
+impl Tx7eTransaction {
+    /// Build the receipt of this deposit after it executed
+    ///
+    /// `gas_used_for_l1` is the L1 share of `gas_used` the processor charged. The
+    /// other L1 fields (`l1_fee` and its split, `l1_block_number`) are copied from
+    /// the transaction. Each deposit is mined into its own block, so the cumulative
+    /// gas equals `gas_used`.
+    pub fn encode_for_receipt(
+        &self,
+        status: bool,
+        gas_used: u64,
+        gas_used_for_l1: u64,
+        effective_gas_price: U256,
+        logs: Vec<PrecompileLog>,
+    ) -> Receipt {
+        Receipt {
+            transaction_hash: self.hash(),
+            status,
+            gas_used,
+            cumulative_gas_used: gas_used,
+            effective_gas_price,
+            l1_fee: self.l1_fee,
+            l1_poster_fee: self.l1_poster_fee,
+            l1_network_fee: self.l1_network_fee,
+            gas_used_for_l1,
+            l1_block_number: self.l1_block_number,
+            logs,
         }
     }
+}
 
-    /// Get the transaction hash
-    pub fn hash(&self) -> [u8; 32] {
-        let encoded = self.rlp_encode();
-        let mut hasher = Keccak256::new();
-        hasher.update(&encoded);
-        hasher.finalize().into()
+/// Strip the `0x` prefix from a JSON-RPC hex string
+fn hex_digits(text: &str) -> &str {
+    text.strip_prefix("0x").unwrap_or(text)
+}
+
+/// Builder for `Tx7eTransaction` with named fields
+///
+/// The chain ID and L1 base fee default to `ArbitrumConfig::default()`, the L1
+/// gas price to the L1 base fee, the gas limit to the intrinsic gas, and the
+/// refund address to the target. The target, L1 block number, L1 timestamp and
+/// source hash have no sensible default and must be set, or `build` fails
+/// validation.
+#[derive(Debug, Clone)]
+pub struct Tx7eTransactionBuilder {
+    chain_id: u64,
+    target: Address,
+    value: U256,
+    data: Vec<u8>,
+    gas_limit: Option<u64>,
+    l1_block_number: u64,
+    l1_timestamp: u64,
+    l1_base_fee: U256,
+    l1_gas_price: Option<U256>,
+    l1_gas_used: u64,
+    l1_fee: U256,
+    l1_fee_split: Option<(U256, U256)>,
+    refund_address: Option<Address>,
+    source_hash: [u8; 32],
+}
+
+impl Tx7eTransactionBuilder {
+    /// Create a builder with the default fields
+    pub fn new() -> Self {
+        let config = ArbitrumConfig::default();
+        Self {
+            chain_id: config.chain_id,
+            target: Address::ZERO,
+            value: U256::zero(),
+            data: Vec::new(),
+            gas_limit: None,
+            l1_block_number: 0,
+            l1_timestamp: 0,
+            l1_base_fee: U256::from_u64(config.l1_base_fee),
+            l1_gas_price: None,
+            l1_gas_used: 0,
+            l1_fee: U256::zero(),
+            l1_fee_split: None,
+            refund_address: None,
+            source_hash: [0u8; 32],
+        }
     }
 
-    /// RLP encode the transaction
-    pub fn rlp_encode(&self) -> Vec<u8> {
-        let mut stream = RlpStream::new();
-        self.rlp_append(&mut stream);
-        stream.out().to_vec()
+    /// Set the chain ID
+    pub fn with_chain_id(mut self, chain_id: u64) -> Self {
+        self.chain_id = chain_id;
+        self
+    }
+
+    /// Set the target address
+    pub fn with_target(mut self, target: Address) -> Self {
+        self.target = target;
+        self
+    }
+
+    /// Set the value in wei
+    pub fn with_value(mut self, value: U256) -> Self {
+        self.value = value;
+        self
+    }
+
+    /// Set the calldata
+    pub fn with_data(mut self, data: Vec<u8>) -> Self {
+        self.data = data;
+        self
+    }
+
+    /// Set the gas limit
+    pub fn with_gas_limit(mut self, gas_limit: u64) -> Self {
+        self.gas_limit = Some(gas_limit);
+        self
+    }
+
+    /// Set the L1 block number
+    pub fn with_l1_block_number(mut self, l1_block_number: u64) -> Self {
+        self.l1_block_number = l1_block_number;
+        self
+    }
+
+    /// Set the L1 timestamp
+    pub fn with_l1_timestamp(mut self, l1_timestamp: u64) -> Self {
+        self.l1_timestamp = l1_timestamp;
+        self
+    }
+
+    /// Set the L1 base fee
+    pub fn with_l1_base_fee(mut self, l1_base_fee: U256) -> Self {
+        self.l1_base_fee = l1_base_fee;
+        self
+    }
+
+    /// Set the L1 gas price
+    pub fn with_l1_gas_price(mut self, l1_gas_price: U256) -> Self {
+        self.l1_gas_price = Some(l1_gas_price);
+        self
     }
 
-    /// Get the total L1 cost
-    pub fn total_l1_cost(&self) -> U256 {
-        self.l1_fee.clone()
+    /// Set the L1 gas used
+    pub fn with_l1_gas_used(mut self, l1_gas_used: u64) -> Self {
+        self.l1_gas_used = l1_gas_used;
+        self
     }
 
-    /// Get the effective gas price
-    pub fn effective_gas_price(&self) -> U256 {
-        if self.l1_gas_used == 0 {
-            return U256::zero();
+    /// Set the L1 fee
+    pub fn with_l1_fee(mut self, l1_fee: U256) -> Self {
+        self.l1_fee = l1_fee;
+        self
+    }
+
+    /// Split the L1 fee into poster and network shares; the L1 fee becomes their sum
+    ///
+    /// `build` fails with `L1FeeSplitMismatch` if the sum overflows.
+    pub fn with_l1_fee_split(mut self, poster_fee: U256, network_fee: U256) -> Self {
+        self.l1_fee_split = Some((poster_fee, network_fee));
+        self
+    }
+
+    /// Set the refund address
+    pub fn with_refund_address(mut self, refund_address: Address) -> Self {
+        self.refund_address = Some(refund_address);
+        self
+    }
+
+    /// Set the source hash
+    pub fn with_source_hash(mut self, source_hash: [u8; 32]) -> Self {
+        self.source_hash = source_hash;
+        self
+    }
+
+    /// Build the transaction, returning the first validation error if any
+    pub fn build(self) -> Result<Tx7eTransaction, TxValidationError> {
+        let mut tx = Tx7eTransaction::new(
+            self.chain_id,
+            self.target,
+            self.value,
+            self.data,
+            0,
+            self.l1_block_number,
+            self.l1_timestamp,
+            self.l1_base_fee,
+            self.l1_gas_price.unwrap_or(self.l1_base_fee),
+            self.l1_gas_used,
+            self.l1_fee,
+            self.refund_address.unwrap_or(self.target),
+            self.source_hash,
+        );
+        if let Some((poster_fee, network_fee)) = self.l1_fee_split {
+            tx = tx
+                .with_l1_fee_split(poster_fee, network_fee)
+                .ok_or(TxValidationError::L1FeeSplitMismatch)?;
         }
-        
-        let _total_cost = self.l1_fee.clone();
-        let gas_used = U256::from_u64(self.l1_gas_used);
-        
-        // Simple division (in a real implementation, this would be more sophisticated)
-        if gas_used == U256::zero() {
-            U256::zero()
-        } else {
-            // For simplicity, return the L1 base fee
-            self.l1_base_fee.clone()
+        tx.gas_limit = self.gas_limit.unwrap_or_else(|| tx.intrinsic_gas());
+
+        match tx.validate().into_iter().next() {
+            Some(error) => Err(error),
+            None => Ok(tx),
         }
     }
 }
 
-impl Encodable for Tx7eTransaction {
-    fn rlp_append(&self, s: &mut RlpStream) {
-        s.begin_list(13);
-        s.append(&self.chain_id);
-        s.append(&self.target.as_bytes().to_vec());
-        s.append(&self.value.to_big_endian());
-        s.append(&self.data);
-        s.append(&self.gas_limit);
-        s.append(&self.l1_block_number);
-        s.append(&self.l1_timestamp);
-        s.append(&self.l1_base_fee.to_big_endian());
-        s.append(&self.l1_gas_price.to_big_endian());
-        s.append(&self.l1_gas_used);
-        s.append(&self.l1_fee.to_big_endian());
-        s.append(&self.refund_address.as_bytes().to_vec());
-        s.append(&self.source_hash.to_vec());
-    }
-}
-
-impl Decodable for Tx7eTransaction {
-    fn decode(rlp: &Rlp) -> Result<Self, DecoderError> {
-        if rlp.item_count()? != 13 {
-            return Err(DecoderError::RlpIncorrectListLen);
-        }
-
-        let chain_id: u64 = rlp.val_at(0)?;
-        let target_bytes: Vec<u8> = rlp.val_at(1)?;
-        let value_bytes: Vec<u8> = rlp.val_at(2)?;
-        let data: Vec<u8> = rlp.val_at(3)?;
-        let gas_limit: u64 = rlp.val_at(4)?;
-        let l1_block_number: u64 = rlp.val_at(5)?;
-        let l1_timestamp: u64 = rlp.val_at(6)?;
-        let l1_base_fee_bytes: Vec<u8> = rlp.val_at(7)?;
-        let l1_gas_price_bytes: Vec<u8> = rlp.val_at(8)?;
-        let l1_gas_used: u64 = rlp.val_at(9)?;
-        let l1_fee_bytes: Vec<u8> = rlp.val_at(10)?;
-        let refund_address_bytes: Vec<u8> = rlp.val_at(11)?;
-        let source_hash: Vec<u8> = rlp.val_at(12)?;
-
-        // Validate and convert bytes to proper types
-        if target_bytes.len() != 20 {
-            return Err(DecoderError::Custom("Invalid target address length"));
-        }
-        if refund_address_bytes.len() != 20 {
-            return Err(DecoderError::Custom("Invalid refund address length"));
-        }
-        if source_hash.len() != 32 {
-            return Err(DecoderError::Custom("Invalid source hash length"));
-        }
-
-        let target = Address::new(target_bytes.try_into().unwrap());
-        let refund_address = Address::new(refund_address_bytes.try_into().unwrap());
-        let source_hash_array: [u8; 32] = source_hash.try_into().unwrap();
-
-        let value = U256::from_big_endian(&value_bytes);
-        let l1_base_fee = U256::from_big_endian(&l1_base_fee_bytes);
-        let l1_gas_price = U256::from_big_endian(&l1_gas_price_bytes);
-        let l1_fee = U256::from_big_endian(&l1_fee_bytes);
+impl Default for Tx7eTransactionBuilder {
+    fn default() -> Self {
+        Self::new()
+    }
+}
 
-        Ok(Self {
-            chain_id,
-            target,
-            value,
-            data,
-            gas_limit,
-            l1_block_number,
-            l1_timestamp,
-            l1_base_fee,
-            l1_gas_price,
-            l1_gas_used,
-            l1_fee,
-            refund_address,
-            source_hash: source_hash_array,
-        })
+/// Encode a `u64` as a JSON-RPC hex quantity (no leading zeros)
+fn u64_quantity(value: u64) -> String {
+    format!("{:#x}", value)
+}
+
+/// Encode bytes as `0x`-prefixed JSON-RPC hex data
+fn hex_data(bytes: &[u8]) -> String {
+    format!("0x{}", hex::encode(bytes))
+}
+
+impl Tx7eRetryableTransaction {
+    /// Build the retryable ticket this transaction creates at `created_at`
+    pub fn to_ticket(&self, created_at: u64) -> RetryableTicket {
+        RetryableTicket {
+            id: self.ticket_id(),
+            from: self.from,
+            beneficiary: self.beneficiary,
+            retry_to: self.retry_to,
+            retry_value: self.retry_value,
+            retry_data: self.retry_data.clone(),
+            gas_limit: self.gas_limit,
+            created_at,
+        }
+    }
+}
+
+/// An error parsing or processing a raw 0x7e transaction
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TxError {
+    #[error("Empty transaction data")]
+    Empty,
+    #[error("Invalid transaction type: expected 0x7e, got 0x{got:02x}")]
+    InvalidType { got: u8 },
+    #[error("Invalid transaction envelope: leading byte 0x{first:02x} is neither a type byte nor an RLP list")]
+    InvalidEnvelope { first: u8 },
+    #[error("Transaction size {size} exceeds max tx size {max}")]
+    TooLarge { size: usize, max: usize },
+    #[error("Transaction declares {declared} bytes, exceeding max tx size {max}")]
+    DeclaredTooLarge { declared: usize, max: usize },
+    #[error("Calldata length {len} exceeds max calldata length {max}")]
+    CalldataTooLong { len: usize, max: usize },
+    #[error("RLP decoding failed: {0:?}")]
+    Rlp(#[from] DecoderError),
+    #[error("Expected a deposit transaction, got a submit-retryable transaction")]
+    ExpectedDeposit,
+    #[error("Expected a submit-retryable transaction, got a deposit transaction")]
+    ExpectedSubmitRetryable,
+    #[error("Invalid batch framing: expected an RLP list")]
+    InvalidBatch,
+    #[error("Batch entry {index}: {source}")]
+    BatchEntry { index: usize, source: Box<TxError> },
+    #[error("Missing field '{field}' in transaction JSON")]
+    MissingJsonField { field: &'static str },
+    #[error("Invalid field '{field}' in transaction JSON: {reason}")]
+    InvalidJsonField { field: &'static str, reason: String },
+}
+
+/// A parsed 0x7e payload
+#[derive(Debug, Clone, PartialEq)]
+pub enum Tx7eVariant {
+    /// Plain deposit (`0x7e || rlp`)
+    Deposit(Tx7eTransaction),
+    /// Submit-retryable (`0x7e || 0x69 || rlp`)
+    SubmitRetryable(Tx7eRetryableTransaction),
+}
+
+/// EIP-2718 type of a raw transaction, read from its leading byte
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum TxType {
+    /// Untyped legacy transaction (an RLP list, leading byte `0xc0..=0xff`)
+    Legacy,
+    /// EIP-2930 access list transaction (`0x01`)
+    AccessList,
+    /// EIP-1559 dynamic fee transaction (`0x02`)
+    DynamicFee,
+    /// Arbitrum deposit or submit-retryable (`0x7e`)
+    Arbitrum,
+    /// Any other type byte (`0x00..=0x7f`)
+    Other(u8),
+}
+
+impl TxType {
+    /// Classify `raw_tx` by its leading byte
+    ///
+    /// Bytes `0x80..=0xbf` start neither a type nor an RLP list and are rejected.
+    pub fn of(raw_tx: &[u8]) -> Result<Self> {
+        match *raw_tx.first().ok_or(TxError::Empty)? {
+            0x01 => Ok(Self::AccessList),
+            0x02 => Ok(Self::DynamicFee),
+            TX_TYPE_0X7E => Ok(Self::Arbitrum),
+            first @ 0x00..=0x7f => Ok(Self::Other(first)),
+            first @ 0x80..=0xbf => Err(TxError::InvalidEnvelope { first }),
+            0xc0..=0xff => Ok(Self::Legacy),
+        }
     }
 }
 
+/// A raw transaction split into the 0x7e payloads handled locally and everything else
+#[derive(Debug, Clone, PartialEq)]
+pub enum TxEnvelope {
+    /// Plain deposit (`0x7e || rlp`)
+    Deposit(Tx7eTransaction),
+    /// Submit-retryable (`0x7e || 0x69 || rlp`)
+    SubmitRetryable(Tx7eRetryableTransaction),
+    /// Any other transaction, kept raw for forwarding to the upstream node
+    Other { tx_type: TxType, raw: Vec<u8> },
+}
+
 /// Transaction parser for 0x7e transactions
-pub struct Tx7eParser;
+#[derive(Debug, Clone)]
+pub struct Tx7eParser {
+    /// Maximum gas limit a transaction may request (the block gas limit)
+    max_gas_limit: u64,
+    /// Maximum encoded transaction size in bytes, including the type prefix
+    max_tx_size: usize,
+    /// Maximum calldata length in bytes
+    max_calldata_len: usize,
+    /// L2 base fee in wei used to price transaction requests
+    l2_base_fee: u64,
+}
 
 impl Tx7eParser {
-    /// Parse raw transaction bytes
+    /// Create a parser with the default block gas limit and size limits
+    pub fn new() -> Self {
+        Self {
+            max_gas_limit: DEFAULT_MAX_GAS_LIMIT,
+            max_tx_size: DEFAULT_MAX_TX_SIZE,
+            max_calldata_len: DEFAULT_MAX_CALLDATA_LEN,
+            l2_base_fee: GasPriceComponents::default().effective_l2_base_fee(),
+        }
+    }
+
+    /// Create a parser enforcing the configured L2 block gas limit and L2 base fee
+    pub fn from_config(config: &ArbitrumConfig) -> Self {
+        Self::new().with_config(config)
+    }
+
+    /// Take the block gas limit and L2 base fee from `config`, keeping the size limits
+    pub fn with_config(self, config: &ArbitrumConfig) -> Self {
+        self.with_max_gas_limit(config.l2_block_gas_limit)
+            .with_l2_base_fee(config.gas_price_components.effective_l2_base_fee())
+    }
+
+    /// Set the maximum gas limit a transaction may request
+    pub fn with_max_gas_limit(mut self, max_gas_limit: u64) -> Self {
+        self.max_gas_limit = max_gas_limit;
+        self
+    }
+
+    /// Get the maximum gas limit a transaction may request
+    pub fn max_gas_limit(&self) -> u64 {
+        self.max_gas_limit
+    }
+
+    /// Set the L2 base fee used to price transaction requests
+    pub fn with_l2_base_fee(mut self, l2_base_fee: u64) -> Self {
+        self.l2_base_fee = l2_base_fee;
+        self
+    }
+
+    /// Get the L2 base fee used to price transaction requests
+    pub fn l2_base_fee(&self) -> u64 {
+        self.l2_base_fee
+    }
+
+    /// Set the maximum encoded transaction size, including the type prefix
+    pub fn with_max_tx_size(mut self, max_tx_size: usize) -> Self {
+        self.max_tx_size = max_tx_size;
+        self
+    }
+
+    /// Get the maximum encoded transaction size
+    pub fn max_tx_size(&self) -> usize {
+        self.max_tx_size
+    }
+
+    /// Set the maximum calldata length
+    pub fn with_max_calldata_len(mut self, max_calldata_len: usize) -> Self {
+        self.max_calldata_len = max_calldata_len;
+        self
+    }
+
+    /// Get the maximum calldata length
+    pub fn max_calldata_len(&self) -> usize {
+        self.max_calldata_len
+    }
+
+    /// Parse a typed deposit, `0x7e || rlp` as `Tx7eTransaction::rlp_encode_typed` produces
     pub fn parse(&self, raw_tx: &[u8]) -> Result<Tx7eTransaction> {
+        match self.parse_variant(raw_tx)? {
+            Tx7eVariant::Deposit(tx) => Ok(tx),
+            Tx7eVariant::SubmitRetryable(_) => Err(TxError::ExpectedDeposit),
+        }
+    }
+
+    /// Parse any raw transaction, decoding 0x7e payloads and passing others through
+    pub fn parse_envelope(&self, raw_tx: &[u8]) -> Result<TxEnvelope> {
+        match TxType::of(raw_tx)? {
+            TxType::Arbitrum => Ok(match self.parse_variant(raw_tx)? {
+                Tx7eVariant::Deposit(tx) => TxEnvelope::Deposit(tx),
+                Tx7eVariant::SubmitRetryable(tx) => TxEnvelope::SubmitRetryable(tx),
+            }),
+            tx_type => Ok(TxEnvelope::Other {
+                tx_type,
+                raw: raw_tx.to_vec(),
+            }),
+        }
+    }
+
+    /// Parse raw transaction bytes as either a deposit or a submit-retryable
+    ///
+    /// Deposits are `0x7e || rlp`, submit-retryables are `0x7e || 0x69 || rlp`.
+    /// An RLP list always starts with a byte >= 0xc0, so the inner discriminator
+    /// never collides with a deposit payload.
+    ///
+    /// The size limits are checked against the RLP headers before any field is
+    /// decoded, so a payload declaring an oversized list or calldata is rejected
+    /// without materializing it.
+    pub fn parse_variant(&self, raw_tx: &[u8]) -> Result<Tx7eVariant> {
         if raw_tx.is_empty() {
-            return Err(anyhow!("Empty transaction data"));
+            return Err(TxError::Empty);
         }
 
         if raw_tx[0] != TX_TYPE_0X7E {
-            return Err(anyhow!("Invalid transaction type: expected 0x7e, got 0x{:02x}", raw_tx[0]));
+            return Err(TxError::InvalidType { got: raw_tx[0] });
+        }
+
+        if raw_tx.len() > self.max_tx_size {
+            return Err(TxError::TooLarge {
+                size: raw_tx.len(),
+                max: self.max_tx_size,
+            });
         }
 
         let rlp_data = &raw_tx[1..];
+        if rlp_data.first() == Some(&TX_SUBTYPE_SUBMIT_RETRYABLE) {
+            self.check_declared_sizes(&rlp_data[1..], 2, RETRYABLE_DATA_INDEX)?;
+            let rlp = Rlp::new(&rlp_data[1..]);
+            return Tx7eRetryableTransaction::decode(&rlp)
+                .map(Tx7eVariant::SubmitRetryable)
+                .map_err(TxError::Rlp);
+        }
+
+        self.check_declared_sizes(rlp_data, 1, DEPOSIT_DATA_INDEX)?;
         let rlp = Rlp::new(rlp_data);
         
         Tx7eTransaction::decode(&rlp)
-            .map_err(|e| anyhow!("RLP decoding failed: {:?}", e))
+            .map(Tx7eVariant::Deposit)
+            .map_err(TxError::Rlp)
     }
 
-    /// Validate a parsed transaction
-    pub fn validate_transaction(&self, tx: &Tx7eTransaction) -> TransactionValidation {
-        let mut errors = Vec::new();
+    /// Check the lengths declared by the RLP headers against the size limits
+    ///
+    /// `prefix_len` is the number of type bytes preceding `rlp_data`. Only the
+    /// headers up to the calldata field at `data_index` are read; the lengths
+    /// they declare are not checked against the buffer here, which is left to
+    /// the full decode.
+    fn check_declared_sizes(&self, rlp_data: &[u8], prefix_len: usize, data_index: usize) -> Result<()> {
+        let header = |offset: usize| PayloadInfo::from(rlp_data.get(offset..).unwrap_or(&[]));
+        // `PayloadInfo::total` overflows on lengths declared close to `usize::MAX`
+        let total = |info: &PayloadInfo| info.header_len.saturating_add(info.value_len);
 
-        // Check chain ID
-        if tx.chain_id == 0 {
-            errors.push("Invalid chain ID: cannot be zero".to_string());
+        let list = header(0)?;
+        let declared_size = prefix_len.saturating_add(total(&list));
+        if declared_size > self.max_tx_size {
+            return Err(TxError::DeclaredTooLarge {
+                declared: declared_size,
+                max: self.max_tx_size,
+            });
         }
 
-        // Check target address
-        if *tx.target.as_bytes() == [0u8; 20] {
-            errors.push("Invalid target address: cannot be zero address".to_string());
+        let mut offset = list.header_len;
+        for _ in 0..data_index {
+            offset = offset.saturating_add(total(&header(offset)?));
         }
-
-        // Check gas limit
-        if tx.gas_limit == 0 {
-            errors.push("Invalid gas limit: cannot be zero".to_string());
+        let data = header(offset)?;
+        if data.value_len > self.max_calldata_len {
+            return Err(TxError::CalldataTooLong {
+                len: data.value_len,
+                max: self.max_calldata_len,
+            });
         }
+        Ok(())
+    }
 
-        // Check L1 block number
-        if tx.l1_block_number == 0 {
-            errors.push("Invalid L1 block number: cannot be zero".to_string());
+    /// Parse a batch of raw 0x7e transactions
+    ///
+    /// A batch is framed as an RLP list of byte strings, each holding one raw
+    /// transaction (`0x7e || rlp`), the same way typed transactions appear in a
+    /// block body. Each entry is parsed independently, so a malformed entry only
+    /// yields an `Err` at its own position. If the outer list itself is malformed
+    /// a single `Err` is returned.
+    pub fn parse_batch(&self, data: &[u8]) -> Vec<Result<Tx7eTransaction>> {
+        let rlp = Rlp::new(data);
+        if !rlp.is_list() {
+            return vec![Err(TxError::InvalidBatch)];
         }
 
-        // Check L1 timestamp
-        if tx.l1_timestamp == 0 {
-            errors.push("Invalid L1 timestamp: cannot be zero".to_string());
+        rlp.iter()
+            .enumerate()
+            .map(|(index, item)| {
+                item.as_val::<Vec<u8>>()
+                    .map_err(TxError::Rlp)
+                    .and_then(|raw_tx| self.parse(&raw_tx))
+                    .map_err(|e| TxError::BatchEntry {
+                        index,
+                        source: Box::new(e),
+                    })
+            })
+            .collect()
+    }
+
+    /// Frame raw transactions as a batch understood by `parse_batch`
+    pub fn encode_batch(raw_txs: &[Vec<u8>]) -> Vec<u8> {
+        let mut stream = RlpStream::new_list(raw_txs.len());
+        for raw_tx in raw_txs {
+            stream.append(raw_tx);
         }
+        stream.out().to_vec()
+    }
 
-        // Check L1 base fee
-        if tx.l1_base_fee == U256::zero() {
-            errors.push("Invalid L1 base fee: cannot be zero".to_string());
+    /// Validate a parsed transaction
+    pub fn validate_transaction(&self, tx: &Tx7eTransaction) -> TransactionValidation {
+        self.validate_with_errors(tx, Vec::new())
+    }
+
+    /// Validate a parsed transaction claimed to come from the given L1 log
+    ///
+    /// On top of `validate_transaction`, the source hash must match the one
+    /// derived from the L1 transaction hash, block number and log index.
+    pub fn validate_transaction_with_provenance(
+        &self,
+        tx: &Tx7eTransaction,
+        l1_tx_hash: &[u8; 32],
+        l1_block_number: u64,
+        l1_log_index: u64,
+    ) -> TransactionValidation {
+        let mut errors = Vec::new();
+        if !self.verify_source_hash(tx, l1_tx_hash, l1_block_number, l1_log_index) {
+            errors.push(TxValidationError::SourceHashMismatch);
         }
+        self.validate_with_errors(tx, errors)
+    }
 
-        // Check source hash
-        if tx.source_hash == [0u8; 32] {
-            errors.push("Invalid source hash: cannot be zero".to_string());
+    fn validate_with_errors(&self, tx: &Tx7eTransaction, extra: Vec<TxValidationError>) -> TransactionValidation {
+        let mut errors = tx.validate();
+
+        // Check gas limit against the block gas limit
+        if tx.gas_limit > self.max_gas_limit {
+            errors.push(TxValidationError::GasLimitExceedsBlockLimit {
+                gas_limit: tx.gas_limit,
+                max_gas_limit: self.max_gas_limit,
+            });
         }
+        errors.extend(extra);
 
         TransactionValidation {
-            isValid: errors.is_empty(),
+            is_valid: errors.is_empty(),
             errors,
         }
     }
 
     /// Convert to a standard transaction request
+    ///
+    /// The fee cap covers both the transaction's effective gas price and the L2
+    /// base fee. Deposits pay no tip, so the priority fee is zero. `gas_price`
+    /// keeps the effective price for legacy consumers.
     pub fn to_transaction_request(&self, tx: &Tx7eTransaction) -> TransactionRequest {
+        let gas_price = tx.effective_gas_price();
+        let l2_base_fee = U256::from_u64(self.l2_base_fee);
+        let max_fee_per_gas = if gas_price > l2_base_fee { gas_price } else { l2_base_fee };
         TransactionRequest {
-            to: Some(tx.target.clone()),
-            value: Some(tx.value.clone()),
+            to: Some(tx.target),
+            value: Some(tx.value),
             data: Some(tx.data.clone()),
             gas: Some(tx.gas_limit),
-            gas_price: Some(tx.effective_gas_price()),
+            gas_price: Some(gas_price),
+            max_fee_per_gas: Some(max_fee_per_gas),
+            max_priority_fee_per_gas: Some(U256::zero()),
             nonce: None,
             chain_id: Some(tx.chain_id),
+            access_list: None,
         }
     }
 
@@ -277,17 +812,72 @@ impl Tx7eParser {
     ) -> [u8; 32] {
         let mut hasher = Keccak256::new();
         hasher.update(l1_tx_hash);
-        hasher.update(&l1_block_number.to_be_bytes());
-        hasher.update(&l1_log_index.to_be_bytes());
+        hasher.update(l1_block_number.to_be_bytes());
+        hasher.update(l1_log_index.to_be_bytes());
         hasher.finalize().into()
     }
+
+    /// Check that `tx.source_hash` is the one derived from the given L1 log
+    pub fn verify_source_hash(
+        &self,
+        tx: &Tx7eTransaction,
+        l1_tx_hash: &[u8; 32],
+        l1_block_number: u64,
+        l1_log_index: u64,
+    ) -> bool {
+        tx.source_hash == self.generate_source_hash(l1_tx_hash, l1_block_number, l1_log_index)
+    }
+}
+
+impl Default for Tx7eParser {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+/// A reason a transaction failed validation
+#[derive(Debug, Clone, PartialEq, Eq, thiserror::Error)]
+pub enum TxValidationError {
+    #[error("Invalid chain ID: cannot be zero")]
+    ZeroChainId,
+    #[error("Invalid target address: cannot be zero address")]
+    ZeroTarget,
+    #[error("Invalid gas limit: cannot be zero")]
+    ZeroGasLimit,
+    #[error("Invalid gas limit: {gas_limit} exceeds block gas limit {max_gas_limit}")]
+    GasLimitExceedsBlockLimit { gas_limit: u64, max_gas_limit: u64 },
+    #[error("Invalid L1 block number: cannot be zero")]
+    ZeroL1BlockNumber,
+    #[error("Invalid L1 timestamp: cannot be zero")]
+    ZeroL1Timestamp,
+    #[error("Invalid L1 base fee: cannot be zero")]
+    ZeroL1BaseFee,
+    #[error("Invalid source hash: cannot be zero")]
+    ZeroSourceHash,
+    #[error("Invalid source hash: does not match the claimed L1 transaction, block and log index")]
+    SourceHashMismatch,
+    #[error("Invalid L1 fee split: poster and network fees must both be set")]
+    IncompleteL1FeeSplit,
+    #[error("Invalid L1 fee split: poster and network fees do not add up to the L1 fee")]
+    L1FeeSplitMismatch,
 }
 
 /// Transaction validation result
 #[derive(Debug)]
 pub struct TransactionValidation {
-    pub isValid: bool,
-    pub errors: Vec<String>,
+    pub is_valid: bool,
+    pub errors: Vec<TxValidationError>,
+}
+
+impl TransactionValidation {
+    /// Convert into a `Result`, yielding the errors if validation failed
+    pub fn into_result(self) -> Result<(), Vec<TxValidationError>> {
+        if self.is_valid {
+            Ok(())
+        } else {
+            Err(self.errors)
+        }
+    }
 }
 
 /// Simple transaction request structure
@@ -297,85 +887,556 @@ pub struct TransactionRequest {
     pub value: Option<U256>,
     pub data: Option<Vec<u8>>,
     pub gas: Option<u64>,
+    /// Legacy gas price
     pub gas_price: Option<U256>,
+    /// EIP-1559 fee cap
+    pub max_fee_per_gas: Option<U256>,
+    /// EIP-1559 priority fee
+    pub max_priority_fee_per_gas: Option<U256>,
     pub nonce: Option<u64>,
     pub chain_id: Option<u64>,
+    /// EIP-2930 access list: each address with the storage keys it pre-warms
+    pub access_list: Option<Vec<(Address, Vec<U256>)>>,
 }
 
-/// Transaction processor for 0x7e transactions
-pub struct Tx7eProcessor {
-    parser: Tx7eParser,
-}
+impl TransactionRequest {
+    /// Intrinsic gas of the request
+    ///
+    /// The base cost, per-byte calldata costs and the access list: 2400 per
+    /// address and 1900 per storage key.
+    pub fn intrinsic_gas(&self) -> u64 {
+        let data = self.data.as_deref().unwrap_or_default();
+        let access_list = self.access_list.as_deref().unwrap_or_default();
+        let storage_keys: u64 = access_list.iter().map(|(_, keys)| keys.len() as u64).sum();
 
-impl Tx7eProcessor {
-    /// Create a new processor
-    pub fn new() -> Self {
-        Self {
-            parser: Tx7eParser,
-        }
+        TX_BASE_GAS
+            + calldata_gas(data)
+            + access_list.len() as u64 * TX_ACCESS_LIST_ADDRESS_GAS
+            + storage_keys * TX_ACCESS_LIST_STORAGE_KEY_GAS
     }
 
-    /// Process a raw transaction
-    pub async fn process_transaction(&self, raw_tx: &[u8]) -> ProcessingResult {
-        // Parse the transaction
-        let tx = match self.parser.parse(raw_tx) {
-            Ok(tx) => tx,
-            Err(e) => return ProcessingResult {
-                success: false,
-                error: format!("Parsing failed: {}", e),
-                transaction: None,
-                gas_used: 0,
-                l1_cost: U256::zero(),
-            },
+    /// Convert to the JSON object shape taken by `eth_sendTransaction` and `eth_call`
+    ///
+    /// Unset fields are omitted. Storage keys are 32-byte hex data.
+    pub fn to_rpc_json(&self) -> Value {
+        let mut json = serde_json::Map::new();
+        let mut set = |key: &str, value: Option<Value>| {
+            if let Some(value) = value {
+                json.insert(key.to_string(), value);
+            }
         };
-
-        // Validate the transaction
-        let validation = self.parser.validate_transaction(&tx);
-        if !validation.isValid {
-            return ProcessingResult {
-                success: false,
-                error: format!("Validation failed: {}", validation.errors.join(", ")),
-                transaction: None,
-                gas_used: 0,
-                l1_cost: U256::zero(),
-            };
-        }
-
-        // Calculate gas usage (simplified)
-        let gas_used = self.calculate_gas_usage(&tx);
-        let l1_cost = tx.total_l1_cost();
-
-        ProcessingResult {
-            success: true,
-            error: String::new(),
-            transaction: Some(tx),
-            gas_used,
-            l1_cost,
-        }
+        set("to", self.to.map(|to| json!(to.to_string())));
+        set("value", self.value.map(|value| json!(value.to_hex_quantity())));
+        set("input", self.data.as_deref().map(|data| json!(hex_data(data))));
+        set("gas", self.gas.map(|gas| json!(u64_quantity(gas))));
+        set("gasPrice", self.gas_price.map(|price| json!(price.to_hex_quantity())));
+        set("maxFeePerGas", self.max_fee_per_gas.map(|fee| json!(fee.to_hex_quantity())));
+        set(
+            "maxPriorityFeePerGas",
+            self.max_priority_fee_per_gas.map(|fee| json!(fee.to_hex_quantity())),
+        );
+        set("nonce", self.nonce.map(|nonce| json!(u64_quantity(nonce))));
+        set("chainId", self.chain_id.map(|chain_id| json!(u64_quantity(chain_id))));
+        set(
+            "accessList",
+            self.access_list.as_ref().map(|access_list| {
+                access_list
+                    .iter()
+                    .map(|(address, keys)| {
+                        json!({
+                            "address": address.to_string(),
+                            "storageKeys": keys.iter().map(|key| hex_data(&key.to_big_endian())).collect::<Vec<_>>(),
+                        })
+                    })
+                    .collect()
+            }),
+        );
+        Value::Object(json)
     }
+}
 
-    /// Calculate gas usage for the transaction
-    fn calculate_gas_usage(&self, tx: &Tx7eTransaction) -> u64 {
-        let mut gas = 21000; // Base cost
+/// Source of account nonces for the transaction requests built by the processor
+pub trait NonceSource: Send + Sync {
+    /// Return the next nonce for the address, advancing it
+    fn next_nonce(&self, addr: &Address) -> u64;
+}
 
-        // Add cost for data
-        if !tx.data.is_empty() {
-            gas += tx.data.len() as u64 * 16; // 16 gas per byte
-        }
+/// Monotonic per-address nonce counter starting at zero
+#[derive(Debug, Default)]
+pub struct CounterNonceSource {
+    nonces: Mutex<HashMap<Address, u64>>,
+}
 
-        // Add cost for value transfer
-        if tx.value != U256::zero() {
-            gas += 9000; // Additional cost for value transfer
-        }
+impl CounterNonceSource {
+    pub fn new() -> Self {
+        Self::default()
+    }
+}
 
-        // Ensure we don't exceed the gas limit
-        gas.min(tx.gas_limit)
+/// Nonces come from the state backend, so they are shared with everything else using it
+impl NonceSource for SharedState {
+    fn next_nonce(&self, addr: &Address) -> u64 {
+        self.increment_nonce(addr)
     }
 }
 
-impl Default for Tx7eProcessor {
-    fn default() -> Self {
-        Self::new()
+impl NonceSource for CounterNonceSource {
+    fn next_nonce(&self, addr: &Address) -> u64 {
+        let mut nonces = self.nonces.lock().unwrap();
+        let nonce = nonces.entry(*addr).or_insert(0);
+        let next = *nonce;
+        *nonce += 1;
+        next
+    }
+}
+
+/// Source hashes of the deposits already applied
+///
+/// A source hash identifies the L1 event behind a deposit, so a second deposit
+/// with the same hash is a replay. Cloning the set shares it, so several
+/// processors in one session can reject each other's replays.
+#[derive(Debug, Clone, Default)]
+pub struct SeenSourceHashes {
+    hashes: Arc<Mutex<HashSet<[u8; 32]>>>,
+}
+
+impl SeenSourceHashes {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Record a source hash, returning `false` if it was already seen
+    pub fn insert(&self, source_hash: [u8; 32]) -> bool {
+        self.hashes.lock().unwrap().insert(source_hash)
+    }
+
+    /// Forget a source hash, returning `false` if it was not seen
+    pub fn remove(&self, source_hash: &[u8; 32]) -> bool {
+        self.hashes.lock().unwrap().remove(source_hash)
+    }
+
+    /// Check whether a source hash has been seen
+    pub fn contains(&self, source_hash: &[u8; 32]) -> bool {
+        self.hashes.lock().unwrap().contains(source_hash)
+    }
+
+    /// Number of seen source hashes
+    pub fn len(&self) -> usize {
+        self.hashes.lock().unwrap().len()
+    }
+
+    /// Check if no source hash has been seen
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+}
+
+/// Transaction processor for 0x7e transactions
+pub struct Tx7eProcessor {
+    parser: Mutex<Tx7eParser>,
+    config: Mutex<ArbitrumConfig>,
+    tickets: RetryableTicketStore,
+    /// Custom nonce source; `None` takes nonces from `state`
+    nonces: Option<Box<dyn NonceSource>>,
+    state: SharedState,
+    gas_pool: GasPool,
+    storage_gas: StorageGas,
+    l1_fee_pool: L1FeePool,
+    l1_base_fee_estimate: L1BaseFeeEstimate,
+    chain: MockChain,
+    seen: SeenSourceHashes,
+    metrics: Metrics,
+    chain_owners: ChainOwnerState,
+}
+
+impl Tx7eProcessor {
+    /// Create a new processor, crediting deposits to and taking nonces from an empty in-memory state
+    pub fn new() -> Self {
+        Self::new_with_nonces(Box::new(CounterNonceSource::new())).with_state_backend(InMemoryState::new().shared())
+    }
+
+    /// Create a processor that assigns request nonces from the given source
+    pub fn new_with_nonces(nonces: Box<dyn NonceSource>) -> Self {
+        Self {
+            parser: Mutex::new(Tx7eParser::new()),
+            config: Mutex::new(ArbitrumConfig::default()),
+            tickets: RetryableTicketStore::new(),
+            nonces: Some(nonces),
+            state: InMemoryState::new().shared(),
+            gas_pool: GasPool::default(),
+            storage_gas: StorageGas::default(),
+            l1_fee_pool: L1FeePool::default(),
+            l1_base_fee_estimate: L1BaseFeeEstimate::default(),
+            chain: MockChain::default(),
+            seen: SeenSourceHashes::new(),
+            metrics: Metrics::new(),
+            chain_owners: ChainOwnerState::default(),
+        }
+    }
+
+    /// Validate with the given parser
+    pub fn with_parser(self, parser: Tx7eParser) -> Self {
+        *self.parser.lock().unwrap() = parser;
+        self
+    }
+
+    /// Get a snapshot of the parser transactions are validated with
+    ///
+    /// An L2 base fee set by a chain owner replaces the parser's.
+    pub fn parser(&self) -> Tx7eParser {
+        let parser = self.parser.lock().unwrap().clone();
+        if self.chain_owners.settings().l2_base_fee.is_none() {
+            return parser;
+        }
+        parser.with_l2_base_fee(self.config().gas_price_components.effective_l2_base_fee())
+    }
+
+    /// Price the L1 share of each transaction's gas with the given configuration
+    pub fn with_config(self, config: ArbitrumConfig) -> Self {
+        *self.config.lock().unwrap() = config;
+        self
+    }
+
+    /// Get a snapshot of the configuration the L1 share of gas is priced with
+    ///
+    /// Parameters set by chain owners are applied on top.
+    pub fn config(&self) -> ArbitrumConfig {
+        let mut config = self.config.lock().unwrap().clone();
+        self.chain_owners.apply_to(&mut config);
+        config
+    }
+
+    /// Process later transactions with `config`, e.g. after it was reloaded
+    ///
+    /// The parser takes the new block gas limit and L2 base fee and keeps its size limits.
+    pub fn set_config(&self, config: &ArbitrumConfig) {
+        let mut parser = self.parser.lock().unwrap();
+        *parser = parser.clone().with_config(config);
+        *self.config.lock().unwrap() = config.clone();
+    }
+
+    /// Record retryable tickets in the given store (e.g. one shared with `ArbRetryableTx`)
+    pub fn with_retryable_tickets(mut self, tickets: RetryableTicketStore) -> Self {
+        self.tickets = tickets;
+        self
+    }
+
+    /// Get the store retryable tickets are recorded in
+    pub fn retryable_tickets(&self) -> &RetryableTicketStore {
+        &self.tickets
+    }
+
+    /// Credit deposits to and assign nonces from the given state (e.g. one shared with `ArbSys`)
+    ///
+    /// This replaces any nonce source passed to `new_with_nonces`.
+    pub fn with_state_backend(mut self, state: SharedState) -> Self {
+        self.nonces = None;
+        self.state = state;
+        self
+    }
+
+    /// Get the state deposits are credited to
+    pub fn state_backend(&self) -> &SharedState {
+        &self.state
+    }
+
+    /// Drain gas used by processed transactions from the given pool (e.g. one shared with `ArbGasInfo`)
+    pub fn with_gas_pool(mut self, gas_pool: GasPool) -> Self {
+        self.gas_pool = gas_pool;
+        self
+    }
+
+    /// Get the gas pool processed transactions drain
+    pub fn gas_pool(&self) -> &GasPool {
+        &self.gas_pool
+    }
+
+    /// Drain storage gas for deposits and tickets (e.g. one shared with `ArbSys`)
+    pub fn with_storage_gas(mut self, storage_gas: StorageGas) -> Self {
+        self.storage_gas = storage_gas;
+        self
+    }
+
+    /// Get the storage gas deposits and tickets drain
+    pub fn storage_gas(&self) -> &StorageGas {
+        &self.storage_gas
+    }
+
+    /// Collect the L1 fee of each deposit in the given pool (e.g. one shared with `ArbGasInfo`)
+    pub fn with_l1_fee_pool(mut self, l1_fee_pool: L1FeePool) -> Self {
+        self.l1_fee_pool = l1_fee_pool;
+        self
+    }
+
+    /// Get the pool deposit L1 fees are collected in
+    pub fn l1_fee_pool(&self) -> &L1FeePool {
+        &self.l1_fee_pool
+    }
+
+    /// Blend the L1 base fee of each deposit into the given estimate (e.g. one shared with `ArbGasInfo`)
+    pub fn with_l1_base_fee_estimate(mut self, l1_base_fee_estimate: L1BaseFeeEstimate) -> Self {
+        self.l1_base_fee_estimate = l1_base_fee_estimate;
+        self
+    }
+
+    /// Get the L1 base fee estimate deposits update
+    pub fn l1_base_fee_estimate(&self) -> &L1BaseFeeEstimate {
+        &self.l1_base_fee_estimate
+    }
+
+    /// Mine each processed transaction into a new block of the given chain
+    pub fn with_mock_chain(mut self, chain: MockChain) -> Self {
+        self.chain = chain;
+        self
+    }
+
+    /// Get the chain processed transactions are mined into
+    pub fn mock_chain(&self) -> &MockChain {
+        &self.chain
+    }
+
+    /// Reject deposits whose source hash is in the given set (e.g. one shared across a session)
+    pub fn with_seen_source_hashes(mut self, seen: SeenSourceHashes) -> Self {
+        self.seen = seen;
+        self
+    }
+
+    /// Get the source hashes of the deposits already processed
+    pub fn seen_source_hashes(&self) -> &SeenSourceHashes {
+        &self.seen
+    }
+
+    /// Share the gas pool, chain, storage gas, L1 fee pool, L1 base fee estimate, state and chain owners of `registry`
+    ///
+    /// Deposits then show up in what the registry's default precompiles report,
+    /// and parameters owners set through `ArbOwner` apply to deposits.
+    pub fn with_registry_state(self, registry: &PrecompileRegistry) -> Self {
+        self.with_gas_pool(registry.gas_pool().clone())
+            .with_mock_chain(registry.mock_chain().clone())
+            .with_storage_gas(registry.storage_gas().clone())
+            .with_l1_fee_pool(registry.l1_fee_pool().clone())
+            .with_l1_base_fee_estimate(registry.l1_base_fee_estimate().clone())
+            .with_state_backend(registry.state_backend().clone())
+            .with_chain_owners(registry.chain_owners().clone())
+    }
+
+    /// Apply the parameters set by the given chain owners (e.g. ones shared with `ArbOwner`)
+    pub fn with_chain_owners(mut self, chain_owners: ChainOwnerState) -> Self {
+        self.chain_owners = chain_owners;
+        self
+    }
+
+    /// Get the chain owners whose parameters are applied
+    pub fn chain_owners(&self) -> &ChainOwnerState {
+        &self.chain_owners
+    }
+
+    /// Count processed transactions in the given metrics (e.g. ones shared with the registry)
+    pub fn with_metrics(mut self, metrics: Metrics) -> Self {
+        self.metrics = metrics;
+        self
+    }
+
+    /// Get the metrics processed transactions are counted in
+    pub fn metrics(&self) -> &Metrics {
+        &self.metrics
+    }
+
+    /// Process a raw submit-retryable transaction, creating its ticket
+    ///
+    /// Returns the id of the created ticket.
+    pub fn process_retryable(&self, raw_tx: &[u8]) -> Result<[u8; 32]> {
+        match self.parser().parse_variant(raw_tx)? {
+            Tx7eVariant::SubmitRetryable(tx) => {
+                let ticket = tx.to_ticket(self.chain.timestamp());
+                let id = ticket.id;
+                self.tickets.insert(ticket);
+                self.storage_gas.consume(STORAGE_WRITE_GAS);
+                Ok(id)
+            }
+            Tx7eVariant::Deposit(_) => Err(TxError::ExpectedSubmitRetryable),
+        }
+    }
+
+    /// Process a raw transaction
+    pub async fn process_transaction(&self, raw_tx: &[u8]) -> ProcessingResult {
+        let result = self.process(raw_tx);
+        self.metrics.record_transaction(result.success);
+        self.log_processed(raw_tx, &result);
+        result
+    }
+
+    /// Emit one structured event describing a processed transaction
+    ///
+    /// The hash is `Tx7eTransaction::hash` of the decoded transaction, even if it
+    /// was rejected. Only payloads that do not decode are logged with the keccak
+    /// of their raw bytes and a `chain_id` of 0.
+    fn log_processed(&self, raw_tx: &[u8], result: &ProcessingResult) {
+        let parsed = match &result.transaction {
+            Some(_) => None,
+            None => self.parser().parse(raw_tx).ok(),
+        };
+        let (hash, chain_id) = match result.transaction.as_ref().or(parsed.as_ref()) {
+            Some(tx) => (tx.hash(), tx.chain_id),
+            None => (Keccak256::digest(raw_tx).into(), 0),
+        };
+        info!(
+            hash = %HexBytes(hash),
+            chain_id,
+            gas_used = result.gas_used,
+            l1_gas_used = result.l1_gas_used,
+            l2_gas_used = result.l2_gas_used,
+            l1_cost = %result.l1_cost,
+            success = result.success,
+            "Processed 0x7e transaction"
+        );
+    }
+
+    fn process(&self, raw_tx: &[u8]) -> ProcessingResult {
+        // Every step of one transaction sees the same configuration
+        let parser = self.parser();
+        let config = self.config();
+
+        // Parse the transaction
+        let tx = match parser.parse(raw_tx) {
+            Ok(tx) => tx,
+            Err(e) => return ProcessingResult::failure(format!("Parsing failed: {}", e)),
+        };
+
+        // Validate the transaction
+        let validation = parser.validate_transaction(&tx);
+        if !validation.is_valid {
+            return ProcessingResult::failure(format!(
+                "Validation failed: {}",
+                validation
+                    .errors
+                    .iter()
+                    .map(|e| e.to_string())
+                    .collect::<Vec<_>>()
+                    .join(", ")
+            ));
+        }
+
+        // Calculate gas usage (simplified), split into the L1 calldata share and L2 execution
+        let gas_used = self.calculate_gas_usage(&tx);
+        let l1_gas_used = config.calculate_l1_gas_cost(&tx.data);
+        if l1_gas_used > gas_used {
+            return ProcessingResult::failure(format!(
+                "L1 gas {} exceeds the {} gas the transaction uses",
+                l1_gas_used, gas_used
+            ));
+        }
+
+        // Reject replays of an L1 event that was already applied
+        if !self.seen.insert(tx.source_hash) {
+            return ProcessingResult::failure(format!(
+                "Deposit with source hash {} already processed",
+                HexBytes(tx.source_hash)
+            ));
+        }
+
+        let l1_cost = tx.total_l1_cost();
+        let nonce = match self.apply(&tx, gas_used, &config) {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                // A deposit that was rolled back can be delivered again
+                self.seen.remove(&tx.source_hash);
+                return ProcessingResult::failure(format!("Execution failed: {}", e));
+            }
+        };
+
+        // Build the equivalent request with the nonce consumed for the target
+        let mut request = parser.to_transaction_request(&tx);
+        request.nonce = Some(nonce);
+
+        ProcessingResult {
+            success: true,
+            error: String::new(),
+            request: Some(request),
+            receipt: Some(tx.encode_for_receipt(
+                true,
+                gas_used,
+                l1_gas_used,
+                U256::from_u64(parser.l2_base_fee()),
+                vec![deposit_log(&tx)],
+            )),
+            transaction: Some(tx),
+            gas_used,
+            l1_gas_used,
+            l2_gas_used: gas_used - l1_gas_used,
+            l1_cost,
+        }
+    }
+
+    /// Apply every effect of a deposit, or none of them
+    ///
+    /// Account changes are staged in a `StateJournal` and only committed if all
+    /// of them succeed. The pools, L1 base fee estimate and chain are only updated once
+    /// they all succeeded. The gas pool first regains the gas for the time since
+    /// the previous block.
+    /// Returns the nonce consumed for the target.
+    fn apply(&self, tx: &Tx7eTransaction, gas_used: u64, config: &ArbitrumConfig) -> Result<u64, StateError> {
+        let mut journal = StateJournal::new(self.state.as_ref());
+        let nonce = match self.apply_to_accounts(&mut journal, tx) {
+            Ok(nonce) => nonce,
+            Err(e) => {
+                journal.rollback();
+                return Err(e);
+            }
+        };
+        journal.commit();
+        self.gas_pool.refill_at(self.chain.timestamp());
+        self.gas_pool.consume(gas_used);
+        self.storage_gas.consume(STORAGE_WRITE_GAS);
+        self.l1_fee_pool.add(tx.l1_fee);
+        let observed = decode_u64_word(&tx.l1_base_fee.to_big_endian()).unwrap_or(u64::MAX);
+        self.l1_base_fee_estimate.observe(observed, config.l1_base_fee);
+        self.chain.advance();
+        Ok(nonce)
+    }
+
+    /// Credit the deposit value, then consume the target's nonce
+    fn apply_to_accounts(&self, journal: &mut StateJournal<'_>, tx: &Tx7eTransaction) -> Result<u64, StateError> {
+        let (target, value) = tx.credit_target();
+        journal.credit(&target, value)?;
+        match &self.nonces {
+            Some(nonces) => Ok(nonces.next_nonce(&tx.target)),
+            None => journal.increment_nonce(&tx.target),
+        }
+    }
+
+    /// Calculate gas usage for the transaction
+    fn calculate_gas_usage(&self, tx: &Tx7eTransaction) -> u64 {
+        // Ensure we don't exceed the gas limit
+        tx.intrinsic_gas().min(tx.gas_limit)
+    }
+}
+
+/// Signature of the event logged for every applied deposit
+pub const DEPOSIT_FINALIZED_EVENT: &str = "DepositFinalized(bytes32,address,uint256)";
+
+/// Log recorded in a deposit's receipt, emitted from ArbSys (0x64)
+///
+/// `DepositFinalized(bytes32 indexed sourceHash, address indexed to, uint256 value)`
+/// is a mock event so receipts show the credit; Nitro emits no log for deposits.
+fn deposit_log(tx: &Tx7eTransaction) -> PrecompileLog {
+    let (target, value) = tx.credit_target();
+    let mut target_word = [0u8; 32];
+    target_word[12..].copy_from_slice(target.as_bytes());
+    PrecompileLog {
+        address: Address::from_hex("0x0000000000000000000000000000000000000064").unwrap(),
+        topics: vec![
+            Keccak256::digest(DEPOSIT_FINALIZED_EVENT.as_bytes()).into(),
+            tx.source_hash,
+            target_word,
+        ],
+        data: value.to_big_endian().to_vec(),
+    }
+}
+
+impl Default for Tx7eProcessor {
+    fn default() -> Self {
+        Self::new()
     }
 }
 
@@ -385,10 +1446,152 @@ pub struct ProcessingResult {
     pub success: bool,
     pub error: String,
     pub transaction: Option<Tx7eTransaction>,
+    pub request: Option<TransactionRequest>,
+    pub receipt: Option<Receipt>,
     pub gas_used: u64,
+    /// Share of `gas_used` paying for the calldata posted to L1 (`ArbitrumConfig::calculate_l1_gas_cost`)
+    pub l1_gas_used: u64,
+    /// Share of `gas_used` left for L2 execution
+    pub l2_gas_used: u64,
     pub l1_cost: U256,
 }
 
+impl ProcessingResult {
+    /// Create a failed result with the given error
+    fn failure(error: String) -> Self {
+        Self {
+            success: false,
+            error,
+            transaction: None,
+            request: None,
+            receipt: None,
+            gas_used: 0,
+            l1_gas_used: 0,
+            l2_gas_used: 0,
+            l1_cost: U256::zero(),
+        }
+    }
+}
+
+/// Receipt of an executed 0x7e deposit, with Arbitrum's L1 extensions
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Receipt {
+    pub transaction_hash: [u8; 32],
+    pub status: bool,
+    pub gas_used: u64,
+    pub cumulative_gas_used: u64,
+    /// L2 gas price paid, in wei
+    pub effective_gas_price: U256,
+    /// L1 fee in wei
+    pub l1_fee: U256,
+    /// Share of the L1 fee paid to the batch poster, if the deposit split it
+    pub l1_poster_fee: Option<U256>,
+    /// Share of the L1 fee paid to the network fee account, if the deposit split it
+    pub l1_network_fee: Option<U256>,
+    /// L1 gas charged for posting the transaction (`gasUsedForL1`)
+    pub gas_used_for_l1: u64,
+    /// L1 block the deposit originated in
+    pub l1_block_number: u64,
+    pub logs: Vec<PrecompileLog>,
+}
+
+impl Receipt {
+    /// Bloom filter over the addresses and topics of `logs`
+    pub fn logs_bloom(&self) -> [u8; 256] {
+        let mut bloom = [0u8; 256];
+        for log in &self.logs {
+            let items = std::iter::once(log.address.as_bytes().as_slice()).chain(log.topics.iter().map(|t| t.as_slice()));
+            for item in items {
+                let hash = Keccak256::digest(item);
+                for i in [0, 2, 4] {
+                    let bit = (u16::from_be_bytes([hash[i], hash[i + 1]]) & 0x7ff) as usize;
+                    bloom[255 - bit / 8] |= 1 << (bit % 8);
+                }
+            }
+        }
+        bloom
+    }
+
+    /// Typed consensus receipt encoding: `0x7e || rlp([status, cumulativeGasUsed, logsBloom, logs])`
+    ///
+    /// The Arbitrum extensions are RPC-only and appear in `to_rpc_json`.
+    pub fn rlp_encode(&self) -> Vec<u8> {
+        let mut out = vec![TX_TYPE_0X7E];
+        out.extend_from_slice(&rlp::encode(self));
+        out
+    }
+
+    /// Convert to the JSON object shape returned by `eth_getTransactionReceipt`
+    ///
+    /// Block fields are left to the caller; `gasUsedForL1`, `l1BlockNumber` and
+    /// `l1Fee` follow the Arbitrum receipt extensions, with `l1PosterFee` and
+    /// `l1NetworkFee` added when the deposit split its L1 fee.
+    pub fn to_rpc_json(&self) -> Value {
+        let logs: Vec<Value> = self
+            .logs
+            .iter()
+            .enumerate()
+            .map(|(index, log)| {
+                json!({
+                    "address": log.address.to_string(),
+                    "topics": log.topics.iter().map(|topic| hex_data(topic)).collect::<Vec<_>>(),
+                    "data": hex_data(&log.data),
+                    "logIndex": u64_quantity(index as u64),
+                    "transactionHash": hex_data(&self.transaction_hash),
+                })
+            })
+            .collect();
+        let mut json = json!({
+            "transactionHash": hex_data(&self.transaction_hash),
+            "type": format!("{:#04x}", TX_TYPE_0X7E),
+            "status": u64_quantity(self.status as u64),
+            "gasUsed": u64_quantity(self.gas_used),
+            "cumulativeGasUsed": u64_quantity(self.cumulative_gas_used),
+            "effectiveGasPrice": self.effective_gas_price.to_hex_quantity(),
+            "logs": logs,
+            "logsBloom": hex_data(&self.logs_bloom()),
+            "gasUsedForL1": u64_quantity(self.gas_used_for_l1),
+            "l1BlockNumber": u64_quantity(self.l1_block_number),
+            "l1Fee": self.l1_fee.to_hex_quantity(),
+        });
+        if let (Some(poster_fee), Some(network_fee)) = (self.l1_poster_fee, self.l1_network_fee) {
+            json["l1PosterFee"] = json!(poster_fee.to_hex_quantity());
+            json["l1NetworkFee"] = json!(network_fee.to_hex_quantity());
+        }
+        json
+    }
+}
+
+impl Encodable for Receipt {
+    fn rlp_append(&self, s: &mut RlpStream) {
+        s.begin_list(4);
+        s.append(&(self.status as u8));
+        s.append(&self.cumulative_gas_used);
+        s.append(&self.logs_bloom().to_vec());
+        s.begin_list(self.logs.len());
+        for log in &self.logs {
+            s.begin_list(3);
+            s.append(&log.address.as_bytes().to_vec());
+            s.begin_list(log.topics.len());
+            for topic in &log.topics {
+                s.append(&topic.to_vec());
+            }
+            s.append(&log.data);
+        }
+    }
+}
+
+/// Builder for a valid test deposit of 1,000 wei to `0x1234…7890`, identified by `source_hash`
+#[cfg(test)]
+pub(crate) fn mock_deposit(source_hash: [u8; 32]) -> Tx7eTransactionBuilder {
+    Tx7eTransaction::builder()
+        .with_target(Address::from_hex("0x1234567890123456789012345678901234567890").unwrap())
+        .with_value(U256::from_u64(1_000))
+        .with_l1_block_number(12345)
+        .with_l1_timestamp(1640995200)
+        .with_source_hash(source_hash)
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
@@ -411,6 +1614,24 @@ mod tests {
         )
     }
 
+    fn create_mock_retryable() -> Tx7eRetryableTransaction {
+        Tx7eRetryableTransaction {
+            chain_id: 42161,
+            request_id: [2u8; 32],
+            from: Address::from_hex("0x1111111111111111111111111111111111111111").unwrap(),
+            l1_base_fee: U256::from_u64(20000000000),
+            deposit_value: U256::from_u64(2000000000000000000),
+            gas_fee_cap: U256::from_u64(100000000),
+            gas_limit: 200000,
+            retry_to: Address::from_hex("0x1234567890123456789012345678901234567890").unwrap(),
+            retry_value: U256::from_u64(1000000000000000000),
+            beneficiary: Address::from_hex("0xabcdefabcdefabcdefabcdefabcdefabcdefabcd").unwrap(),
+            max_submission_fee: U256::from_u64(500000000000000),
+            fee_refund_address: Address::from_hex("0xabcdefabcdefabcdefabcdefabcdefabcdefabcd").unwrap(),
+            retry_data: vec![0xde, 0xad, 0xbe, 0xef],
+        }
+    }
+
     #[test]
     fn test_transaction_creation() {
         let tx = create_mock_transaction();
@@ -419,6 +1640,19 @@ mod tests {
         assert_eq!(tx.l1_block_number, 12345);
     }
 
+    #[test]
+    fn test_deposit_value_credit() {
+        let tx = create_mock_transaction();
+
+        let (recipient, amount) = tx.credit_target();
+        assert_eq!(recipient, tx.target);
+        assert_eq!(amount, U256::from_u64(1000000000000000000));
+
+        let (recipient, amount) = tx.refund_recipient();
+        assert_eq!(recipient, tx.refund_address);
+        assert_eq!(amount, tx.value);
+    }
+
     #[test]
     fn test_transaction_encoding_decoding() {
         let tx = create_mock_transaction();
@@ -427,29 +1661,447 @@ mod tests {
         assert_eq!(tx, decoded);
     }
 
+    #[test]
+    fn test_single_fee_deposit_decodes_without_split() {
+        let tx = create_mock_transaction();
+        let encoded = tx.rlp_encode();
+        assert_eq!(Rlp::new(&encoded).item_count().unwrap(), DEPOSIT_FIELDS_SINGLE_FEE);
+
+        let decoded = Tx7eParser::new().parse(&tx.rlp_encode_typed()).unwrap();
+        assert_eq!(decoded.l1_fee, U256::from_u64(1_000_000_000_000_000));
+        assert_eq!(decoded.l1_fee_split(), None);
+        let json = decoded.encode_for_receipt(true, 21000, 0, U256::zero(), Vec::new()).to_rpc_json();
+        assert!(json.get("l1PosterFee").is_none());
+        assert!(json.get("l1NetworkFee").is_none());
+    }
+
+    #[test]
+    fn test_split_fee_deposit_round_trips() {
+        let poster_fee = U256::from_u64(600_000_000_000_000);
+        let network_fee = U256::from_u64(400_000_000_000_000);
+        let tx = create_mock_transaction().with_l1_fee_split(poster_fee, network_fee).unwrap();
+        assert_eq!(tx.l1_fee, U256::from_u64(1_000_000_000_000_000));
+        assert!(tx.validate().is_empty());
+        assert_eq!(Rlp::new(&tx.rlp_encode()).item_count().unwrap(), DEPOSIT_FIELDS_SPLIT_FEE);
+
+        let parser = Tx7eParser::new();
+        let decoded = parser.parse(&tx.rlp_encode_typed()).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded.l1_fee_split(), Some((poster_fee, network_fee)));
+        assert_eq!(Tx7eTransaction::from_rpc_json(&tx.to_rpc_json()).unwrap(), tx);
+
+        let json = decoded.encode_for_receipt(true, 21000, 0, U256::zero(), Vec::new()).to_rpc_json();
+        assert_eq!(json["l1Fee"], "0x38d7ea4c68000");
+        assert_eq!(json["l1PosterFee"], poster_fee.to_hex_quantity());
+        assert_eq!(json["l1NetworkFee"], network_fee.to_hex_quantity());
+
+        // A hand-built encoding whose split does not add up to the L1 fee is rejected
+        let fields = tx.rlp_encode();
+        let fields = Rlp::new(&fields);
+        let mut stream = RlpStream::new_list(DEPOSIT_FIELDS_SPLIT_FEE);
+        for index in 0..DEPOSIT_FIELDS_SPLIT_FEE {
+            if index == 10 {
+                stream.append(&1u8);
+            } else {
+                stream.append_raw(fields.at(index).unwrap().as_raw(), 1);
+            }
+        }
+        let mut raw_tx = vec![TX_TYPE_0X7E];
+        raw_tx.extend_from_slice(&stream.out());
+        assert_eq!(
+            parser.parse(&raw_tx).unwrap_err(),
+            TxError::Rlp(DecoderError::Custom("L1 fee is not the sum of the poster and network fees"))
+        );
+    }
+
+    #[test]
+    fn test_l1_fee_split_must_add_up() {
+        let max = U256::new([0xff; 32]);
+        assert!(create_mock_transaction().with_l1_fee_split(max, U256::from_u64(1)).is_none());
+
+        let builder = mock_deposit([1u8; 32]);
+        assert_eq!(
+            builder.clone().with_l1_fee_split(max, U256::from_u64(1)).build(),
+            Err(TxValidationError::L1FeeSplitMismatch)
+        );
+        let tx = builder
+            .with_l1_fee_split(U256::from_u64(600), U256::from_u64(400))
+            .build()
+            .unwrap();
+        assert_eq!(tx.l1_fee, U256::from_u64(1_000));
+
+        // Changing the fee after the split, or setting a lone share, fails validation
+        let mut mismatched = tx.clone();
+        mismatched.l1_fee = U256::from_u64(1);
+        assert_eq!(mismatched.validate(), vec![TxValidationError::L1FeeSplitMismatch]);
+        assert!(!Tx7eParser::new().validate_transaction(&mismatched).is_valid);
+        let mut lone = tx.clone();
+        lone.l1_network_fee = None;
+        assert_eq!(lone.validate(), vec![TxValidationError::IncompleteL1FeeSplit]);
+
+        // The JSON shape enforces the same sum
+        let mut json = tx.to_rpc_json();
+        json["l1Fee"] = json!("0x1");
+        assert!(matches!(
+            Tx7eTransaction::from_rpc_json(&json),
+            Err(TxError::InvalidJsonField { field: "l1Fee", .. })
+        ));
+    }
+
+    #[test]
+    fn test_typed_encoding_round_trips_through_parse() {
+        let tx = create_mock_transaction();
+        let raw_tx = tx.rlp_encode_typed();
+        assert_eq!(raw_tx[0], TX_TYPE_0X7E);
+        assert_eq!(&raw_tx[1..], tx.rlp_encode().as_slice());
+        assert_eq!(Tx7eParser::new().parse(&raw_tx).unwrap(), tx);
+    }
+
+    #[test]
+    fn test_retryable_encoding_decoding() {
+        let tx = create_mock_retryable();
+        let encoded = tx.rlp_encode();
+        let decoded = Tx7eRetryableTransaction::decode(&Rlp::new(&encoded)).unwrap();
+        assert_eq!(tx, decoded);
+    }
+
+    #[test]
+    fn test_parse_variant_dispatch() {
+        let parser = Tx7eParser::new();
+
+        let retryable = create_mock_retryable();
+        let parsed = parser.parse_variant(&retryable.rlp_encode_typed()).unwrap();
+        assert_eq!(parsed, Tx7eVariant::SubmitRetryable(retryable.clone()));
+
+        let deposit = create_mock_transaction();
+        let parsed = parser.parse_variant(&deposit.rlp_encode_typed()).unwrap();
+        assert_eq!(parsed, Tx7eVariant::Deposit(deposit));
+
+        // parse() only accepts deposits
+        let result = parser.parse(&retryable.rlp_encode_typed());
+        assert_eq!(result.unwrap_err(), TxError::ExpectedDeposit);
+    }
+
+    #[test]
+    fn test_process_retryable_creates_ticket() {
+        let processor = Tx7eProcessor::new();
+        let retryable = create_mock_retryable();
+
+        let id = processor.process_retryable(&retryable.rlp_encode_typed()).unwrap();
+        assert_eq!(id, retryable.ticket_id());
+
+        let ticket = processor.retryable_tickets().get(&id).unwrap();
+        assert_eq!(ticket.beneficiary, retryable.beneficiary);
+        assert_eq!(ticket.retry_to, retryable.retry_to);
+        assert_eq!(ticket.retry_data, retryable.retry_data);
+    }
+
+    #[test]
+    fn test_zero_value_and_leading_zero_address_round_trip() {
+        let mut tx = create_mock_transaction();
+        tx.value = U256::zero();
+        tx.l1_fee = U256::zero();
+        tx.target = Address::from_hex("0x0000c0ffee000000000000000000000000000001").unwrap();
+
+        let encoded = tx.rlp_encode();
+        let decoded = Tx7eTransaction::decode(&Rlp::new(&encoded)).unwrap();
+        assert_eq!(decoded, tx);
+        assert_eq!(decoded.target.as_bytes()[0], 0x00);
+    }
+
+    #[test]
+    fn test_decode_minimal_integer_encoding() {
+        let tx = create_mock_transaction();
+
+        // Encode the U256 fields the way integer RLP encoders do: zero is the empty string
+        let mut stream = RlpStream::new_list(13);
+        stream.append(&tx.chain_id);
+        stream.append(&tx.target.as_bytes().to_vec());
+        stream.append_empty_data(); // value
+        stream.append(&tx.data);
+        stream.append(&tx.gas_limit);
+        stream.append(&tx.l1_block_number);
+        stream.append(&tx.l1_timestamp);
+        stream.append_empty_data(); // l1_base_fee
+        stream.append(&25000000000u64); // l1_gas_price
+        stream.append(&tx.l1_gas_used);
+        stream.append_empty_data(); // l1_fee
+        stream.append(&tx.refund_address.as_bytes().to_vec());
+        stream.append(&tx.source_hash.to_vec());
+
+        let decoded = Tx7eTransaction::decode(&Rlp::new(&stream.out())).unwrap();
+        assert_eq!(decoded.value, U256::zero());
+        assert_eq!(decoded.l1_base_fee, U256::zero());
+        assert_eq!(decoded.l1_fee, U256::zero());
+        assert_eq!(decoded.l1_gas_price, tx.l1_gas_price);
+    }
+
+    #[test]
+    fn test_u256_fields_encode_as_minimal_integers() {
+        let mut tx = create_mock_transaction();
+        tx.l1_fee = U256::zero();
+
+        // The same stream as above, with u64 values encoded by the rlp crate as geth would
+        let mut stream = RlpStream::new_list(13);
+        stream.append(&tx.chain_id);
+        stream.append(&tx.target.as_bytes().to_vec());
+        stream.append(&1_000_000_000_000_000_000u64); // value
+        stream.append(&tx.data);
+        stream.append(&tx.gas_limit);
+        stream.append(&tx.l1_block_number);
+        stream.append(&tx.l1_timestamp);
+        stream.append(&20_000_000_000u64); // l1_base_fee
+        stream.append(&25_000_000_000u64); // l1_gas_price
+        stream.append(&tx.l1_gas_used);
+        stream.append_empty_data(); // l1_fee
+        stream.append(&tx.refund_address.as_bytes().to_vec());
+        stream.append(&tx.source_hash.to_vec());
+        assert_eq!(tx.rlp_encode(), stream.out().to_vec());
+
+        // 1 ETH is the 8-byte string 0x0de0b6b3a7640000
+        let value = Rlp::new(&tx.rlp_encode()).at(2).unwrap().as_raw().to_vec();
+        assert_eq!(value, hex::decode("880de0b6b3a7640000").unwrap());
+    }
+
+    #[test]
+    fn test_decode_truncated_input_never_panics() {
+        let encoded = create_mock_transaction().rlp_encode();
+
+        // Every strict prefix of a valid encoding must be rejected
+        for len in 0..encoded.len() {
+            let result = Tx7eTransaction::decode(&Rlp::new(&encoded[..len]));
+            assert!(result.is_err(), "prefix of length {} decoded", len);
+        }
+
+        // Corrupt single bytes of the valid encoding
+        for i in 0..encoded.len() {
+            let mut corrupted = encoded.clone();
+            corrupted[i] ^= 0xff;
+            let _ = Tx7eTransaction::decode(&Rlp::new(&corrupted));
+        }
+
+        // Pseudo-random byte strings (deterministic LCG so failures are reproducible)
+        let mut state: u64 = 0x2545_f491_4f6c_dd1d;
+        for _ in 0..2000 {
+            state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+            let len = (state >> 56) as usize;
+            let bytes: Vec<u8> = (0..len)
+                .map(|_| {
+                    state = state.wrapping_mul(6364136223846793005).wrapping_add(1442695040888963407);
+                    (state >> 33) as u8
+                })
+                .collect();
+            assert!(Tx7eTransaction::decode(&Rlp::new(&bytes)).is_err());
+        }
+    }
+
+    #[test]
+    fn test_decode_rejects_wrong_field_widths() {
+        let tx = create_mock_transaction();
+        let mut stream = RlpStream::new_list(13);
+        stream.append(&tx.chain_id);
+        stream.append(&vec![0x12u8; 19]); // 19-byte target
+        for _ in 2..13 {
+            stream.append(&0u8);
+        }
+        let result = Tx7eTransaction::decode(&Rlp::new(&stream.out()));
+        assert_eq!(result, Err(DecoderError::Custom("Invalid target address length")));
+    }
+
     #[test]
     fn test_transaction_validation() {
-        let parser = Tx7eParser;
+        let parser = Tx7eParser::new();
         let tx = create_mock_transaction();
         let validation = parser.validate_transaction(&tx);
-        assert!(validation.isValid);
+        assert!(validation.is_valid);
         assert!(validation.errors.is_empty());
     }
 
     #[test]
     fn test_transaction_validation_errors() {
-        let parser = Tx7eParser;
+        let parser = Tx7eParser::new();
         let mut tx = create_mock_transaction();
         tx.chain_id = 0; // Invalid chain ID
         
         let validation = parser.validate_transaction(&tx);
-        assert!(!validation.isValid);
-        assert!(validation.errors.iter().any(|e| e.contains("chain ID")));
+        assert!(!validation.is_valid);
+        assert_eq!(validation.errors, vec![TxValidationError::ZeroChainId]);
+        assert_eq!(validation.errors[0].to_string(), "Invalid chain ID: cannot be zero");
+    }
+
+    #[test]
+    fn test_builder_builds_valid_transaction() {
+        let tx = mock_deposit([1u8; 32]).with_data(vec![0x00, 0x01]).build().unwrap();
+        let target = tx.target;
+
+        assert_eq!(tx.chain_id, 42161);
+        assert_eq!(tx.refund_address, target);
+        assert_eq!(tx.l1_gas_price, tx.l1_base_fee);
+        assert_eq!(tx.gas_limit, TX_BASE_GAS + TX_DATA_ZERO_GAS + TX_DATA_NON_ZERO_GAS);
+        assert!(Tx7eParser::new().validate_transaction(&tx).is_valid);
+
+        let tx = Tx7eTransaction::builder()
+            .with_target(target)
+            .with_gas_limit(100_000)
+            .with_l1_block_number(1)
+            .with_l1_timestamp(1)
+            .with_source_hash([1u8; 32])
+            .build()
+            .unwrap();
+        assert_eq!(tx.gas_limit, 100_000);
+    }
+
+    #[test]
+    fn test_builder_rejects_invalid_transaction() {
+        let builder = mock_deposit([0u8; 32]);
+
+        assert_eq!(builder.clone().build(), Err(TxValidationError::ZeroSourceHash));
+        assert_eq!(
+            builder.with_source_hash([1u8; 32]).with_chain_id(0).build(),
+            Err(TxValidationError::ZeroChainId)
+        );
+    }
+
+    #[test]
+    fn test_transaction_validate_reports_each_zero_field() {
+        let mut tx = create_mock_transaction();
+        tx.target = Address::ZERO;
+        tx.gas_limit = 0;
+        tx.l1_block_number = 0;
+
+        assert_eq!(
+            tx.validate(),
+            vec![
+                TxValidationError::ZeroTarget,
+                TxValidationError::ZeroGasLimit,
+                TxValidationError::ZeroL1BlockNumber,
+            ]
+        );
+        assert!(create_mock_transaction().validate().is_empty());
+    }
+
+    #[test]
+    fn test_validation_into_result() {
+        let parser = Tx7eParser::new();
+        assert!(parser.validate_transaction(&create_mock_transaction()).into_result().is_ok());
+
+        let mut tx = create_mock_transaction();
+        tx.source_hash = [0u8; 32];
+        let errors = parser.validate_transaction(&tx).into_result().unwrap_err();
+        assert_eq!(errors, vec![TxValidationError::ZeroSourceHash]);
+    }
+
+    #[test]
+    fn test_oversized_calldata_prefix_rejected_early() {
+        // chain_id, target and value, then a calldata header claiming 99,000
+        // bytes inside a list claiming 100,000, with neither actually present
+        let mut fields = rlp::encode(&42161u64).to_vec();
+        fields.push(0x94);
+        fields.extend_from_slice(&[0x11; 20]);
+        fields.push(0x80);
+        fields.extend_from_slice(&[0xba, 0x01, 0x82, 0xb8]);
+
+        let mut raw_tx = vec![TX_TYPE_0X7E, 0xfa, 0x01, 0x86, 0xa0];
+        raw_tx.extend_from_slice(&fields);
+
+        let err = Tx7eParser::new().parse(&raw_tx).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Calldata length 99000 exceeds max calldata length 95000"
+        );
+
+        // With the limit raised the truncated payload fails the full decode instead
+        let parser = Tx7eParser::new().with_max_calldata_len(100_000);
+        assert!(matches!(parser.parse(&raw_tx), Err(TxError::Rlp(_))));
+    }
+
+    #[test]
+    fn test_field_header_declaring_usize_max_is_rejected() {
+        // The chain ID header claims 2^64 - 1 bytes; summing it with its own
+        // header length must not overflow while skipping to the calldata
+        let mut raw_tx = vec![TX_TYPE_0X7E, 0xc9, 0xbf];
+        raw_tx.extend_from_slice(&[0xff; 8]);
+        assert!(Tx7eParser::new().parse(&raw_tx).is_err());
+    }
+
+    #[test]
+    fn test_list_header_declaring_usize_max_is_rejected() {
+        // The list header claims 2^64 - 1 bytes; adding the type prefix must
+        // saturate rather than overflow, for deposits and submit-retryables alike
+        let mut list = vec![0xff];
+        list.extend_from_slice(&[0xff; 8]);
+        for prefix in [vec![TX_TYPE_0X7E], vec![TX_TYPE_0X7E, TX_SUBTYPE_SUBMIT_RETRYABLE]] {
+            let mut raw_tx = prefix;
+            raw_tx.extend_from_slice(&list);
+            assert!(matches!(
+                Tx7eParser::new().parse_variant(&raw_tx),
+                Err(TxError::DeclaredTooLarge { declared: usize::MAX, .. })
+            ));
+        }
+    }
+
+    #[test]
+    fn test_oversized_tx_rejected() {
+        // A list header claiming 16 MiB is rejected from the header alone
+        let raw_tx = [TX_TYPE_0X7E, 0xfb, 0x01, 0x00, 0x00, 0x00, 0xc0];
+        let err = Tx7eParser::new().parse(&raw_tx).unwrap_err();
+        assert_eq!(
+            err.to_string(),
+            "Transaction declares 16777222 bytes, exceeding max tx size 131072"
+        );
+
+        let raw_tx = create_mock_transaction().rlp_encode_typed();
+        let parser = Tx7eParser::new().with_max_tx_size(raw_tx.len() - 1);
+        assert!(parser.parse(&raw_tx).unwrap_err().to_string().contains("exceeds max tx size"));
+        assert!(parser.with_max_tx_size(raw_tx.len()).parse(&raw_tx).is_ok());
+
+        let parser = Tx7eParser::new().with_max_calldata_len(0);
+        assert!(parser.parse(&raw_tx).unwrap_err().to_string().contains("max calldata length 0"));
+    }
+
+    #[test]
+    fn test_gas_limit_above_block_limit_rejected() {
+        let parser = Tx7eParser::new().with_max_gas_limit(100_000);
+        let mut tx = create_mock_transaction();
+        tx.gas_limit = 100_001;
+
+        let validation = parser.validate_transaction(&tx);
+        assert!(!validation.is_valid);
+        assert_eq!(
+            validation.errors,
+            vec![TxValidationError::GasLimitExceedsBlockLimit {
+                gas_limit: 100_001,
+                max_gas_limit: 100_000,
+            }]
+        );
+    }
+
+    #[test]
+    fn test_parser_from_config_uses_block_gas_limit() {
+        let config = ArbitrumConfig {
+            l2_block_gas_limit: 50_000,
+            ..Default::default()
+        };
+        let parser = Tx7eParser::from_config(&config);
+        assert_eq!(parser.max_gas_limit(), 50_000);
+
+        let validation = parser.validate_transaction(&create_mock_transaction());
+        assert!(!validation.is_valid);
+    }
+
+    #[test]
+    fn test_gas_limit_at_block_limit_accepted() {
+        let parser = Tx7eParser::new().with_max_gas_limit(100_000);
+        let tx = create_mock_transaction();
+        assert_eq!(tx.gas_limit, 100_000);
+
+        let validation = parser.validate_transaction(&tx);
+        assert!(validation.is_valid);
     }
 
     #[test]
     fn test_transaction_parsing() {
-        let parser = Tx7eParser;
+        let parser = Tx7eParser::new();
         let tx = create_mock_transaction();
         let encoded = tx.rlp_encode();
         let mut raw_tx = vec![TX_TYPE_0X7E];
@@ -459,9 +2111,30 @@ mod tests {
         assert_eq!(tx, parsed);
     }
 
+    #[test]
+    fn test_batch_parsing_localizes_errors() {
+        let parser = Tx7eParser::new();
+        let first = create_mock_transaction();
+        let mut third = create_mock_transaction();
+        third.l1_block_number = 12346;
+
+        let raw_first = first.rlp_encode_typed();
+        let raw_malformed = vec![TX_TYPE_0X7E, 0xc3, 0x01];
+        let raw_third = third.rlp_encode_typed();
+
+        let batch = Tx7eParser::encode_batch(&[raw_first, raw_malformed, raw_third]);
+        let results = parser.parse_batch(&batch);
+
+        assert_eq!(results.len(), 3);
+        assert_eq!(results[0].as_ref().unwrap(), &first);
+        assert!(results[1].as_ref().unwrap_err().to_string().contains("Batch entry 1"));
+        assert!(matches!(results[1], Err(TxError::BatchEntry { index: 1, .. })));
+        assert_eq!(results[2].as_ref().unwrap(), &third);
+    }
+
     #[test]
     fn test_transaction_parsing_invalid_type() {
-        let parser = Tx7eParser;
+        let parser = Tx7eParser::new();
         let tx = create_mock_transaction();
         let encoded = tx.rlp_encode();
         let mut raw_tx = vec![0x01]; // Wrong transaction type
@@ -469,7 +2142,9 @@ mod tests {
         
         let result = parser.parse(&raw_tx);
         assert!(result.is_err());
-        assert!(result.unwrap_err().to_string().contains("Invalid transaction type"));
+        let err = result.unwrap_err();
+        assert!(err.to_string().contains("Invalid transaction type"));
+        assert_eq!(err, TxError::InvalidType { got: 0x01 });
     }
 
     #[test]
@@ -486,9 +2161,326 @@ mod tests {
         assert!(result.gas_used > 0);
     }
 
+    #[test]
+    fn test_receipt_carries_l1_fields() {
+        let processor = Tx7eProcessor::new();
+        let tx = create_mock_transaction();
+        let raw_tx = tx.rlp_encode_typed();
+
+        let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        let receipt = result.receipt.unwrap();
+        assert!(receipt.status);
+        assert_eq!(receipt.transaction_hash, tx.hash());
+        assert_eq!(receipt.gas_used, result.gas_used);
+        assert_eq!(receipt.cumulative_gas_used, result.gas_used);
+        assert_eq!(receipt.effective_gas_price, U256::from_u64(processor.parser().l2_base_fee()));
+        assert_eq!(receipt.l1_fee, tx.l1_fee);
+        // The receipt reports the L1 gas the processor charged, not the declared one
+        assert_eq!(receipt.gas_used_for_l1, result.l1_gas_used);
+        assert_ne!(receipt.gas_used_for_l1, tx.l1_gas_used);
+        assert_eq!(receipt.l1_block_number, tx.l1_block_number);
+
+        let json = receipt.to_rpc_json();
+        assert_eq!(json["status"], "0x1");
+        assert_eq!(json["gasUsedForL1"], format!("{:#x}", result.l1_gas_used));
+        assert_eq!(json["l1BlockNumber"], format!("{:#x}", tx.l1_block_number));
+        assert_eq!(json["l1Fee"], tx.l1_fee.to_hex_quantity());
+
+        // The deposit is logged with its source hash, target and value
+        assert_eq!(receipt.logs, vec![deposit_log(&tx)]);
+        let log = &receipt.logs[0];
+        assert_eq!(log.topics[0], <[u8; 32]>::from(Keccak256::digest(DEPOSIT_FINALIZED_EVENT.as_bytes())));
+        assert_eq!(log.topics[1], tx.source_hash);
+        assert_eq!(&log.topics[2][12..], tx.target.as_bytes());
+        assert_eq!(log.data, tx.value.to_big_endian().to_vec());
+
+        // The consensus encoding carries only the four standard fields
+        let encoded = receipt.rlp_encode();
+        assert_eq!(encoded[0], TX_TYPE_0X7E);
+        let rlp = Rlp::new(&encoded[1..]);
+        assert_eq!(rlp.item_count().unwrap(), 4);
+        assert_eq!(rlp.val_at::<u8>(0).unwrap(), 1);
+        assert_eq!(rlp.val_at::<u64>(1).unwrap(), result.gas_used);
+        assert_eq!(rlp.at(3).unwrap().item_count().unwrap(), 1);
+
+        // Failed transactions produce no receipt
+        let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert!(result.receipt.is_none());
+    }
+
+    #[test]
+    fn test_receipt_logs_bloom() {
+        let log = PrecompileLog {
+            address: Address::from_hex("0x00000000000000000000000000000000000000ff").unwrap(),
+            topics: vec![[7u8; 32]],
+            data: vec![1, 2, 3],
+        };
+        let receipt = create_mock_transaction().encode_for_receipt(false, 21000, 0, U256::zero(), vec![log.clone()]);
+        let bloom = receipt.logs_bloom();
+
+        // Each of the address and topic sets at most three bits
+        let bits: u32 = bloom.iter().map(|byte| byte.count_ones()).sum();
+        assert!((1..=6).contains(&bits));
+        let hash = Keccak256::digest(log.address.as_bytes());
+        let bit = (u16::from_be_bytes([hash[0], hash[1]]) & 0x7ff) as usize;
+        assert_ne!(bloom[255 - bit / 8] & (1 << (bit % 8)), 0);
+        assert_eq!(receipt.to_rpc_json()["status"], "0x0");
+        assert_eq!(receipt.to_rpc_json()["logs"][0]["data"], "0x010203");
+    }
+
+    #[test]
+    fn test_gas_usage_zero_and_non_zero_bytes() {
+        let processor = Tx7eProcessor::new();
+        let mut tx = create_mock_transaction();
+        tx.value = U256::zero();
+        tx.data = vec![0x00, 0x00, 0x01, 0x02, 0x00, 0xff, 0x00];
+
+        // 4 zero bytes * 4 + 3 non-zero bytes * 16
+        assert_eq!(processor.calculate_gas_usage(&tx), 21000 + 4 * 4 + 3 * 16);
+    }
+
+    #[test]
+    fn test_gas_used_splits_into_l1_and_l2() {
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.l1_calldata_cost = 20;
+        let processor = Tx7eProcessor::new().with_config(config.clone());
+        let mut tx = create_mock_transaction();
+        tx.data = vec![0xab; 100];
+
+        let raw_tx = tx.rlp_encode_typed();
+        let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert!(result.success, "{}", result.error);
+        assert_eq!(result.gas_used, tx.intrinsic_gas());
+        assert_eq!(result.l1_gas_used, config.calculate_l1_gas_cost(&tx.data));
+        assert_eq!(result.l1_gas_used, 100 * 20);
+        assert_eq!(result.l1_gas_used + result.l2_gas_used, result.gas_used);
+
+        // A gas limit below the L1 share is rejected rather than clamped
+        tx.source_hash = [9u8; 32];
+        tx.gas_limit = 1_000;
+        let result = futures::executor::block_on(processor.process_transaction(&tx.rlp_encode_typed()));
+        assert!(!result.success);
+        assert_eq!(result.error, "L1 gas 2000 exceeds the 1000 gas the transaction uses");
+        assert!(!processor.seen_source_hashes().contains(&tx.source_hash));
+    }
+
+    #[test]
+    fn test_debug_formats_source_hash_as_hex() {
+        let tx = create_mock_transaction();
+        let source_hash = format!("0x{}", hex::encode(tx.source_hash));
+        assert_eq!(source_hash.len(), 66);
+
+        let debug = format!("{:?}", tx);
+        assert!(debug.contains(&format!("source_hash: {}", source_hash)));
+        assert!(debug.contains("data: 0x602b57fd"));
+
+        let hash = HexBytes(tx.hash()).to_string();
+        assert_eq!(hash.len(), 66);
+        assert_eq!(hash, format!("0x{}", hex::encode(tx.hash())));
+    }
+
+    #[test]
+    fn test_hash_covers_typed_raw_transaction() {
+        let tx = create_mock_transaction();
+        let expected: [u8; 32] = Keccak256::digest(tx.rlp_encode_typed()).into();
+        assert_eq!(tx.hash(), expected);
+        assert_ne!(tx.hash(), <[u8; 32]>::from(Keccak256::digest(tx.rlp_encode())));
+    }
+
+    #[test]
+    fn test_intrinsic_gas() {
+        // Plain transfer with no calldata
+        let mut tx = create_mock_transaction();
+        tx.data = Vec::new();
+        assert_eq!(tx.intrinsic_gas(), TX_BASE_GAS);
+        // Transferred value is not part of intrinsic gas
+        assert_ne!(tx.value, U256::zero());
+        tx.value = U256::zero();
+        assert_eq!(tx.intrinsic_gas(), TX_BASE_GAS);
+
+        // Data-heavy call: 1 KiB of non-zero bytes and 1 KiB of zeros, not capped by gas_limit
+        tx.data = [vec![0xab; 1024], vec![0x00; 1024]].concat();
+        tx.gas_limit = 21000;
+        assert_eq!(tx.intrinsic_gas(), 21000 + 1024 * 16 + 1024 * 4);
+    }
+
+    #[test]
+    fn test_processor_assigns_sequential_nonces() {
+        let processor = Tx7eProcessor::new();
+        let first = create_mock_transaction();
+        let mut second = create_mock_transaction();
+        second.source_hash = [2u8; 32];
+
+        let mut nonces = Vec::new();
+        for tx in [first, second] {
+            let raw_tx = tx.rlp_encode_typed();
+            let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+            nonces.push(result.request.unwrap().nonce);
+        }
+
+        assert_eq!(nonces, vec![Some(0), Some(1)]);
+    }
+
+    #[test]
+    fn test_processed_transactions_raise_congestion_fee() {
+        use crate::precompiles::{selector, ArbGasInfoHandler, PrecompileHandler};
+
+        // A seeded chain moves one second per block, regaining 1_000 gas each time
+        let gas_pool = GasPool::new(100_000, 1_000);
+        let processor = Tx7eProcessor::new()
+            .with_gas_pool(gas_pool.clone())
+            .with_mock_chain(MockChain::from_seed(1));
+        let arb_gas_info = ArbGasInfoHandler::with_gas_pool(gas_pool);
+        let config = ArbitrumConfig::default();
+        let congestion_fee = || {
+            let output = arb_gas_info.handle_call(&selector("getPricesInWei()"), &config).unwrap();
+            U256::from_big_endian(&output[4 * 32..5 * 32])
+        };
+        assert_eq!(congestion_fee(), U256::zero());
+
+        let mut fees = Vec::new();
+        for i in 0..3u8 {
+            let mut tx = create_mock_transaction();
+            tx.source_hash = [i + 1; 32];
+            let raw_tx = tx.rlp_encode_typed();
+            assert!(futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+            fees.push(congestion_fee());
+        }
+        assert!(fees[0] > U256::zero());
+        assert!(fees[1] > fees[0]);
+        assert!(fees[2] > fees[1]);
+        assert_eq!(
+            processor.gas_pool().available(),
+            100_000 - 3 * create_mock_transaction().intrinsic_gas() + 2 * 1_000
+        );
+    }
+
+    #[test]
+    fn test_congestion_fee_decays_as_blocks_pass() {
+        let gas_pool = GasPool::new(100_000, 10_000);
+        let chain = MockChain::from_seed(1);
+        let processor = Tx7eProcessor::new()
+            .with_gas_pool(gas_pool.clone())
+            .with_mock_chain(chain.clone());
+        let gas = GasPriceComponents::default();
+        let process = |i: u8| {
+            let mut tx = create_mock_transaction();
+            tx.source_hash = [i + 1; 32];
+            assert!(futures::executor::block_on(processor.process_transaction(&tx.rlp_encode_typed())).success);
+            gas_pool.congestion_fee(&gas)
+        };
+
+        let fees: Vec<u64> = (0..3).map(process).collect();
+        assert!(fees[2] > fees[1] && fees[1] > fees[0]);
+
+        // Ten empty blocks refill the pool before the next deposit drains it again
+        for _ in 0..10 {
+            chain.advance();
+        }
+        assert_eq!(process(3), fees[0]);
+        assert!(fees[0] > gas.congestion_fee);
+    }
+
+    #[test]
+    fn test_processor_rejects_replayed_deposit() {
+        let raw_tx = create_mock_transaction().rlp_encode_typed();
+
+        let seen = SeenSourceHashes::new();
+        let processor = Tx7eProcessor::new().with_seen_source_hashes(seen.clone());
+        assert!(futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+
+        let replay = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert!(!replay.success);
+        assert!(replay.error.contains("already processed"));
+        assert_eq!(processor.mock_chain().block_number(), 2);
+
+        // A processor sharing the set rejects the replay too
+        let other = Tx7eProcessor::new().with_seen_source_hashes(seen.clone());
+        assert!(!futures::executor::block_on(other.process_transaction(&raw_tx)).success);
+        assert!(seen.contains(&create_mock_transaction().source_hash));
+        assert_eq!(seen.len(), 1);
+    }
+
+    #[test]
+    fn test_processor_uses_injected_nonce_source() {
+        struct FixedNonce;
+        impl NonceSource for FixedNonce {
+            fn next_nonce(&self, _addr: &Address) -> u64 {
+                42
+            }
+        }
+
+        let processor = Tx7eProcessor::new_with_nonces(Box::new(FixedNonce));
+        let raw_tx = create_mock_transaction().rlp_encode_typed();
+        let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert_eq!(result.request.unwrap().nonce, Some(42));
+    }
+
+    #[test]
+    fn test_deposit_credits_seeded_balance() {
+        use crate::state::StateBackend;
+
+        let tx = create_mock_transaction();
+        let state = InMemoryState::new();
+        state.set_balance(&tx.target, U256::from_u64(5));
+        let processor = Tx7eProcessor::new().with_state_backend(state.clone().shared());
+
+        let raw_tx = tx.rlp_encode_typed();
+        let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert!(result.success, "{}", result.error);
+        assert_eq!(state.balance(&tx.target), U256::from_u64(1_000_000_000_000_000_005));
+        assert_eq!(state.balance(&tx.refund_address), U256::zero());
+
+        // The request nonce came from the same state
+        assert_eq!(result.request.unwrap().nonce, Some(0));
+        assert_eq!(state.nonce(&tx.target), 1);
+
+        // Rejected deposits credit nothing
+        futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert_eq!(state.balance(&tx.target), U256::from_u64(1_000_000_000_000_000_005));
+    }
+
+    #[test]
+    fn test_deposit_failing_mid_apply_leaves_state_unchanged() {
+        use crate::state::StateBackend;
+
+        let mut tx = create_mock_transaction();
+        tx.l1_fee = U256::from_u64(1_000);
+        let state = InMemoryState::new();
+        state.set_balance(&tx.target, U256::from_u64(5));
+        // The value is credited before the nonce is consumed, so the exhausted nonce fails the deposit after the credit
+        state.set_nonce(&tx.target, u64::MAX);
+        let processor = Tx7eProcessor::new().with_state_backend(state.clone().shared());
+        let start_block = processor.mock_chain().block_number();
+
+        let raw_tx = tx.rlp_encode_typed();
+        let result = futures::executor::block_on(processor.process_transaction(&raw_tx));
+        assert!(!result.success);
+        assert_eq!(
+            result.error,
+            format!("Execution failed: Nonce of {} is exhausted", tx.target)
+        );
+        assert!(result.receipt.is_none());
+
+        // Every effect was rolled back or never applied
+        assert_eq!(state.balance(&tx.target), U256::from_u64(5));
+        assert_eq!(state.nonce(&tx.target), u64::MAX);
+        assert_eq!(processor.l1_fee_pool().available(), U256::zero());
+        assert_eq!(processor.gas_pool().available(), processor.gas_pool().max());
+        assert_eq!(processor.storage_gas().available(), StorageGas::default().available());
+        assert_eq!(processor.mock_chain().block_number(), start_block);
+        assert!(processor.seen_source_hashes().is_empty());
+
+        // The same deposit applies once the account can take it
+        state.set_nonce(&tx.target, 0);
+        assert!(futures::executor::block_on(processor.process_transaction(&raw_tx)).success);
+        assert_eq!(state.balance(&tx.target), U256::from_u64(1_000_000_000_000_000_005));
+        assert_eq!(processor.l1_fee_pool().available(), U256::from_u64(1_000));
+    }
+
     #[test]
     fn test_source_hash_generation() {
-        let parser = Tx7eParser;
+        let parser = Tx7eParser::new();
         let l1_tx_hash = [1u8; 32];
         let l1_block_number = 12345;
         let l1_log_index = 0;
@@ -498,14 +2490,195 @@ mod tests {
         assert_ne!(source_hash, [0u8; 32]);
     }
 
+    #[test]
+    fn test_verify_source_hash() {
+        let parser = Tx7eParser::new();
+        let l1_tx_hash = [0xab; 32];
+        let mut tx = create_mock_transaction();
+        tx.source_hash = parser.generate_source_hash(&l1_tx_hash, 12345, 2);
+
+        assert!(parser.verify_source_hash(&tx, &l1_tx_hash, 12345, 2));
+        assert!(parser.validate_transaction_with_provenance(&tx, &l1_tx_hash, 12345, 2).is_valid);
+
+        assert!(!parser.verify_source_hash(&tx, &l1_tx_hash, 12345, 3));
+        assert!(!parser.verify_source_hash(&tx, &l1_tx_hash, 12346, 2));
+        assert!(!parser.verify_source_hash(&tx, &[0xac; 32], 12345, 2));
+        let validation = parser.validate_transaction_with_provenance(&tx, &l1_tx_hash, 12345, 3);
+        assert_eq!(validation.errors, vec![TxValidationError::SourceHashMismatch]);
+    }
+
+    #[test]
+    fn test_request_id_stable_across_round_trip() {
+        let tx = create_mock_transaction();
+        let raw_tx = tx.rlp_encode_typed();
+        let decoded = Tx7eParser::new().parse(&raw_tx).unwrap();
+        assert_eq!(decoded.request_id(), tx.request_id());
+        assert_ne!(tx.request_id(), tx.source_hash);
+
+        // The same L1 message on another chain gets a different id
+        let mut other_chain = tx.clone();
+        other_chain.chain_id = 421614;
+        assert_ne!(other_chain.request_id(), tx.request_id());
+    }
+
+    #[test]
+    fn test_same_deposit_ignores_derived_fields() {
+        let tx = create_mock_transaction();
+        let mut repriced = tx.clone();
+        repriced.gas_limit += 1;
+        repriced.l1_gas_price = U256::from_u64(30_000_000_000);
+        repriced.l1_fee = U256::zero();
+        repriced.refund_address = tx.target;
+        assert!(tx.same_deposit(&repriced));
+        assert_ne!(tx, repriced);
+
+        let mut other_value = tx.clone();
+        other_value.value = U256::from_u64(1);
+        assert!(!tx.same_deposit(&other_value));
+
+        let mut other_message = tx.clone();
+        other_message.source_hash = [2u8; 32];
+        assert!(!tx.same_deposit(&other_message));
+    }
+
+    #[test]
+    fn test_rpc_json_shape() {
+        let tx = create_mock_transaction();
+        let json = tx.to_rpc_json();
+
+        assert_eq!(json["type"], "0x7e");
+        assert_eq!(json["chainId"], "0xa4b1");
+        assert_eq!(json["to"], "0x1234567890123456789012345678901234567890");
+        assert_eq!(json["value"], "0xde0b6b3a7640000");
+        assert_eq!(json["input"], "0x602b57fd");
+        assert_eq!(json["gas"], "0x186a0");
+        assert_eq!(json["l1BlockNumber"], "0x3039");
+        assert_eq!(json["requestId"], format!("0x{}", hex::encode(tx.request_id())));
+        assert_eq!(json["sourceHash"], format!("0x{}", "01".repeat(32)));
+        assert_eq!(json["hash"], format!("0x{}", hex::encode(tx.hash())));
+
+        // Zero quantities are encoded as "0x0", not "0x" or zero-padded
+        let mut zero_value = tx.clone();
+        zero_value.value = U256::zero();
+        assert_eq!(zero_value.to_rpc_json()["value"], "0x0");
+    }
+
+    #[test]
+    fn test_parse_envelope_dispatches_on_leading_byte() {
+        let parser = Tx7eParser::new();
+        let tx = create_mock_transaction();
+        let raw_deposit = tx.rlp_encode_typed();
+        assert_eq!(parser.parse_envelope(&raw_deposit).unwrap(), TxEnvelope::Deposit(tx));
+
+        let retryable = create_mock_retryable();
+        assert_eq!(
+            parser.parse_envelope(&retryable.rlp_encode_typed()).unwrap(),
+            TxEnvelope::SubmitRetryable(retryable)
+        );
+
+        for (raw, tx_type) in [
+            (vec![0xf8, 0x6b, 0x80], TxType::Legacy),
+            (vec![0xc0], TxType::Legacy),
+            (vec![0x01, 0xf8, 0x6b], TxType::AccessList),
+            (vec![0x02, 0xf8, 0x6b], TxType::DynamicFee),
+            (vec![0x03, 0xf8, 0x6b], TxType::Other(0x03)),
+        ] {
+            assert_eq!(parser.parse_envelope(&raw).unwrap(), TxEnvelope::Other { tx_type, raw: raw.clone() });
+        }
+
+        assert_eq!(parser.parse_envelope(&[]), Err(TxError::Empty));
+        assert_eq!(parser.parse_envelope(&[0x80, 0x01]), Err(TxError::InvalidEnvelope { first: 0x80 }));
+        assert!(matches!(parser.parse_envelope(&[TX_TYPE_0X7E, 0x01]), Err(TxError::Rlp(_))));
+    }
+
+    #[test]
+    fn test_rpc_json_round_trip() {
+        let tx = create_mock_transaction();
+        assert_eq!(Tx7eTransaction::from_rpc_json(&tx.to_rpc_json()).unwrap(), tx);
+
+        let mut zero_value = tx.clone();
+        zero_value.value = U256::zero();
+        zero_value.data = Vec::new();
+        assert_eq!(Tx7eTransaction::from_rpc_json(&zero_value.to_rpc_json()).unwrap(), zero_value);
+
+        let mut json = tx.to_rpc_json();
+        json.as_object_mut().unwrap().remove("l1BaseFee");
+        assert_eq!(
+            Tx7eTransaction::from_rpc_json(&json),
+            Err(TxError::MissingJsonField { field: "l1BaseFee" })
+        );
+
+        let mut json = tx.to_rpc_json();
+        json["type"] = json!("0x2");
+        assert_eq!(Tx7eTransaction::from_rpc_json(&json), Err(TxError::InvalidType { got: 0x02 }));
+
+        let mut json = tx.to_rpc_json();
+        json["sourceHash"] = json!("0x01");
+        let err = Tx7eTransaction::from_rpc_json(&json).unwrap_err();
+        assert_eq!(err.to_string(), "Invalid field 'sourceHash' in transaction JSON: expected 32 bytes, got 1");
+    }
+
     #[test]
     fn test_transaction_request_conversion() {
-        let parser = Tx7eParser;
+        let parser = Tx7eParser::new();
         let tx = create_mock_transaction();
         let request = parser.to_transaction_request(&tx);
         
         assert_eq!(request.to, Some(tx.target));
         assert_eq!(request.value, Some(tx.value));
         assert_eq!(request.chain_id, Some(tx.chain_id));
+        assert_eq!(request.access_list, None);
+    }
+
+    #[test]
+    fn test_transaction_request_access_list_gas() {
+        let tx = create_mock_transaction();
+        let mut request = Tx7eParser::new().to_transaction_request(&tx);
+        let base = request.intrinsic_gas();
+        assert_eq!(base, tx.intrinsic_gas());
+
+        // Two addresses with three storage keys between them
+        let token = Address::from_hex("0x1111111111111111111111111111111111111111").unwrap();
+        request.access_list = Some(vec![
+            (token, vec![U256::zero(), U256::from_u64(1)]),
+            (tx.target, vec![U256::from_u64(0xff)]),
+        ]);
+        assert_eq!(
+            request.intrinsic_gas(),
+            base + 2 * TX_ACCESS_LIST_ADDRESS_GAS + 3 * TX_ACCESS_LIST_STORAGE_KEY_GAS
+        );
+        assert_eq!(request.intrinsic_gas(), base + 10_500);
+
+        let json = request.to_rpc_json();
+        assert_eq!(json["accessList"].as_array().unwrap().len(), 2);
+        assert_eq!(json["accessList"][0]["storageKeys"][1], format!("0x{}01", "0".repeat(62)));
+        assert_eq!(json["accessList"][1]["storageKeys"][0], format!("0x{}ff", "0".repeat(62)));
+        assert_eq!(json["chainId"], "0xa4b1");
+        assert!(json.get("nonce").is_none());
+
+        // An empty list costs nothing but is still serialized
+        request.access_list = Some(Vec::new());
+        assert_eq!(request.intrinsic_gas(), base);
+        assert_eq!(request.to_rpc_json()["accessList"], json!([]));
+    }
+
+    #[test]
+    fn test_transaction_request_eip1559_fees() {
+        let mut config = ArbitrumConfig::default();
+        config.gas_price_components.l2_base_fee = 50_000_000_000;
+        let parser = Tx7eParser::from_config(&config);
+        assert_eq!(parser.l2_base_fee(), 50_000_000_000);
+
+        // The base fee is above the transaction's effective price, so it sets the cap
+        let tx = create_mock_transaction();
+        let request = parser.to_transaction_request(&tx);
+        assert_eq!(request.gas_price, Some(tx.effective_gas_price()));
+        assert_eq!(request.max_fee_per_gas, Some(U256::from_u64(50_000_000_000)));
+        assert_eq!(request.max_priority_fee_per_gas, Some(U256::zero()));
+
+        // Otherwise the effective price does
+        let request = parser.with_l2_base_fee(1).to_transaction_request(&tx);
+        assert_eq!(request.max_fee_per_gas, Some(tx.effective_gas_price()));
+        assert!(request.max_fee_per_gas.unwrap() >= U256::from_u64(1));
     }
 }
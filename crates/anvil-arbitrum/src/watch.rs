@@ -0,0 +1,125 @@
+//! Hot-reloading the Arbitrum configuration when its file changes
+
+use crate::arbitrum::ArbitrumConfig;
+use crate::rpc::RpcState;
+use anyhow::Result;
+use std::path::PathBuf;
+use std::sync::Arc;
+use std::time::Duration;
+use tokio::task::JoinHandle;
+use tracing::{info, warn};
+
+/// Default interval between checks of the watched file
+pub const DEFAULT_POLL_INTERVAL: Duration = Duration::from_millis(500);
+
+/// Polls a config file and installs each valid new version into an `RpcState`
+///
+/// A change is detected by comparing the file's contents between polls. The
+/// new configuration comes from `load`, which is expected to re-read the file
+/// and validate the result; if it fails, the previous configuration is kept.
+pub struct ConfigWatcher<F> {
+    path: PathBuf,
+    load: F,
+    poll_interval: Duration,
+}
+
+impl<F> ConfigWatcher<F>
+where
+    F: Fn() -> Result<ArbitrumConfig> + Send + 'static,
+{
+    /// Watch `path`, building the configuration with `load` whenever it changes
+    pub fn new(path: impl Into<PathBuf>, load: F) -> Self {
+        Self {
+            path: path.into(),
+            load,
+            poll_interval: DEFAULT_POLL_INTERVAL,
+        }
+    }
+
+    /// Check the file at the given interval instead of the default
+    pub fn with_poll_interval(mut self, poll_interval: Duration) -> Self {
+        self.poll_interval = poll_interval;
+        self
+    }
+
+    /// Spawn the polling task, swapping reloaded configurations into `state`
+    ///
+    /// RPC methods and precompile calls see the new configuration on their
+    /// next request. The task runs until it is aborted.
+    pub fn spawn(self, state: Arc<RpcState>) -> JoinHandle<()> {
+        // Read the baseline before spawning so edits made right after this call are seen
+        let mut last = std::fs::read(&self.path).ok();
+        tokio::spawn(async move {
+            let mut interval = tokio::time::interval(self.poll_interval);
+            loop {
+                interval.tick().await;
+                let current = tokio::fs::read(&self.path).await.ok();
+                if current == last {
+                    continue;
+                }
+                last = current;
+
+                match (self.load)() {
+                    Ok(config) => {
+                        info!("Reloaded configuration from {}", self.path.display());
+                        state.set_config(config);
+                    }
+                    Err(e) => warn!(
+                        "Ignoring invalid configuration in {}, keeping the previous one: {}",
+                        self.path.display(),
+                        e
+                    ),
+                }
+            }
+        })
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    async fn wait_for_l1_base_fee(state: &RpcState, l1_base_fee: u64) -> bool {
+        for _ in 0..200 {
+            if state.config().l1_base_fee == l1_base_fee {
+                return true;
+            }
+            tokio::time::sleep(Duration::from_millis(10)).await;
+        }
+        false
+    }
+
+    #[tokio::test]
+    async fn test_modified_file_updates_l1_base_fee() {
+        let dir = tempfile::tempdir().unwrap();
+        let path = dir.path().join("config.json");
+        let mut config = ArbitrumConfig::default();
+        std::fs::write(&path, config.to_json_pretty().unwrap()).unwrap();
+
+        let state = Arc::new(RpcState::new(config.clone()));
+        let load_path = path.to_str().unwrap().to_string();
+        let watcher = ConfigWatcher::new(&path, move || {
+            let config = ArbitrumConfig::from_path(&load_path)?;
+            config.validate()?;
+            Ok(config)
+        })
+        .with_poll_interval(Duration::from_millis(10))
+        .spawn(state.clone());
+
+        config.l1_base_fee = 15_000_000_000;
+        std::fs::write(&path, config.to_json_pretty().unwrap()).unwrap();
+        assert!(wait_for_l1_base_fee(&state, 15_000_000_000).await);
+
+        // An invalid file is ignored, and a later valid one is still picked up
+        config.l1_base_fee = 0;
+        std::fs::write(&path, config.to_json_pretty().unwrap()).unwrap();
+        tokio::time::sleep(Duration::from_millis(100)).await;
+        assert_eq!(state.config().l1_base_fee, 15_000_000_000);
+
+        config.l1_base_fee = 30_000_000_000;
+        std::fs::write(&path, config.to_json_pretty().unwrap()).unwrap();
+        assert!(wait_for_l1_base_fee(&state, 30_000_000_000).await);
+
+        watcher.abort();
+    }
+}
@@ -0,0 +1,206 @@
+//! End-to-end flow of a 0x7e deposit: build, encode, parse, validate, process and
+//! read back the receipt, alongside the precompiles sharing the processor's state
+
+#![cfg(feature = "std")]
+
+use anvil_arbitrum::arbitrum::ArbitrumConfig;
+use anvil_arbitrum::gas_pool::{GasPool, StorageGas, STORAGE_WRITE_GAS};
+use anvil_arbitrum::mock_chain::MockChain;
+use anvil_arbitrum::precompiles::{
+    selectors, Address, ArbSysHandler, CallContext, PrecompileRegistry, WithdrawalStore, U256,
+};
+#[cfg(feature = "alloy")]
+use anvil_arbitrum::primitives::{AddressExt, U256Ext};
+use anvil_arbitrum::state::{InMemoryState, StateBackend};
+use anvil_arbitrum::tx7e::{Tx7eParser, Tx7eProcessor, Tx7eTransaction};
+
+const ARB_SYS: &str = "0x0000000000000000000000000000000000000064";
+const TARGET: &str = "0x1234567890123456789012345678901234567890";
+
+/// Processor and registry wired to the same gas pool, storage gas, chain and state, as `main` does
+struct Harness {
+    config: ArbitrumConfig,
+    processor: Tx7eProcessor,
+    registry: PrecompileRegistry,
+    withdrawals: WithdrawalStore,
+    state: InMemoryState,
+}
+
+impl Harness {
+    fn new() -> Self {
+        let config = ArbitrumConfig::default();
+        let gas_pool = GasPool::default();
+        let chain = MockChain::default();
+        let storage_gas = StorageGas::new(config.storage_gas_available);
+        let withdrawals = WithdrawalStore::new();
+        let state = InMemoryState::new();
+
+        let mut registry = PrecompileRegistry::new();
+        registry.register(Box::new(
+            ArbSysHandler::with_withdrawals(withdrawals.clone())
+                .with_mock_chain(chain.clone())
+                .with_storage_gas(storage_gas.clone())
+                .with_state_backend(state.clone().shared()),
+        ));
+        let processor = Tx7eProcessor::new()
+            .with_parser(Tx7eParser::from_config(&config))
+            .with_config(config.clone())
+            .with_gas_pool(gas_pool)
+            .with_storage_gas(storage_gas)
+            .with_state_backend(state.clone().shared())
+            .with_mock_chain(chain);
+
+        Self {
+            config,
+            processor,
+            registry,
+            withdrawals,
+            state,
+        }
+    }
+
+    fn call_arb_sys(&self, input: &[u8], context: &CallContext) -> U256 {
+        let arb_sys = Address::from_hex(ARB_SYS).unwrap();
+        let output = self
+            .registry
+            .handle_call_with_context(arb_sys, input, context, &self.config)
+            .unwrap();
+        U256::from_big_endian(&output)
+    }
+}
+
+fn deposit() -> Tx7eTransaction {
+    Tx7eTransaction::builder()
+        .with_chain_id(42161)
+        .with_target(Address::from_hex(TARGET).unwrap())
+        .with_value(U256::from_u64(1_000_000_000_000_000_000))
+        .with_data(vec![0x60, 0x2b, 0x57, 0xfd])
+        .with_l1_block_number(12345)
+        .with_l1_timestamp(1640995200)
+        .with_l1_gas_used(50_000)
+        .with_l1_fee(U256::from_u64(1_000_000_000_000_000))
+        .with_source_hash([1u8; 32])
+        .build()
+        .unwrap()
+}
+
+#[tokio::test]
+async fn test_deposit_flow_produces_receipt_and_l2_to_l1_message() {
+    let harness = Harness::new();
+    let start_block = harness.processor.mock_chain().block_number();
+    let tx = deposit();
+    let raw_tx = tx.rlp_encode_typed();
+
+    // The encoded transaction parses back to itself and validates
+    let parser = Tx7eParser::from_config(&harness.config);
+    let parsed = parser.parse(&raw_tx).unwrap();
+    assert_eq!(parsed, tx);
+    assert!(parser.validate_transaction(&parsed).is_valid);
+
+    let result = harness.processor.process_transaction(&raw_tx).await;
+    assert!(result.success, "{}", result.error);
+    assert_eq!(result.transaction.as_ref(), Some(&tx));
+    assert_eq!(result.gas_used, tx.intrinsic_gas());
+    assert_eq!(result.l1_cost, tx.l1_fee);
+    assert_eq!(harness.processor.gas_pool().available(), harness.processor.gas_pool().max() - result.gas_used);
+
+    let receipt = result.receipt.unwrap();
+    assert!(receipt.status);
+    assert_eq!(receipt.transaction_hash, tx.hash());
+    assert_eq!(receipt.gas_used, result.gas_used);
+    // The L1 gas is what the processor charged for the calldata, not the declared 50_000
+    assert_eq!(receipt.gas_used_for_l1, result.l1_gas_used);
+    assert_eq!(receipt.gas_used_for_l1, harness.config.calculate_l1_gas_cost(&tx.data));
+    assert_eq!(receipt.l1_block_number, 12345);
+    assert_eq!(receipt.l1_fee, tx.l1_fee);
+    assert_eq!(receipt.logs.len(), 1);
+    assert_eq!(receipt.logs[0].topics[1], tx.source_hash);
+    let json = receipt.to_rpc_json();
+    assert_eq!(json["transactionHash"], format!("0x{}", hex::encode(tx.hash())));
+    assert_eq!(json["gasUsedForL1"], format!("{:#x}", result.l1_gas_used));
+
+    // The deposit mined a block that ArbSys reports
+    let block_number = harness.call_arb_sys(&hex::decode(selectors::ARB_BLOCK_NUMBER).unwrap(), &CallContext::default());
+    assert_eq!(block_number, U256::from_u64(start_block + 1));
+
+    // The deposit credited its target in the shared state
+    let (target, value) = tx.credit_target();
+    assert_eq!(harness.state.balance(&target), value);
+
+    // Withdrawing from the credited target records an L2-to-L1 message and debits it
+    let mut input = hex::decode(selectors::WITHDRAW_ETH).unwrap();
+    input.extend_from_slice(&[0u8; 12]);
+    input.extend_from_slice(target.as_bytes());
+    let context = CallContext {
+        caller: target,
+        value,
+        ..CallContext::default()
+    };
+    assert_eq!(harness.call_arb_sys(&input, &context), U256::from_u64(0));
+
+    let message = harness.withdrawals.get(0).unwrap();
+    assert_eq!(message.caller, target);
+    assert_eq!(message.destination, target);
+    assert_eq!(message.value, value);
+    assert_eq!(harness.withdrawals.outbox().root(), message.leaf_hash());
+    assert_eq!(harness.state.balance(&target), U256::from_u64(0));
+
+    // The deposit and the message each consumed one storage write
+    let storage_gas = harness.call_arb_sys(
+        &hex::decode(selectors::GET_STORAGE_GAS_AVAILABLE).unwrap(),
+        &CallContext::default(),
+    );
+    assert_eq!(
+        storage_gas,
+        U256::from_u64(harness.config.storage_gas_available - 2 * STORAGE_WRITE_GAS)
+    );
+}
+
+#[tokio::test]
+async fn test_invalid_type_is_rejected_before_processing() {
+    let harness = Harness::new();
+    let start_block = harness.processor.mock_chain().block_number();
+    let mut raw_tx = deposit().rlp_encode_typed();
+    raw_tx[0] = 0x02;
+
+    let result = harness.processor.process_transaction(&raw_tx).await;
+    assert!(!result.success);
+    assert_eq!(result.error, "Parsing failed: Invalid transaction type: expected 0x7e, got 0x02");
+    assert!(result.transaction.is_none());
+    assert!(result.receipt.is_none());
+    assert_eq!(result.gas_used, 0);
+
+    // Nothing was mined, drained or recorded
+    assert_eq!(harness.processor.mock_chain().block_number(), start_block);
+    assert_eq!(harness.processor.gas_pool().available(), harness.processor.gas_pool().max());
+    assert!(harness.processor.seen_source_hashes().is_empty());
+    assert_eq!(harness.processor.metrics().snapshot().transactions_failed, 1);
+}
+
+#[tokio::test]
+async fn test_failed_validation_is_rejected_before_processing() {
+    let harness = Harness::new();
+    let start_block = harness.processor.mock_chain().block_number();
+    let mut tx = deposit();
+    tx.target = Address::from_hex("0x0000000000000000000000000000000000000000").unwrap();
+    tx.l1_block_number = 0;
+    let raw_tx = tx.rlp_encode_typed();
+
+    // The transaction still parses; validation is what rejects it
+    assert!(Tx7eParser::from_config(&harness.config).parse(&raw_tx).is_ok());
+    let result = harness.processor.process_transaction(&raw_tx).await;
+    assert!(!result.success);
+    assert_eq!(
+        result.error,
+        "Validation failed: Invalid target address: cannot be zero address, Invalid L1 block number: cannot be zero"
+    );
+    assert!(result.receipt.is_none());
+
+    // Rejected deposits neither credit the target nor mark their source hash as seen,
+    // so a corrected one goes through
+    assert!(harness.state.is_empty());
+    assert!(harness.processor.seen_source_hashes().is_empty());
+    let result = harness.processor.process_transaction(&deposit().rlp_encode_typed()).await;
+    assert!(result.success, "{}", result.error);
+    assert_eq!(harness.processor.mock_chain().block_number(), start_block + 1);
+}